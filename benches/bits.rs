@@ -0,0 +1,95 @@
+use bytebuffer::{ByteBuffer, ByteReader};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+const FIELDS: usize = 1024;
+
+/// The previous implementation, recursing once per bit
+fn read_bits_recursive(reader: &mut ByteReader, n: u8) -> u64 {
+    if n == 0 {
+        0
+    } else {
+        (u64::from(reader.read_bit().unwrap()) << (n - 1)) | read_bits_recursive(reader, n - 1)
+    }
+}
+
+fn write_bits_recursive(buffer: &mut ByteBuffer, value: u64, n: u8) {
+    if n > 0 {
+        buffer.write_bit((value >> (n - 1)) & 1 != 0);
+        write_bits_recursive(buffer, value, n - 1);
+    }
+}
+
+fn bench_read_bits(c: &mut Criterion) {
+    let data = vec![0xA5; FIELDS * 8];
+    let mut group = c.benchmark_group("read_bits");
+    for n in [3u8, 13, 32, 64] {
+        group.throughput(Throughput::Bytes((FIELDS * n as usize / 8) as u64));
+        group.bench_with_input(BenchmarkId::new("recursive", n), &n, |b, &n| {
+            b.iter(|| {
+                let mut reader = ByteReader::from_bytes(&data);
+                for _ in 0..FIELDS {
+                    black_box(read_bits_recursive(&mut reader, n));
+                }
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("word", n), &n, |b, &n| {
+            b.iter(|| {
+                let mut reader = ByteReader::from_bytes(&data);
+                for _ in 0..FIELDS {
+                    black_box(reader.read_bits(n).unwrap());
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_write_bits(c: &mut Criterion) {
+    let mut group = c.benchmark_group("write_bits");
+    for n in [3u8, 13, 32, 64] {
+        group.throughput(Throughput::Bytes((FIELDS * n as usize / 8) as u64));
+        group.bench_with_input(BenchmarkId::new("recursive", n), &n, |b, &n| {
+            b.iter(|| {
+                let mut buffer = ByteBuffer::new();
+                for i in 0..FIELDS {
+                    write_bits_recursive(&mut buffer, black_box(i as u64), n);
+                }
+                buffer
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("word", n), &n, |b, &n| {
+            b.iter(|| {
+                let mut buffer = ByteBuffer::new();
+                for i in 0..FIELDS {
+                    buffer.write_bits(black_box(i as u64), n);
+                }
+                buffer
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_bits_u128(c: &mut Criterion) {
+    let data = vec![0xA5; FIELDS * 16];
+    c.bench_function("read_bits_u128/121", |b| {
+        b.iter(|| {
+            let mut reader = ByteReader::from_bytes(&data);
+            for _ in 0..FIELDS {
+                black_box(reader.read_bits_u128(121).unwrap());
+            }
+        })
+    });
+    c.bench_function("write_bits_u128/121", |b| {
+        b.iter(|| {
+            let mut buffer = ByteBuffer::new();
+            for i in 0..FIELDS {
+                buffer.write_bits_u128(black_box(i as u128), 121);
+            }
+            buffer
+        })
+    });
+}
+
+criterion_group!(benches, bench_read_bits, bench_write_bits, bench_bits_u128);
+criterion_main!(benches);
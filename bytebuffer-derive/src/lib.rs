@@ -0,0 +1,387 @@
+//! Derive macros for the `ByteEncode` and `ByteDecode` traits of `bytebuffer`.
+//!
+//! Use them through the `derive` feature of `bytebuffer` rather than directly. Fields are
+//! written in declaration order, and can be customized with `#[bytebuffer(..)]` attributes:
+//!
+//! - `endian = "big"` or `endian = "little"`: use this byte order for the field (or for the
+//!   whole type when set on the struct or enum)
+//! - `bits = n`: write the field with `write_bits` in `n` bits (see `BitField`)
+//! - `prefix = VarInt`: write a `String` or a `Vec<u8>` with this `LengthPrefix`
+//! - `skip`: do not write the field and decode it with `Default::default()`, or with
+//!   `default = expr` when given
+//!
+//! Enums are written as a tag followed by the fields of the variant. The tag is the
+//! discriminant of the variant, written as a `u32` unless `tag = u8` (or any other integer
+//! type) is set on the enum.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Literal, Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, parse_quote, spanned::Spanned, Attribute, Data, DeriveInput, Error, Expr,
+    Fields, Generics, Ident, LitInt, LitStr, Result, Type,
+};
+
+/// Derive `ByteEncode`, writing every field in declaration order.
+#[proc_macro_derive(ByteEncode, attributes(bytebuffer))]
+pub fn derive_encode(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_encode(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Derive `ByteDecode`, reading every field in declaration order.
+#[proc_macro_derive(ByteDecode, attributes(bytebuffer))]
+pub fn derive_decode(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_decode(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Options set on the struct or enum
+#[derive(Default)]
+struct ContainerAttrs {
+    endian: Option<TokenStream2>,
+    tag: Option<Type>,
+}
+
+/// Options set on a field
+#[derive(Default)]
+struct FieldAttrs {
+    endian: Option<TokenStream2>,
+    bits: Option<LitInt>,
+    prefix: Option<Expr>,
+    skip: bool,
+    default: Option<Expr>,
+}
+
+fn parse_endian(value: LitStr) -> Result<TokenStream2> {
+    match value.value().as_str() {
+        "big" => Ok(quote!(::bytebuffer::Endian::BigEndian)),
+        "little" => Ok(quote!(::bytebuffer::Endian::LittleEndian)),
+        _ => Err(Error::new(
+            value.span(),
+            "expected \"big\" or \"little\" as endian",
+        )),
+    }
+}
+
+impl ContainerAttrs {
+    fn parse(attrs: &[Attribute], is_enum: bool) -> Result<Self> {
+        let mut res = ContainerAttrs::default();
+        for attr in attrs.iter().filter(|a| a.path().is_ident("bytebuffer")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("endian") {
+                    res.endian = Some(parse_endian(meta.value()?.parse()?)?);
+                } else if meta.path.is_ident("tag") && is_enum {
+                    res.tag = Some(meta.value()?.parse()?);
+                } else {
+                    return Err(meta.error("unsupported bytebuffer attribute"));
+                }
+                Ok(())
+            })?;
+        }
+        Ok(res)
+    }
+}
+
+impl FieldAttrs {
+    fn parse(attrs: &[Attribute]) -> Result<Self> {
+        let mut res = FieldAttrs::default();
+        for attr in attrs.iter().filter(|a| a.path().is_ident("bytebuffer")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("endian") {
+                    res.endian = Some(parse_endian(meta.value()?.parse()?)?);
+                } else if meta.path.is_ident("bits") {
+                    res.bits = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("prefix") {
+                    res.prefix = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("skip") {
+                    res.skip = true;
+                } else if meta.path.is_ident("default") {
+                    res.default = Some(meta.value()?.parse()?);
+                } else {
+                    return Err(meta.error("unsupported bytebuffer attribute"));
+                }
+                Ok(())
+            })?;
+            if res.bits.is_some() && res.prefix.is_some() {
+                return Err(Error::new(
+                    attr.span(),
+                    "`bits` and `prefix` cannot be used together",
+                ));
+            }
+            if res.default.is_some() && !res.skip {
+                return Err(Error::new(attr.span(), "`default` requires `skip`"));
+            }
+        }
+        Ok(res)
+    }
+}
+
+/// Wrap a `Result` expression so that it runs with another byte order, restoring the
+/// previous one afterwards.
+fn with_endian(
+    endian: Option<&TokenStream2>,
+    io: &Ident,
+    trait_name: &TokenStream2,
+    expr: TokenStream2,
+) -> TokenStream2 {
+    match endian {
+        None => expr,
+        Some(endian) => quote! {{
+            let __endian = #trait_name::endian(#io);
+            #trait_name::set_endian(#io, #endian);
+            let __res = #expr;
+            #trait_name::set_endian(#io, __endian);
+            __res
+        }},
+    }
+}
+
+fn add_bounds(generics: &Generics, bound: TokenStream2) -> Generics {
+    let mut generics = generics.clone();
+    for param in generics.type_params_mut() {
+        param.bounds.push(parse_quote!(#bound));
+    }
+    generics
+}
+
+/// The discriminant of every variant, following the rules of the language when they are
+/// not explicit.
+fn discriminants<'a>(variants: impl Iterator<Item = &'a syn::Variant>) -> Vec<TokenStream2> {
+    let mut base: Option<&Expr> = None;
+    let mut offset = 0u128;
+    variants
+        .map(|variant| {
+            if let Some((_, expr)) = &variant.discriminant {
+                base = Some(expr);
+                offset = 0;
+            }
+            let lit = Literal::u128_unsuffixed(offset);
+            offset += 1;
+            match base {
+                Some(expr) if offset == 1 => quote!(#expr),
+                Some(expr) => quote!((#expr) + #lit),
+                None => quote!(#lit),
+            }
+        })
+        .collect()
+}
+
+fn field_names(fields: &Fields) -> Vec<Ident> {
+    (0..fields.len())
+        .map(|i| format_ident!("__field{}", i))
+        .collect()
+}
+
+/// Construct `path` from the bindings of `names`.
+fn construct(path: TokenStream2, fields: &Fields, names: &[Ident]) -> TokenStream2 {
+    match fields {
+        Fields::Named(named) => {
+            let idents = named.named.iter().map(|f| &f.ident);
+            quote!(#path { #(#idents: #names),* })
+        }
+        Fields::Unnamed(_) => quote!(#path ( #(#names),* )),
+        Fields::Unit => path,
+    }
+}
+
+fn encode_fields(fields: &Fields, names: &[Ident]) -> Result<TokenStream2> {
+    let io = Ident::new("__writer", Span::call_site());
+    let trait_name = quote!(::bytebuffer::ByteWrite);
+    let mut res = TokenStream2::new();
+    for (field, name) in fields.iter().zip(names) {
+        let attrs = FieldAttrs::parse(&field.attrs)?;
+        if attrs.skip {
+            continue;
+        }
+        let expr = if let Some(bits) = &attrs.bits {
+            quote!(::bytebuffer::BitField::encode_bits(#name, #io, #bits))
+        } else if let Some(prefix) = &attrs.prefix {
+            quote!(::bytebuffer::Prefixed::encode_prefixed(
+                #name,
+                #io,
+                ::bytebuffer::LengthPrefix::#prefix
+            ))
+        } else {
+            quote!(::bytebuffer::ByteEncode::encode(#name, #io))
+        };
+        let expr = with_endian(attrs.endian.as_ref(), &io, &trait_name, expr);
+        res.extend(quote!(#expr?;));
+    }
+    Ok(res)
+}
+
+fn decode_fields(fields: &Fields, names: &[Ident]) -> Result<TokenStream2> {
+    let io = Ident::new("__reader", Span::call_site());
+    let trait_name = quote!(::bytebuffer::ByteRead);
+    let mut res = TokenStream2::new();
+    for (field, name) in fields.iter().zip(names) {
+        let attrs = FieldAttrs::parse(&field.attrs)?;
+        let ty = &field.ty;
+        if attrs.skip {
+            let value = match attrs.default {
+                Some(expr) => quote!(#expr),
+                None => quote!(::core::default::Default::default()),
+            };
+            res.extend(quote!(let #name: #ty = #value;));
+            continue;
+        }
+        let expr = if let Some(bits) = &attrs.bits {
+            quote!(<#ty as ::bytebuffer::BitField>::decode_bits(#io, #bits))
+        } else if let Some(prefix) = &attrs.prefix {
+            quote!(<#ty as ::bytebuffer::Prefixed>::decode_prefixed(
+                #io,
+                ::bytebuffer::LengthPrefix::#prefix
+            ))
+        } else {
+            quote!(<#ty as ::bytebuffer::ByteDecode>::decode(#io))
+        };
+        let expr = with_endian(attrs.endian.as_ref(), &io, &trait_name, expr);
+        res.extend(quote!(let #name: #ty = #expr?;));
+    }
+    Ok(res)
+}
+
+fn expand_encode(input: DeriveInput) -> Result<TokenStream2> {
+    let attrs = ContainerAttrs::parse(&input.attrs, matches!(input.data, Data::Enum(_)))?;
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let names = field_names(&data.fields);
+            let pattern = construct(quote!(Self), &data.fields, &names);
+            let fields = encode_fields(&data.fields, &names)?;
+            quote! {
+                let #pattern = self;
+                #fields
+                ::core::result::Result::Ok(())
+            }
+        }
+        Data::Enum(data) => {
+            let tag_ty = attrs.tag.clone().unwrap_or_else(|| parse_quote!(u32));
+            let mut arms = TokenStream2::new();
+            for (variant, tag) in data
+                .variants
+                .iter()
+                .zip(discriminants(data.variants.iter()))
+            {
+                let ident = &variant.ident;
+                let names = field_names(&variant.fields);
+                let pattern = construct(quote!(Self::#ident), &variant.fields, &names);
+                let fields = encode_fields(&variant.fields, &names)?;
+                arms.extend(quote! {
+                    #pattern => {
+                        let __tag: #tag_ty = #tag;
+                        ::bytebuffer::ByteEncode::encode(&__tag, __writer)?;
+                        #fields
+                    }
+                });
+            }
+            quote! {
+                match self {
+                    #arms
+                }
+                ::core::result::Result::Ok(())
+            }
+        }
+        Data::Union(_) => {
+            return Err(Error::new(
+                input.span(),
+                "ByteEncode cannot be derived for unions",
+            ))
+        }
+    };
+
+    let io = Ident::new("__writer", Span::call_site());
+    let body = with_endian(
+        attrs.endian.as_ref(),
+        &io,
+        &quote!(::bytebuffer::ByteWrite),
+        quote!((|| -> ::bytebuffer::Result<()> { #body })()),
+    );
+    let name = &input.ident;
+    let generics = add_bounds(&input.generics, quote!(::bytebuffer::ByteEncode));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        #[automatically_derived]
+        impl #impl_generics ::bytebuffer::ByteEncode for #name #ty_generics #where_clause {
+            fn encode<__W: ::bytebuffer::ByteWrite + ?Sized>(
+                &self,
+                #io: &mut __W,
+            ) -> ::bytebuffer::Result<()> {
+                #body
+            }
+        }
+    })
+}
+
+fn expand_decode(input: DeriveInput) -> Result<TokenStream2> {
+    let attrs = ContainerAttrs::parse(&input.attrs, matches!(input.data, Data::Enum(_)))?;
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let names = field_names(&data.fields);
+            let fields = decode_fields(&data.fields, &names)?;
+            let value = construct(quote!(Self), &data.fields, &names);
+            quote! {
+                #fields
+                ::core::result::Result::Ok(#value)
+            }
+        }
+        Data::Enum(data) => {
+            let tag_ty = attrs.tag.clone().unwrap_or_else(|| parse_quote!(u32));
+            let mut arms = TokenStream2::new();
+            for (variant, tag) in data
+                .variants
+                .iter()
+                .zip(discriminants(data.variants.iter()))
+            {
+                let ident = &variant.ident;
+                let names = field_names(&variant.fields);
+                let fields = decode_fields(&variant.fields, &names)?;
+                let value = construct(quote!(Self::#ident), &variant.fields, &names);
+                arms.extend(quote! {
+                    if __tag == #tag {
+                        #fields
+                        ::core::result::Result::Ok(#value)
+                    } else
+                });
+            }
+            quote! {
+                let __offset = ::bytebuffer::ByteRead::get_rpos(__reader);
+                let __tag = <#tag_ty as ::bytebuffer::ByteDecode>::decode(__reader)?;
+                #arms {
+                    ::core::result::Result::Err(::bytebuffer::Error::InvalidValue { offset: __offset })
+                }
+            }
+        }
+        Data::Union(_) => {
+            return Err(Error::new(
+                input.span(),
+                "ByteDecode cannot be derived for unions",
+            ))
+        }
+    };
+
+    let io = Ident::new("__reader", Span::call_site());
+    let body = with_endian(
+        attrs.endian.as_ref(),
+        &io,
+        &quote!(::bytebuffer::ByteRead),
+        quote!((|| -> ::bytebuffer::Result<Self> { #body })()),
+    );
+    let name = &input.ident;
+    let generics = add_bounds(&input.generics, quote!(::bytebuffer::ByteDecode));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        #[automatically_derived]
+        impl #impl_generics ::bytebuffer::ByteDecode for #name #ty_generics #where_clause {
+            fn decode<__R: ::bytebuffer::ByteRead + ?Sized>(
+                #io: &mut __R,
+            ) -> ::bytebuffer::Result<Self> {
+                #body
+            }
+        }
+    })
+}
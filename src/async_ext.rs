@@ -0,0 +1,239 @@
+//! Async extension traits shared by the `futures-io` and `tokio` streams.
+//!
+//! The traits are generated once per stream flavour by `async_ext!`, on top of the `read_exact`
+//! and `write_all` functions of the invoking module. Values are read with exactly as many
+//! reads as they need, so the stream can be handed over to other code afterwards.
+
+/// Largest number of bytes allocated at once for data whose length comes from the stream, so
+/// that a bogus length fails at the end of the stream instead of allocating all of it
+pub(crate) const CHUNK_LEN: usize = 8 * 1024;
+
+macro_rules! async_read_number {
+    ($name:ident, $type:ty) => {
+        #[doc = concat!("Read a `", stringify!($type), "` value in the given byte order.")]
+        async fn $name(&mut self, endian: $crate::Endian) -> std::io::Result<$type> {
+            let mut buf = [0; core::mem::size_of::<$type>()];
+            read_exact(self, &mut buf).await?;
+            Ok(match endian {
+                $crate::Endian::BigEndian => <$type>::from_be_bytes(buf),
+                $crate::Endian::LittleEndian => <$type>::from_le_bytes(buf),
+            })
+        }
+    };
+}
+
+macro_rules! async_write_number {
+    ($name:ident, $type:ty) => {
+        #[doc = concat!("Write a `", stringify!($type), "` value in the given byte order.")]
+        async fn $name(&mut self, val: $type, endian: $crate::Endian) -> std::io::Result<()> {
+            let buf = match endian {
+                $crate::Endian::BigEndian => val.to_be_bytes(),
+                $crate::Endian::LittleEndian => val.to_le_bytes(),
+            };
+            write_all(self, &buf).await
+        }
+    };
+}
+
+macro_rules! async_ext {
+    ($read:path, $write:path, $import:literal, $stream:literal) => {
+        use alloc::{string::String, vec::Vec};
+        use $crate::{ByteBuffer, ByteReader, Endian, Error};
+
+        /// Read values straight from an async stream, as `ByteRead` does from a buffer.
+        ///
+        /// Errors come as `std::io::Error`s, with the offsets of the decoding errors relative
+        /// to the start of the value being read.
+        ///
+        /// _Note_: The numbers are read with `read_byte()` and `read_*_with(endian)`, so that the
+        /// names do not clash with the methods of `tokio::io::AsyncReadExt` when both traits are
+        /// imported.
+        ///
+        /// #Example
+        ///
+        /// ```
+        /// # use bytebuffer::Endian;
+        #[doc = $import]
+        ///
+        #[doc = concat!("async fn read_message<R: ", $stream, " + Unpin>(stream: &mut R) -> std::io::Result<()> {")]
+        ///     let id = stream.read_u32_with(Endian::LittleEndian).await?;
+        ///     let name = stream.read_string(Endian::LittleEndian).await?;
+        ///     let len = stream.read_var_u64().await? as usize;
+        ///     let mut frame = stream.read_frame(len).await?;
+        ///     Ok(())
+        /// }
+        /// ```
+        // Implemented for every stream only, so the futures are `Send` whenever the stream is
+        #[allow(async_fn_in_trait)]
+        pub trait AsyncByteReadExt: $read + Unpin {
+            /// Read one byte.
+            async fn read_byte(&mut self) -> std::io::Result<u8> {
+                let mut buf = [0; 1];
+                read_exact(self, &mut buf).await?;
+                Ok(buf[0])
+            }
+
+            /// Same as `read_byte()` but for signed values
+            async fn read_signed_byte(&mut self) -> std::io::Result<i8> {
+                Ok(self.read_byte().await? as i8)
+            }
+
+            async_read_number!(read_u16_with, u16);
+            async_read_number!(read_i16_with, i16);
+            async_read_number!(read_u32_with, u32);
+            async_read_number!(read_i32_with, i32);
+            async_read_number!(read_u64_with, u64);
+            async_read_number!(read_i64_with, i64);
+            async_read_number!(read_u128_with, u128);
+            async_read_number!(read_i128_with, i128);
+            async_read_number!(read_f32_with, f32);
+            async_read_number!(read_f64_with, f64);
+
+            /// Read exactly `len` raw bytes.
+            async fn read_bytes(&mut self, len: usize) -> std::io::Result<Vec<u8>> {
+                let mut bytes = Vec::new();
+                while bytes.len() < len {
+                    let start = bytes.len();
+                    let chunk = core::cmp::min(len - start, $crate::async_ext::CHUNK_LEN);
+                    bytes.resize(start + chunk, 0);
+                    read_exact(self, &mut bytes[start..]).await?;
+                }
+                Ok(bytes)
+            }
+
+            /// Read an UTF-8 string, with the layout of `ByteRead::read_string`: a `u32` length
+            /// in the given byte order, then the bytes of the string.
+            async fn read_string(&mut self, endian: Endian) -> std::io::Result<String> {
+                let len = self.read_u32_with(endian).await?;
+                let bytes = self.read_bytes(len as usize).await?;
+                Ok(String::from_utf8(bytes).map_err(|e| Error::InvalidUtf8 {
+                    offset: 4 + e.utf8_error().valid_up_to(),
+                })?)
+            }
+
+            /// Read an unsigned LEB128 variable-length value.
+            async fn read_var_u32(&mut self) -> std::io::Result<u32> {
+                let mut buf = [0; 5];
+                let len = read_var_bytes(self, &mut buf).await?;
+                Ok(ByteReader::from_bytes(&buf[..len]).read_var_u32()?)
+            }
+
+            /// Read an unsigned LEB128 variable-length value.
+            async fn read_var_u64(&mut self) -> std::io::Result<u64> {
+                let mut buf = [0; 10];
+                let len = read_var_bytes(self, &mut buf).await?;
+                Ok(ByteReader::from_bytes(&buf[..len]).read_var_u64()?)
+            }
+
+            /// Read a signed LEB128 variable-length value.
+            async fn read_var_i32(&mut self) -> std::io::Result<i32> {
+                let mut buf = [0; 5];
+                let len = read_var_bytes(self, &mut buf).await?;
+                Ok(ByteReader::from_bytes(&buf[..len]).read_var_i32()?)
+            }
+
+            /// Read a signed LEB128 variable-length value.
+            async fn read_var_i64(&mut self) -> std::io::Result<i64> {
+                let mut buf = [0; 10];
+                let len = read_var_bytes(self, &mut buf).await?;
+                Ok(ByteReader::from_bytes(&buf[..len]).read_var_i64()?)
+            }
+
+            /// Read a frame of exactly `len` bytes into a new `ByteBuffer`, to decode it
+            /// synchronously afterwards.
+            async fn read_frame(&mut self, len: usize) -> std::io::Result<ByteBuffer> {
+                Ok(ByteBuffer::from_vec(self.read_bytes(len).await?))
+            }
+        }
+
+        impl<R: $read + Unpin + ?Sized> AsyncByteReadExt for R {}
+
+        /// Write values straight to an async stream, as `ByteWrite` does to a buffer.
+        ///
+        /// _Note_: The numbers are written with `write_byte()` and `write_*_with(val, endian)`,
+        /// so that the names do not clash with the methods of `tokio::io::AsyncWriteExt` when
+        /// both traits are imported.
+        // Implemented for every stream only, so the futures are `Send` whenever the stream is
+        #[allow(async_fn_in_trait)]
+        pub trait AsyncByteWriteExt: $write + Unpin {
+            /// Write one byte.
+            async fn write_byte(&mut self, val: u8) -> std::io::Result<()> {
+                write_all(self, &[val]).await
+            }
+
+            /// Same as `write_byte()` but for signed values
+            async fn write_signed_byte(&mut self, val: i8) -> std::io::Result<()> {
+                self.write_byte(val as u8).await
+            }
+
+            async_write_number!(write_u16_with, u16);
+            async_write_number!(write_i16_with, i16);
+            async_write_number!(write_u32_with, u32);
+            async_write_number!(write_i32_with, i32);
+            async_write_number!(write_u64_with, u64);
+            async_write_number!(write_i64_with, i64);
+            async_write_number!(write_u128_with, u128);
+            async_write_number!(write_i128_with, i128);
+            async_write_number!(write_f32_with, f32);
+            async_write_number!(write_f64_with, f64);
+
+            /// Write raw bytes, such as the content of a `ByteBuffer`.
+            async fn write_bytes(&mut self, bytes: &[u8]) -> std::io::Result<()> {
+                write_all(self, bytes).await
+            }
+
+            /// Write an UTF-8 string, with the layout of `ByteWrite::write_string`, or return an
+            /// error if it is too long for a `u32` length.
+            async fn write_string(&mut self, val: &str, endian: Endian) -> std::io::Result<()> {
+                let len = u32::try_from(val.len()).map_err(|_| Error::LimitExceeded {
+                    value: val.len() as u64,
+                    limit: u32::MAX.into(),
+                    offset: 0,
+                })?;
+                self.write_u32_with(len, endian).await?;
+                write_all(self, val.as_bytes()).await
+            }
+
+            /// Write an unsigned LEB128 variable-length value.
+            async fn write_var_u32(&mut self, val: u32) -> std::io::Result<()> {
+                self.write_var_u64(val.into()).await
+            }
+
+            /// Write an unsigned LEB128 variable-length value.
+            async fn write_var_u64(&mut self, val: u64) -> std::io::Result<()> {
+                let mut buf = [0; $crate::varint::MAX_LEN];
+                let len = $crate::varint::encode_unsigned(val.into(), &mut buf);
+                write_all(self, &buf[..len]).await
+            }
+
+            /// Write a signed LEB128 variable-length value.
+            async fn write_var_i32(&mut self, val: i32) -> std::io::Result<()> {
+                self.write_var_i64(val.into()).await
+            }
+
+            /// Write a signed LEB128 variable-length value.
+            async fn write_var_i64(&mut self, val: i64) -> std::io::Result<()> {
+                let mut buf = [0; $crate::varint::MAX_LEN];
+                let len = $crate::varint::encode_signed(val.into(), &mut buf);
+                write_all(self, &buf[..len]).await
+            }
+        }
+
+        impl<W: $write + Unpin + ?Sized> AsyncByteWriteExt for W {}
+
+        /// Read the bytes of a LEB128 value into `buf`, stopping after its last byte or when
+        /// `buf` is full, and return the number of bytes read.
+        async fn read_var_bytes<R: $read + Unpin + ?Sized>(
+            reader: &mut R,
+            buf: &mut [u8],
+        ) -> std::io::Result<usize> {
+            for len in 0..buf.len() {
+                read_exact(reader, &mut buf[len..len + 1]).await?;
+                if buf[len] & 0x80 == 0 {
+                    return Ok(len + 1);
+                }
+            }
+            Ok(buf.len())
+        }
+    };
+}
@@ -0,0 +1,196 @@
+//! Bit extraction and insertion shared by the readers and writers.
+//!
+//! Values are moved up to 56 bits at a time through a 64 bits window loaded from the data,
+//! with shifts and masks, instead of one bit per call. The callers check the bounds.
+
+use crate::{BitOrder, ByteRead, ByteWrite, Error, Result};
+
+/// The largest number of bits moved at once, so that a chunk starting at any bit of a byte
+/// fits in the window
+const CHUNK: u32 = 56;
+
+/// Number of bits left after the bit `bit` of the byte `pos`
+pub(crate) fn available(len: usize, pos: usize, bit: usize) -> usize {
+    len.saturating_sub(pos)
+        .saturating_mul(8)
+        .saturating_sub(bit)
+}
+
+/// Number of bytes touched by `n` bits starting at the bit `bit` of a byte
+pub(crate) fn span(bit: usize, n: u32) -> usize {
+    (bit + n as usize).div_ceil(8)
+}
+
+#[inline]
+fn mask(n: u32) -> u64 {
+    u64::MAX >> (64 - n)
+}
+
+/// Load up to 8 bytes starting at `pos`, the missing ones being read as `0`.
+#[inline]
+fn load(data: &[u8], pos: usize, order: BitOrder) -> u64 {
+    let buf = match data.get(pos..pos + 8) {
+        Some(bytes) => bytes.try_into().expect("range should always be 8"),
+        None => {
+            let mut buf = [0; 8];
+            buf[..data.len() - pos].copy_from_slice(&data[pos..]);
+            buf
+        }
+    };
+    match order {
+        BitOrder::MsbFirst => u64::from_be_bytes(buf),
+        BitOrder::LsbFirst => u64::from_le_bytes(buf),
+    }
+}
+
+#[inline]
+fn store(data: &mut [u8], pos: usize, word: u64, order: BitOrder) {
+    let buf = match order {
+        BitOrder::MsbFirst => word.to_be_bytes(),
+        BitOrder::LsbFirst => word.to_le_bytes(),
+    };
+    match data.get_mut(pos..pos + 8) {
+        Some(bytes) => bytes.copy_from_slice(&buf),
+        None => {
+            let len = data.len() - pos;
+            data[pos..].copy_from_slice(&buf[..len]);
+        }
+    }
+}
+
+/// Read `take` bits (at most `CHUNK`) starting at the bit `bit` of the byte `pos`.
+#[inline]
+fn read_chunk(data: &[u8], pos: usize, bit: usize, take: u32, order: BitOrder) -> u64 {
+    if bit + take as usize <= 8 {
+        // The bits are all in the same byte
+        let byte = u64::from(data[pos]);
+        return match order {
+            BitOrder::MsbFirst => (byte >> (8 - bit - take as usize)) & mask(take),
+            BitOrder::LsbFirst => (byte >> bit) & mask(take),
+        };
+    }
+    let word = load(data, pos, order);
+    match order {
+        BitOrder::MsbFirst => (word << bit) >> (64 - take),
+        BitOrder::LsbFirst => (word >> bit) & mask(take),
+    }
+}
+
+/// Write the `take` low bits (at most `CHUNK`) of `chunk` starting at the bit `bit` of the
+/// byte `pos`.
+#[inline]
+fn write_chunk(data: &mut [u8], pos: usize, bit: usize, chunk: u64, take: u32, order: BitOrder) {
+    let chunk = chunk & mask(take);
+    if bit + take as usize <= 8 {
+        let shift = match order {
+            BitOrder::MsbFirst => 8 - bit - take as usize,
+            BitOrder::LsbFirst => bit,
+        };
+        let byte_mask = (mask(take) << shift) as u8;
+        data[pos] = (data[pos] & !byte_mask) | ((chunk << shift) as u8);
+        return;
+    }
+    let shift = match order {
+        BitOrder::MsbFirst => 64 - bit as u32 - take,
+        BitOrder::LsbFirst => bit as u32,
+    };
+    let word = load(data, pos, order) & !(mask(take) << shift);
+    store(data, pos, word | (chunk << shift), order);
+}
+
+/// Read `n` bits (at most 128) starting at the bit `bit` of the byte `pos`.
+#[inline]
+pub(crate) fn read(data: &[u8], mut pos: usize, mut bit: usize, n: u32, order: BitOrder) -> u128 {
+    if n <= CHUNK {
+        return if n == 0 {
+            0
+        } else {
+            read_chunk(data, pos, bit, n, order).into()
+        };
+    }
+    let mut value = 0u128;
+    let mut done = 0;
+    while done < n {
+        let take = core::cmp::min(n - done, CHUNK);
+        let chunk = u128::from(read_chunk(data, pos, bit, take, order));
+        value = match order {
+            BitOrder::MsbFirst => (value << take) | chunk,
+            BitOrder::LsbFirst => value | (chunk << done),
+        };
+        done += take;
+        pos += (bit + take as usize) / 8;
+        bit = (bit + take as usize) % 8;
+    }
+    value
+}
+
+/// Write the `n` low bits (at most 128) of `value` starting at the bit `bit` of the byte
+/// `pos`. The other bits of the data are left untouched.
+#[inline]
+pub(crate) fn write(
+    data: &mut [u8],
+    mut pos: usize,
+    mut bit: usize,
+    value: u128,
+    n: u32,
+    order: BitOrder,
+) {
+    if n <= CHUNK {
+        if n > 0 {
+            write_chunk(data, pos, bit, value as u64, n, order);
+        }
+        return;
+    }
+    let mut done = 0;
+    while done < n {
+        let take = core::cmp::min(n - done, CHUNK);
+        let chunk = match order {
+            BitOrder::MsbFirst => value >> (n - done - take),
+            BitOrder::LsbFirst => value >> done,
+        };
+        write_chunk(data, pos, bit, chunk as u64, take, order);
+        done += take;
+        pos += (bit + take as usize) / 8;
+        bit = (bit + take as usize) % 8;
+    }
+}
+
+/// Read a two's complement value stored in `n` bits (at most 64), extending its sign.
+pub(crate) fn read_signed<R: ByteRead + ?Sized>(reader: &mut R, n: u8) -> Result<i64> {
+    let value = reader.read_bits(n)?;
+    if n == 0 {
+        return Ok(0);
+    }
+    let unused = 64 - u32::from(n);
+    Ok(((value << unused) as i64) >> unused)
+}
+
+/// Write a value in `n` bits (at most 64) as two's complement, or return an error if it does
+/// not fit.
+pub(crate) fn write_signed<W: ByteWrite + ?Sized>(writer: &mut W, value: i64, n: u8) -> Result<()> {
+    if n > 64 {
+        return Err(Error::TooManyBits {
+            requested: n as usize,
+            max: 64,
+        });
+    }
+    let fits = match n {
+        0 => value == 0,
+        64 => true,
+        // Every bit above the sign bit must be a copy of it
+        _ => matches!(value >> (n - 1), 0 | -1),
+    };
+    if !fits {
+        return Err(Error::OutOfRange {
+            value,
+            bits: n as usize,
+            offset: writer.get_wpos(),
+        });
+    }
+    let value = if n == 0 {
+        0
+    } else {
+        value as u64 & (u64::MAX >> (64 - n))
+    };
+    writer.write_bits(value, n)
+}
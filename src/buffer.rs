@@ -1,9 +1,12 @@
-use crate::Endian;
-use byteorder::{BigEndian, ByteOrder, LittleEndian};
-use std::{
-    fmt::Debug,
-    io::{Error, ErrorKind, Read, Result, Write},
+use crate::{
+    bits, golomb, prefix, varint, BitOrder, ByteEncode, ByteRead, ByteReader, ByteWrite,
+    Checkpoint, CheckpointGuard, Endian, Error, FieldHandle, LengthPrefix, Result,
 };
+use alloc::{format, string::String, vec, vec::Vec};
+use byteorder::{BigEndian, ByteOrder, LittleEndian};
+use core::{fmt::Debug, marker::PhantomData};
+#[cfg(feature = "std")]
+use std::io::{Read, Write};
 
 /// A byte buffer object specifically turned to easily read and write binary values
 #[derive(Clone, PartialEq, Eq, Hash)]
@@ -14,6 +17,8 @@ pub struct ByteBuffer {
     wbit: usize,
     rbit: usize,
     endian: Endian,
+    bit_order: BitOrder,
+    auto_compact: Option<usize>,
 }
 
 impl From<&[u8]> for ByteBuffer {
@@ -34,16 +39,33 @@ impl From<ByteBuffer> for Vec<u8> {
     }
 }
 
+/// Take ownership of the data, without copying it when `val` is the only handle to it.
+#[cfg(feature = "bytes")]
+impl From<bytes::Bytes> for ByteBuffer {
+    fn from(val: bytes::Bytes) -> Self {
+        ByteBuffer::from_vec(val.into())
+    }
+}
+
+/// Hand the whole data over to a `Bytes`, without copying it.
+#[cfg(feature = "bytes")]
+impl From<ByteBuffer> for bytes::Bytes {
+    fn from(val: ByteBuffer) -> Self {
+        val.into_vec().into()
+    }
+}
+
 impl Default for ByteBuffer {
     fn default() -> Self {
         Self::new()
     }
 }
 
+#[cfg(feature = "std")]
 impl Read for ByteBuffer {
-    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
         self.flush_bits();
-        let read_len = std::cmp::min(self.data.len() - self.rpos, buf.len());
+        let read_len = core::cmp::min(self.data.len() - self.rpos, buf.len());
         let range = self.rpos..self.rpos + read_len;
         for (i, val) in self.data[range].iter().enumerate() {
             buf[i] = *val;
@@ -53,24 +75,87 @@ impl Read for ByteBuffer {
     }
 }
 
+#[cfg(feature = "std")]
 impl Write for ByteBuffer {
-    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
         self.write_bytes(buf);
         Ok(buf.len())
     }
 
-    fn flush(&mut self) -> Result<()> {
+    fn flush(&mut self) -> std::io::Result<()> {
         Ok(())
     }
 }
 
+/// Read the bytes after the reading cursor. Pending bits are discarded as with `read_bytes`.
+#[cfg(feature = "bytes")]
+impl bytes::Buf for ByteBuffer {
+    fn remaining(&self) -> usize {
+        self.data.len() - self.byte_rpos()
+    }
+
+    fn chunk(&self) -> &[u8] {
+        &self.data[self.byte_rpos()..]
+    }
+
+    fn advance(&mut self, cnt: usize) {
+        self.flush_bits();
+        assert!(
+            cnt <= self.data.len() - self.rpos,
+            "cannot advance past the end of the buffer"
+        );
+        self.rpos += cnt;
+    }
+}
+
+/// Write the bytes at the writing cursor, growing the buffer as needed. Pending bits are
+/// flushed as with `write_bytes`.
+#[cfg(feature = "bytes")]
+unsafe impl bytes::BufMut for ByteBuffer {
+    fn remaining_mut(&self) -> usize {
+        isize::MAX as usize - self.wpos
+    }
+
+    unsafe fn advance_mut(&mut self, cnt: usize) {
+        self.flush_bits();
+        let end = self.wpos + cnt;
+        if self.wpos < self.data.len() {
+            assert!(
+                end <= self.data.len(),
+                "cannot advance past the end of the chunk"
+            );
+        } else if end > self.data.len() {
+            assert!(
+                end <= self.data.capacity(),
+                "cannot advance past the end of the chunk"
+            );
+            // SAFETY: the caller initialized the first `cnt` bytes of the spare capacity
+            // returned by `chunk_mut`
+            unsafe { self.data.set_len(end) };
+        }
+        self.wpos = end;
+    }
+
+    fn chunk_mut(&mut self) -> &mut bytes::buf::UninitSlice {
+        self.flush_bits();
+        if self.wpos < self.data.len() {
+            return bytes::buf::UninitSlice::new(&mut self.data[self.wpos..]);
+        }
+        if self.data.capacity() == self.data.len() {
+            self.compact_if_needed();
+            self.data.reserve(64);
+        }
+        bytes::buf::UninitSlice::uninit(self.data.spare_capacity_mut())
+    }
+
+    fn put_slice(&mut self, src: &[u8]) {
+        self.write_bytes(src);
+    }
+}
+
 impl Debug for ByteBuffer {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        let rpos = if self.rbit > 0 {
-            self.rpos + 1
-        } else {
-            self.rpos
-        };
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        let rpos = self.byte_rpos();
 
         let read_len = self.data.len() - rpos;
         let mut remaining_data = vec![0; read_len];
@@ -87,25 +172,6 @@ impl Debug for ByteBuffer {
     }
 }
 
-macro_rules! read_number {
-    ($self:ident, $name:ident, $offset:expr) => {{
-        $self.flush_bits();
-        if $self.rpos + $offset > $self.data.len() {
-            return Err(Error::new(
-                ErrorKind::UnexpectedEof,
-                "could not read enough bits from buffer",
-            ));
-        }
-        let range = $self.rpos..$self.rpos + $offset;
-        $self.rpos += $offset;
-
-        Ok(match $self.endian {
-            Endian::BigEndian => BigEndian::$name(&$self.data[range]),
-            Endian::LittleEndian => LittleEndian::$name(&$self.data[range]),
-        })
-    }};
-}
-
 impl ByteBuffer {
     /// Construct a new, empty, ByteBuffer
     pub fn new() -> ByteBuffer {
@@ -116,6 +182,8 @@ impl ByteBuffer {
             rbit: 0,
             wbit: 0,
             endian: Endian::BigEndian,
+            bit_order: BitOrder::MsbFirst,
+            auto_compact: None,
         }
     }
 
@@ -137,9 +205,16 @@ impl ByteBuffer {
             rbit: 0,
             wbit: 0,
             endian: Endian::BigEndian,
+            bit_order: BitOrder::MsbFirst,
+            auto_compact: None,
         }
     }
 
+    /// Position of the first byte without any bit read yet
+    fn byte_rpos(&self) -> usize {
+        self.rpos + usize::from(self.rbit > 0)
+    }
+
     /// Return the buffer size
     pub fn len(&self) -> usize {
         self.data.len()
@@ -156,6 +231,97 @@ impl ByteBuffer {
         self.reset_bits_cursors();
     }
 
+    /// Remove the bytes before the reading cursor, which have already been read, and move
+    /// both cursors back accordingly. Bits already read in the current byte stay read.
+    ///
+    /// _Note_: If the writing cursor is before the reading cursor, only the bytes before the
+    /// writing cursor are removed, so that the next writes never overwrite unread bytes.
+    ///
+    /// #Example
+    ///
+    /// ```
+    /// #  use bytebuffer::*;
+    /// let mut buffer = ByteBuffer::from_bytes(&[0x1, 0x2, 0x3]);
+    /// buffer.read_u16().unwrap();
+    /// buffer.compact(); // buffer contains [0x3]
+    /// assert_eq!(buffer.get_rpos(), 0);
+    /// assert_eq!(buffer.get_wpos(), 1);
+    /// ```
+    pub fn compact(&mut self) {
+        let consumed = core::cmp::min(self.rpos, self.wpos);
+        self.data.drain(..consumed);
+        self.wpos -= consumed;
+        self.rpos -= consumed;
+    }
+
+    /// Same as `compact()`
+    pub fn discard_consumed(&mut self) {
+        self.compact();
+    }
+
+    /// Return the number of bytes before the reading cursor, which `compact()` would remove
+    pub fn consumed(&self) -> usize {
+        self.rpos
+    }
+
+    /// Return the number of whole bytes left to read after the reading cursor
+    pub fn remaining(&self) -> usize {
+        self.data.len() - self.byte_rpos()
+    }
+
+    /// Compact the buffer automatically when it needs to grow for a write and at least
+    /// `threshold` bytes have been consumed, or never with `None`, which is the default.
+    /// This keeps the memory of a buffer used as a receive buffer bounded by its unread data.
+    ///
+    /// _Note_: Compacting moves the cursors, so positions saved before a write, like the
+    /// result of `get_rpos()` or a `FieldHandle`, may no longer be valid after it.
+    ///
+    /// #Example
+    ///
+    /// ```
+    /// #  use bytebuffer::*;
+    /// let mut buffer = ByteBuffer::new();
+    /// buffer.set_auto_compact(Some(2));
+    /// buffer.write_u16(0x0102);
+    /// buffer.read_u16().unwrap();
+    /// buffer.write_u8(0x3); // buffer contains [0x3]
+    /// assert_eq!(buffer.consumed(), 0);
+    /// ```
+    pub fn set_auto_compact(&mut self, threshold: Option<usize>) {
+        self.auto_compact = threshold;
+    }
+
+    /// Return the auto compact threshold set with `set_auto_compact()`
+    pub fn auto_compact(&self) -> Option<usize> {
+        self.auto_compact
+    }
+
+    fn compact_if_needed(&mut self) {
+        // Compacting while writing before the reading cursor would drop written bytes
+        if self
+            .auto_compact
+            .is_some_and(|threshold| self.rpos >= threshold && self.rpos <= self.wpos)
+        {
+            self.compact();
+        }
+    }
+
+    /// Run `f` with the auto compaction disabled, so that the positions it saves stay valid
+    fn without_auto_compact<T>(&mut self, f: impl FnOnce(&mut Self) -> T) -> T {
+        let threshold = self.auto_compact.take();
+        let result = f(self);
+        self.auto_compact = threshold;
+        result
+    }
+
+    /// Extend the buffer so that `len` bytes can be written at the writing cursor
+    fn reserve_write(&mut self, len: usize) {
+        if self.wpos + len > self.data.len() {
+            self.compact_if_needed();
+            self.resize(self.wpos + len);
+        }
+    }
+
     /// Reinitialize the reading and writing cursor
     pub fn reset_cursors(&mut self) {
         self.wpos = 0;
@@ -174,7 +340,7 @@ impl ByteBuffer {
     pub fn resize(&mut self, size: usize) {
         let diff = size - self.data.len();
         if diff > 0 {
-            self.data.extend(std::iter::repeat(0).take(diff))
+            self.data.extend(core::iter::repeat_n(0, diff))
         }
     }
 
@@ -190,6 +356,21 @@ impl ByteBuffer {
         self.endian
     }
 
+    /// Set the order of the bits for the bit functions
+    ///
+    /// _Note_: By default the bits are read and written most significant bit first
+    ///
+    /// _Note_: The order applies to whole bytes, so this method resets the read and write cursor for bitwise reading.
+    pub fn set_bit_order(&mut self, bit_order: BitOrder) {
+        self.flush_bits();
+        self.bit_order = bit_order;
+    }
+
+    /// Returns the current bit order of the buffer
+    pub fn bit_order(&self) -> BitOrder {
+        self.bit_order
+    }
+
     // Write operations
 
     /// Append a byte array to the buffer. The buffer is automatically extended if needed
@@ -204,12 +385,7 @@ impl ByteBuffer {
     /// ```
     pub fn write_bytes(&mut self, bytes: &[u8]) {
         self.flush_bits();
-
-        let size = bytes.len() + self.wpos;
-
-        if size > self.data.len() {
-            self.resize(size);
-        }
+        self.reserve_write(bytes.len());
 
         for v in bytes {
             self.data[self.wpos] = *v;
@@ -396,272 +572,1258 @@ impl ByteBuffer {
     /// let mut buffer = ByteBuffer::new();
     /// buffer.write_string("Hello")
     /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the string is longer than `u32::MAX` bytes, which its length prefix cannot
+    /// hold. Use `write_prefixed_string()` with `LengthPrefix::U32` to get an error instead.
     pub fn write_string(&mut self, val: &str) {
-        self.write_u32(val.len() as u32);
-        self.write_bytes(val.as_bytes());
+        self.write_prefixed_string(val, LengthPrefix::U32)
+            .expect("string is too long for a u32 length prefix");
     }
 
-    // Read operations
+    /// Append an unsigned LEB128 variable-length value to the buffer: 7 bits per byte, least
+    /// significant group first, with the high bit set on every byte but the last.
+    /// _Note_: This method resets the read and write cursor for bitwise reading.
+    ///
+    /// #Example
+    ///
+    /// ```
+    /// #  use bytebuffer::*;
+    /// let mut buffer = ByteBuffer::new();
+    /// buffer.write_var_u32(300) // buffer contains [0xAC, 0x02]
+    /// ```
+    pub fn write_var_u32(&mut self, val: u32) {
+        self.write_var_u128(u128::from(val));
+    }
 
-    /// Read a defined amount of raw bytes, or return an IO error if not enough bytes are
-    /// available.
+    /// Same as `write_var_u32()` but for 64 bits values
     /// _Note_: This method resets the read and write cursor for bitwise reading.
-    pub fn read_bytes(&mut self, size: usize) -> Result<Vec<u8>> {
-        self.flush_bits();
-        if self.rpos + size > self.data.len() {
-            return Err(Error::new(
-                ErrorKind::UnexpectedEof,
-                "could not read enough bytes from buffer",
-            ));
-        }
-        let range = self.rpos..self.rpos + size;
-        let mut res = Vec::<u8>::new();
-        res.write_all(&self.data[range])?;
-        self.rpos += size;
-        Ok(res)
+    pub fn write_var_u64(&mut self, val: u64) {
+        self.write_var_u128(u128::from(val));
+    }
+
+    /// Same as `write_var_u32()` but for 128 bits values
+    /// _Note_: This method resets the read and write cursor for bitwise reading.
+    pub fn write_var_u128(&mut self, val: u128) {
+        let mut buf = [0; varint::MAX_LEN];
+        let len = varint::encode_unsigned(val, &mut buf);
+        self.write_bytes(&buf[..len]);
     }
 
-    /// Read one byte, or return an IO error if not enough bytes are available.
+    /// Append a signed LEB128 variable-length value to the buffer. The sign is extended from
+    /// the highest bit of the last byte.
     /// _Note_: This method resets the read and write cursor for bitwise reading.
     ///
     /// #Example
     ///
     /// ```
     /// #  use bytebuffer::*;
-    /// let mut buffer = ByteBuffer::from_bytes(&vec![0x1]);
-    /// let value = buffer.read_u8().unwrap(); //Value contains 1
+    /// let mut buffer = ByteBuffer::new();
+    /// buffer.write_var_i32(-123456) // buffer contains [0xC0, 0xBB, 0x78]
     /// ```
-    pub fn read_u8(&mut self) -> Result<u8> {
-        self.flush_bits();
-        if self.rpos >= self.data.len() {
-            return Err(Error::new(
-                ErrorKind::UnexpectedEof,
-                "could not read enough bits from buffer",
-            ));
-        }
-        let pos = self.rpos;
-        self.rpos += 1;
-        Ok(self.data[pos])
+    pub fn write_var_i32(&mut self, val: i32) {
+        self.write_var_i128(i128::from(val));
     }
 
-    /// Same as `read_u8()` but for signed values
-    pub fn read_i8(&mut self) -> Result<i8> {
-        Ok(self.read_u8()? as i8)
+    /// Same as `write_var_i32()` but for 64 bits values
+    /// _Note_: This method resets the read and write cursor for bitwise reading.
+    pub fn write_var_i64(&mut self, val: i64) {
+        self.write_var_i128(i128::from(val));
+    }
+
+    /// Same as `write_var_i32()` but for 128 bits values
+    /// _Note_: This method resets the read and write cursor for bitwise reading.
+    pub fn write_var_i128(&mut self, val: i128) {
+        let mut buf = [0; varint::MAX_LEN];
+        let len = varint::encode_signed(val, &mut buf);
+        self.write_bytes(&buf[..len]);
     }
 
-    /// Read a 2-bytes long value, or return an IO error if not enough bytes are available.
+    /// Append a ZigZag encoded LEB128 variable-length value to the buffer, so that values of
+    /// small magnitude use few bytes whatever their sign (0 => 0, -1 => 1, 1 => 2, ...).
     /// _Note_: This method resets the read and write cursor for bitwise reading.
     ///
     /// #Example
     ///
     /// ```
     /// #  use bytebuffer::*;
-    /// let mut buffer = ByteBuffer::from_bytes(&vec![0x0, 0x1]);
-    /// let value = buffer.read_u16().unwrap(); //Value contains 1
+    /// let mut buffer = ByteBuffer::new();
+    /// buffer.write_zigzag_i32(-2) // buffer contains [0x03]
     /// ```
-    pub fn read_u16(&mut self) -> Result<u16> {
-        read_number!(self, read_u16, 2)
+    pub fn write_zigzag_i32(&mut self, val: i32) {
+        self.write_var_u128(varint::zigzag_encode(i128::from(val), 32));
     }
 
-    /// Same as `read_u16()` but for signed values
+    /// Same as `write_zigzag_i32()` but for 64 bits values
     /// _Note_: This method resets the read and write cursor for bitwise reading.
-    pub fn read_i16(&mut self) -> Result<i16> {
-        Ok(self.read_u16()? as i16)
+    pub fn write_zigzag_i64(&mut self, val: i64) {
+        self.write_var_u128(varint::zigzag_encode(i128::from(val), 64));
     }
 
-    /// Read a four-bytes long value, or return an IO error if not enough bytes are available.
+    /// Same as `write_zigzag_i32()` but for 128 bits values
+    /// _Note_: This method resets the read and write cursor for bitwise reading.
+    pub fn write_zigzag_i128(&mut self, val: i128) {
+        self.write_var_u128(varint::zigzag_encode(val, 128));
+    }
+
+    /// Append a byte array to the buffer, with its length encoded as described by `prefix`.
+    /// Return an error, without writing anything, if the length cannot be represented by
+    /// `prefix`, or if the array contains a `0` byte and `prefix` is `NulTerminated`.
     /// _Note_: This method resets the read and write cursor for bitwise reading.
     ///
     /// #Example
     ///
     /// ```
     /// #  use bytebuffer::*;
-    /// let mut buffer = ByteBuffer::from_bytes(&vec![0x0, 0x0, 0x0, 0x1]);
-    /// let value = buffer.read_u32().unwrap(); // Value contains 1
+    /// let mut buffer = ByteBuffer::new();
+    /// buffer.write_prefixed_bytes(&[0xFF, 0x45], LengthPrefix::U8).unwrap(); // buffer contains [0x2, 0xFF, 0x45]
+    /// buffer.write_prefixed_bytes(&[0xFF], LengthPrefix::Fixed(3)).unwrap(); // buffer contains [..., 0xFF, 0x0, 0x0]
+    /// assert!(buffer.write_prefixed_bytes(&[0; 300], LengthPrefix::U8).is_err());
     /// ```
-    pub fn read_u32(&mut self) -> Result<u32> {
-        read_number!(self, read_u32, 4)
+    pub fn write_prefixed_bytes(&mut self, bytes: &[u8], prefix: LengthPrefix) -> Result<()> {
+        prefix::write_prefixed(self, bytes, prefix)
     }
 
-    /// Same as `read_u32()` but for signed values
+    /// Same as `write_prefixed_bytes()` but for strings
     /// _Note_: This method resets the read and write cursor for bitwise reading.
-    pub fn read_i32(&mut self) -> Result<i32> {
-        Ok(self.read_u32()? as i32)
+    ///
+    /// #Example
+    ///
+    /// ```
+    /// #  use bytebuffer::*;
+    /// let mut buffer = ByteBuffer::new();
+    /// buffer.write_prefixed_string("Hello", LengthPrefix::NulTerminated).unwrap(); // buffer contains b"Hello\0"
+    /// ```
+    pub fn write_prefixed_string(&mut self, val: &str, prefix: LengthPrefix) -> Result<()> {
+        prefix::write_prefixed(self, val.as_bytes(), prefix)
     }
 
-    /// Read an eight bytes long value, or return an IO error if not enough bytes are available.
+    /// Write data with `body`, preceded by its length encoded as described by `prefix`, and
+    /// return the result of `body`. The length is filled in once `body` returns, or an error
+    /// is returned if it does not fit in the prefix. If `body` fails or the length does not
+    /// fit, the length and the data written so far are removed.
+    /// _Note_: With `VarInt`, the data is moved after the length once its size is known.
     /// _Note_: This method resets the read and write cursor for bitwise reading.
     ///
     /// #Example
     ///
     /// ```
     /// #  use bytebuffer::*;
-    /// let mut buffer = ByteBuffer::from_bytes(&vec![0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x1]);
-    /// let value = buffer.read_u64().unwrap(); //Value contains 1
+    /// let mut buffer = ByteBuffer::new();
+    /// buffer
+    ///     .write_length_prefixed(LengthPrefix::U16, |buffer| {
+    ///         buffer.write_u8(0x1);
+    ///         buffer.write_u16(0x2);
+    ///         Ok(())
+    ///     })
+    ///     .unwrap(); // buffer contains [0x0, 0x3, 0x1, 0x0, 0x2]
     /// ```
-    pub fn read_u64(&mut self) -> Result<u64> {
-        read_number!(self, read_u64, 8)
+    pub fn write_length_prefixed<T>(
+        &mut self,
+        prefix: LengthPrefix,
+        body: impl FnOnce(&mut Self) -> Result<T>,
+    ) -> Result<T> {
+        // Compacting would move the length away from `offset`
+        self.without_auto_compact(|buffer| {
+            buffer.flush_bits();
+            let (offset, len) = (buffer.wpos, buffer.data.len());
+            let reserved = match prefix {
+                LengthPrefix::U8 => 1,
+                LengthPrefix::U16 => 2,
+                LengthPrefix::U32 => 4,
+                LengthPrefix::U64 => 8,
+                _ => 0,
+            };
+            buffer.write_bytes(&[0; 8][..reserved]);
+            let start = buffer.wpos;
+
+            let value = body(buffer).inspect_err(|_| buffer.rollback_write(offset, len))?;
+            buffer.flush_bits();
+            let end = core::cmp::max(buffer.wpos, start);
+
+            let mut header = ByteBuffer::new();
+            header.set_endian(buffer.endian);
+            prefix::write_len(&mut header, &buffer.data[start..end], prefix, offset)
+                .inspect_err(|_| buffer.rollback_write(offset, len))?;
+            if reserved > 0 {
+                buffer.data[offset..start].copy_from_slice(header.as_bytes());
+            } else {
+                buffer.data.splice(start..start, header.data);
+                buffer.wpos = end + header.wpos;
+            }
+            prefix::write_trailer(buffer, end - start, prefix)
+                .inspect_err(|_| buffer.rollback_write(offset, len))?;
+            Ok(value)
+        })
     }
 
-    /// Same as `read_u64()` but for signed values
-    /// _Note_: This method resets the read and write cursor for bitwise reading.
-    pub fn read_i64(&mut self) -> Result<i64> {
-        Ok(self.read_u64()? as i64)
+    /// Remove what was written since the writing cursor was at `wpos` with `len` bytes stored.
+    fn rollback_write(&mut self, wpos: usize, len: usize) {
+        self.data.truncate(len);
+        self.wpos = wpos;
+        self.wbit = 0;
     }
 
-    /// Read a sixteen bytes long value, or return an IO error if not enough bytes are available.
+    /// Write a placeholder for a value of type `T`, to be filled in later with `patch_*()`
+    /// methods once it is known, and return a handle to it.
     /// _Note_: This method resets the read and write cursor for bitwise reading.
     ///
     /// #Example
     ///
     /// ```
     /// #  use bytebuffer::*;
-    /// let mut buffer = ByteBuffer::from_bytes(&vec![0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x1]);
-    /// let value = buffer.read_u128().unwrap(); //Value contains 1
+    /// let mut buffer = ByteBuffer::new();
+    /// let checksum = buffer.reserve_field::<u32>().unwrap(); // buffer contains [0x0, 0x0, 0x0, 0x0]
+    /// buffer.write_bytes(&[0x1, 0x2]);
+    /// let sum = buffer.as_bytes()[4..].iter().map(|b| *b as u32).sum();
+    /// buffer.patch_u32(checksum, sum).unwrap(); // buffer contains [0x0, 0x0, 0x0, 0x3, 0x1, 0x2]
     /// ```
-    pub fn read_u128(&mut self) -> Result<u128> {
-        read_number!(self, read_u128, 16)
+    pub fn reserve_field<T: ByteEncode + Default>(&mut self) -> Result<FieldHandle<T>> {
+        self.without_auto_compact(|buffer| {
+            buffer.flush_bits();
+            let offset = buffer.wpos;
+            T::default().encode(buffer)?;
+            buffer.flush_bits();
+            Ok(FieldHandle {
+                offset,
+                size: buffer.wpos - offset,
+                field: PhantomData,
+            })
+        })
     }
 
-    /// Same as `read_u128()` but for signed values
-    /// _Note_: This method resets the read and write cursor for bitwise reading.
-    pub fn read_i128(&mut self) -> Result<i128> {
-        Ok(self.read_u128()? as i128)
+    /// Fill in a field reserved with `reserve_field()`, without moving the cursors. Return
+    /// `Error::SizeMismatch` if the encoded value does not take the size of the field, or an
+    /// error if the buffer was shortened since.
+    pub fn patch_field<T: ByteEncode>(&mut self, handle: FieldHandle<T>, value: &T) -> Result<()> {
+        let mut field = ByteBuffer::new();
+        field.set_endian(self.endian);
+        field.set_bit_order(self.bit_order);
+        value.encode(&mut field)?;
+        if field.len() != handle.size {
+            return Err(Error::SizeMismatch {
+                expected: handle.size,
+                found: field.len(),
+                offset: handle.offset,
+            });
+        }
+        self.patch_bytes(handle.offset, field.as_bytes())
     }
 
-    /// Read a 32 bits floating point value, or return an IO error if not enough bytes are available.
-    /// _Note_: This method resets the read and write cursor for bitwise reading.
-    pub fn read_f32(&mut self) -> Result<f32> {
-        read_number!(self, read_f32, 4)
+    /// Fill in a `u16` field reserved with `reserve_field()`, without moving the cursors.
+    pub fn patch_u16(&mut self, handle: FieldHandle<u16>, value: u16) -> Result<()> {
+        self.patch_field(handle, &value)
     }
 
-    /// Read a 64 bits floating point value, or return an IO error if not enough bytes are available.
-    /// _Note_: This method resets the read and write cursor for bitwise reading.
-    pub fn read_f64(&mut self) -> Result<f64> {
-        read_number!(self, read_f64, 8)
+    /// Fill in a `u32` field reserved with `reserve_field()`, without moving the cursors.
+    pub fn patch_u32(&mut self, handle: FieldHandle<u32>, value: u32) -> Result<()> {
+        self.patch_field(handle, &value)
     }
 
-    /// Read a string.
+    /// Fill in a `u64` field reserved with `reserve_field()`, without moving the cursors.
+    pub fn patch_u64(&mut self, handle: FieldHandle<u64>, value: u64) -> Result<()> {
+        self.patch_field(handle, &value)
+    }
+
+    /// Fill in any field reserved with `reserve_field()` with an unsigned LEB128 value, padded
+    /// to the size of the field so that it can be read with `read_var_u64()`. Return an error
+    /// if the value does not fit in the field, or if the field is longer than the 10 bytes of
+    /// the longest `u64` value.
     ///
-    /// _Note_: First it reads a 32 bits value representing the size, then 'size' raw bytes
-    ///         that  must be encoded as UTF8.
-    /// _Note_: This method resets the read and write cursor for bitwise reading.
-    pub fn read_string(&mut self) -> Result<String> {
-        let size = self.read_u32()?;
-        match String::from_utf8(self.read_bytes(size as usize)?) {
-            Ok(string_result) => Ok(string_result),
-            Err(e) => Err(Error::new(ErrorKind::InvalidData, e)),
+    /// #Example
+    ///
+    /// ```
+    /// #  use bytebuffer::*;
+    /// let mut buffer = ByteBuffer::new();
+    /// let len = buffer.reserve_field::<u16>().unwrap();
+    /// buffer.patch_var(len, 300).unwrap(); // buffer contains [0xAC, 0x02]
+    /// assert!(buffer.patch_var(len, 1 << 14).is_err());
+    /// ```
+    pub fn patch_var<T>(&mut self, handle: FieldHandle<T>, value: u64) -> Result<()> {
+        if handle.size > varint::MAX_U64_LEN {
+            return Err(Error::LimitExceeded {
+                value: handle.size as u64,
+                limit: varint::MAX_U64_LEN as u64,
+                offset: handle.offset,
+            });
+        }
+        let mut field = [0; varint::MAX_U64_LEN];
+        let field = &mut field[..handle.size];
+        if !varint::encode_unsigned_padded(value.into(), field) {
+            let bits = 7 * handle.size as u32;
+            return Err(Error::LimitExceeded {
+                value,
+                limit: u64::MAX.checked_shr(64 - bits).unwrap_or(0),
+                offset: handle.offset,
+            });
         }
+        self.patch_bytes(handle.offset, field)
     }
 
-    // Other
-
-    /// Dump the byte buffer to a string.
-    pub fn to_hex_dump(&self) -> String {
-        let mut str = String::new();
-        for b in &self.data {
-            str = str + &format!("0x{:01$x} ", b, 2);
+    fn patch_bytes(&mut self, offset: usize, bytes: &[u8]) -> Result<()> {
+        if bytes.len() > self.data.len().saturating_sub(offset) {
+            return Err(Error::full(bytes.len(), offset, self.data.len()));
         }
-        str.pop();
-        str
+        self.data[offset..offset + bytes.len()].copy_from_slice(bytes);
+        Ok(())
     }
 
-    /// Return the position of the reading cursor
-    pub fn get_rpos(&self) -> usize {
-        self.rpos
+    /// Read one byte at `index`, without moving the reading cursor, or return an error if
+    /// `index` is out of bounds.
+    ///
+    /// #Example
+    ///
+    /// ```
+    /// #  use bytebuffer::*;
+    /// let buffer = ByteBuffer::from_bytes(&[0x1, 0x2, 0x3]);
+    /// let value = buffer.get_u8_at(2).unwrap(); // value contains 3
+    /// assert_eq!(buffer.get_rpos(), 0);
+    /// ```
+    pub fn get_u8_at(&self, index: usize) -> Result<u8> {
+        self.data
+            .get(index)
+            .copied()
+            .ok_or_else(|| Error::eof(1, index, self.data.len()))
     }
 
-    /// Set the reading cursor position.
-    /// _Note_: Sets the reading cursor to `min(newPosition, self.len())` to prevent overflow
-    pub fn set_rpos(&mut self, rpos: usize) {
-        self.rpos = std::cmp::min(rpos, self.data.len());
+    /// Same as `get_u8_at()` but for signed values
+    pub fn get_i8_at(&self, index: usize) -> Result<i8> {
+        Ok(self.get_u8_at(index)? as i8)
     }
 
-    /// Return the writing cursor position
-    pub fn get_wpos(&self) -> usize {
-        self.wpos
+    /// Same as `read_u16()` but at `index`, without moving the reading cursor
+    pub fn get_u16_at(&self, index: usize) -> Result<u16> {
+        Ok(get_number!(self, read_u16, 2, index))
     }
 
-    /// Set the writing cursor position.
-    /// _Note_: Sets the writing cursor to `min(newPosition, self.len())` to prevent overflow
-    pub fn set_wpos(&mut self, wpos: usize) {
-        self.wpos = std::cmp::min(wpos, self.data.len());
+    /// Same as `get_u16_at()` but for signed values
+    pub fn get_i16_at(&self, index: usize) -> Result<i16> {
+        Ok(self.get_u16_at(index)? as i16)
     }
 
-    /// Return the raw byte buffer bytes.
-    pub fn as_bytes(&self) -> &[u8] {
-        &self.data
+    /// Same as `read_u32()` but at `index`, without moving the reading cursor
+    pub fn get_u32_at(&self, index: usize) -> Result<u32> {
+        Ok(get_number!(self, read_u32, 4, index))
     }
 
-    /// Return the raw byte buffer as a Vec<u8>.
-    #[deprecated(
-        since = "2.1.0",
-        note = "use `as_bytes().to_vec()` or `into_vec()` instead"
-    )]
-    pub fn into_bytes(&self) -> Vec<u8> {
-        self.data.to_vec()
+    /// Same as `get_u32_at()` but for signed values
+    pub fn get_i32_at(&self, index: usize) -> Result<i32> {
+        Ok(self.get_u32_at(index)? as i32)
     }
 
-    /// Return the raw byte buffer as a Vec<u8>.
-    pub fn into_vec(self) -> Vec<u8> {
-        self.data
+    /// Same as `read_u64()` but at `index`, without moving the reading cursor
+    pub fn get_u64_at(&self, index: usize) -> Result<u64> {
+        Ok(get_number!(self, read_u64, 8, index))
     }
 
-    //Bit manipulation functions
+    /// Same as `get_u64_at()` but for signed values
+    pub fn get_i64_at(&self, index: usize) -> Result<i64> {
+        Ok(self.get_u64_at(index)? as i64)
+    }
 
-    /// Read 1 bit. Return true if the bit is set to 1, otherwhise, return false.
-    ///
-    /// _Note_: Bits are read from left to right
+    /// Same as `read_u128()` but at `index`, without moving the reading cursor
+    pub fn get_u128_at(&self, index: usize) -> Result<u128> {
+        Ok(get_number!(self, read_u128, 16, index))
+    }
+
+    /// Same as `get_u128_at()` but for signed values
+    pub fn get_i128_at(&self, index: usize) -> Result<i128> {
+        Ok(self.get_u128_at(index)? as i128)
+    }
+
+    /// Same as `read_f32()` but at `index`, without moving the reading cursor
+    pub fn get_f32_at(&self, index: usize) -> Result<f32> {
+        Ok(get_number!(self, read_f32, 4, index))
+    }
+
+    /// Same as `read_f64()` but at `index`, without moving the reading cursor
+    pub fn get_f64_at(&self, index: usize) -> Result<f64> {
+        Ok(get_number!(self, read_f64, 8, index))
+    }
+
+    /// Overwrite one byte at `index`, without moving the cursors, or return an error if
+    /// `index` is out of bounds. The buffer is never extended.
     ///
     /// #Example
     ///
     /// ```
     /// #  use bytebuffer::*;
-    /// let mut buffer = ByteBuffer::from_bytes(&vec![128]); // 10000000b
-    /// let value1 = buffer.read_bit().unwrap(); //value1 contains true (eg: bit is 1)
-    /// let value2 = buffer.read_bit().unwrap(); //value2 contains false (eg: bit is 0)
+    /// let mut buffer = ByteBuffer::from_bytes(&[0x1, 0x2, 0x3]);
+    /// buffer.put_u8_at(1, 0xFF).unwrap(); // buffer contains [0x1, 0xFF, 0x3]
+    /// assert!(buffer.put_u16_at(2, 0x1).is_err());
     /// ```
-    pub fn read_bit(&mut self) -> Result<bool> {
-        if self.rpos >= self.data.len() {
-            return Err(Error::new(
-                ErrorKind::UnexpectedEof,
-                "could not read enough bits from buffer",
-            ));
+    pub fn put_u8_at(&mut self, index: usize, val: u8) -> Result<()> {
+        self.patch_bytes(index, &[val])
+    }
+
+    /// Same as `put_u8_at()` but for signed values
+    pub fn put_i8_at(&mut self, index: usize, val: i8) -> Result<()> {
+        self.put_u8_at(index, val as u8)
+    }
+
+    /// Same as `put_u8_at()` but for `u16` values, in the byte order of the buffer
+    pub fn put_u16_at(&mut self, index: usize, val: u16) -> Result<()> {
+        let mut buf = [0; 2];
+
+        match self.endian {
+            Endian::BigEndian => BigEndian::write_u16(&mut buf, val),
+            Endian::LittleEndian => LittleEndian::write_u16(&mut buf, val),
+        };
+
+        self.patch_bytes(index, &buf)
+    }
+
+    /// Same as `put_u16_at()` but for signed values
+    pub fn put_i16_at(&mut self, index: usize, val: i16) -> Result<()> {
+        self.put_u16_at(index, val as u16)
+    }
+
+    /// Same as `put_u8_at()` but for `u32` values, in the byte order of the buffer
+    pub fn put_u32_at(&mut self, index: usize, val: u32) -> Result<()> {
+        let mut buf = [0; 4];
+
+        match self.endian {
+            Endian::BigEndian => BigEndian::write_u32(&mut buf, val),
+            Endian::LittleEndian => LittleEndian::write_u32(&mut buf, val),
+        };
+
+        self.patch_bytes(index, &buf)
+    }
+
+    /// Same as `put_u32_at()` but for signed values
+    pub fn put_i32_at(&mut self, index: usize, val: i32) -> Result<()> {
+        self.put_u32_at(index, val as u32)
+    }
+
+    /// Same as `put_u8_at()` but for `u64` values, in the byte order of the buffer
+    pub fn put_u64_at(&mut self, index: usize, val: u64) -> Result<()> {
+        let mut buf = [0; 8];
+
+        match self.endian {
+            Endian::BigEndian => BigEndian::write_u64(&mut buf, val),
+            Endian::LittleEndian => LittleEndian::write_u64(&mut buf, val),
+        };
+
+        self.patch_bytes(index, &buf)
+    }
+
+    /// Same as `put_u64_at()` but for signed values
+    pub fn put_i64_at(&mut self, index: usize, val: i64) -> Result<()> {
+        self.put_u64_at(index, val as u64)
+    }
+
+    /// Same as `put_u8_at()` but for `u128` values, in the byte order of the buffer
+    pub fn put_u128_at(&mut self, index: usize, val: u128) -> Result<()> {
+        let mut buf = [0; 16];
+
+        match self.endian {
+            Endian::BigEndian => BigEndian::write_u128(&mut buf, val),
+            Endian::LittleEndian => LittleEndian::write_u128(&mut buf, val),
+        };
+
+        self.patch_bytes(index, &buf)
+    }
+
+    /// Same as `put_u128_at()` but for signed values
+    pub fn put_i128_at(&mut self, index: usize, val: i128) -> Result<()> {
+        self.put_u128_at(index, val as u128)
+    }
+
+    /// Same as `put_u8_at()` but for `f32` values, in the byte order of the buffer
+    pub fn put_f32_at(&mut self, index: usize, val: f32) -> Result<()> {
+        let mut buf = [0; 4];
+
+        match self.endian {
+            Endian::BigEndian => BigEndian::write_f32(&mut buf, val),
+            Endian::LittleEndian => LittleEndian::write_f32(&mut buf, val),
+        };
+
+        self.patch_bytes(index, &buf)
+    }
+
+    /// Same as `put_u8_at()` but for `f64` values, in the byte order of the buffer
+    pub fn put_f64_at(&mut self, index: usize, val: f64) -> Result<()> {
+        let mut buf = [0; 8];
+
+        match self.endian {
+            Endian::BigEndian => BigEndian::write_f64(&mut buf, val),
+            Endian::LittleEndian => LittleEndian::write_f64(&mut buf, val),
+        };
+
+        self.patch_bytes(index, &buf)
+    }
+
+    // Read operations
+
+    /// Read a defined amount of raw bytes, or return an error if not enough bytes are
+    /// available.
+    /// _Note_: This method resets the read and write cursor for bitwise reading.
+    pub fn read_bytes(&mut self, size: usize) -> Result<Vec<u8>> {
+        self.flush_bits();
+        if size > self.data.len().saturating_sub(self.rpos) {
+            return Err(Error::eof(size, self.rpos, self.data.len()));
+        }
+        let range = self.rpos..self.rpos + size;
+        let res = self.data[range].to_vec();
+        self.rpos += size;
+        Ok(res)
+    }
+
+    /// Read one byte, or return an error if not enough bytes are available.
+    /// _Note_: This method resets the read and write cursor for bitwise reading.
+    ///
+    /// #Example
+    ///
+    /// ```
+    /// #  use bytebuffer::*;
+    /// let mut buffer = ByteBuffer::from_bytes(&vec![0x1]);
+    /// let value = buffer.read_u8().unwrap(); //Value contains 1
+    /// ```
+    pub fn read_u8(&mut self) -> Result<u8> {
+        self.flush_bits();
+        if self.rpos >= self.data.len() {
+            return Err(Error::eof(1, self.rpos, self.data.len()));
+        }
+        let pos = self.rpos;
+        self.rpos += 1;
+        Ok(self.data[pos])
+    }
+
+    /// Same as `read_u8()` but for signed values
+    pub fn read_i8(&mut self) -> Result<i8> {
+        Ok(self.read_u8()? as i8)
+    }
+
+    /// Read a 2-bytes long value, or return an error if not enough bytes are available.
+    /// _Note_: This method resets the read and write cursor for bitwise reading.
+    ///
+    /// #Example
+    ///
+    /// ```
+    /// #  use bytebuffer::*;
+    /// let mut buffer = ByteBuffer::from_bytes(&vec![0x0, 0x1]);
+    /// let value = buffer.read_u16().unwrap(); //Value contains 1
+    /// ```
+    pub fn read_u16(&mut self) -> Result<u16> {
+        read_number!(self, read_u16, 2)
+    }
+
+    /// Same as `read_u16()` but for signed values
+    /// _Note_: This method resets the read and write cursor for bitwise reading.
+    pub fn read_i16(&mut self) -> Result<i16> {
+        Ok(self.read_u16()? as i16)
+    }
+
+    /// Read a four-bytes long value, or return an error if not enough bytes are available.
+    /// _Note_: This method resets the read and write cursor for bitwise reading.
+    ///
+    /// #Example
+    ///
+    /// ```
+    /// #  use bytebuffer::*;
+    /// let mut buffer = ByteBuffer::from_bytes(&vec![0x0, 0x0, 0x0, 0x1]);
+    /// let value = buffer.read_u32().unwrap(); // Value contains 1
+    /// ```
+    pub fn read_u32(&mut self) -> Result<u32> {
+        read_number!(self, read_u32, 4)
+    }
+
+    /// Same as `read_u32()` but for signed values
+    /// _Note_: This method resets the read and write cursor for bitwise reading.
+    pub fn read_i32(&mut self) -> Result<i32> {
+        Ok(self.read_u32()? as i32)
+    }
+
+    /// Read an eight bytes long value, or return an error if not enough bytes are available.
+    /// _Note_: This method resets the read and write cursor for bitwise reading.
+    ///
+    /// #Example
+    ///
+    /// ```
+    /// #  use bytebuffer::*;
+    /// let mut buffer = ByteBuffer::from_bytes(&vec![0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x1]);
+    /// let value = buffer.read_u64().unwrap(); //Value contains 1
+    /// ```
+    pub fn read_u64(&mut self) -> Result<u64> {
+        read_number!(self, read_u64, 8)
+    }
+
+    /// Same as `read_u64()` but for signed values
+    /// _Note_: This method resets the read and write cursor for bitwise reading.
+    pub fn read_i64(&mut self) -> Result<i64> {
+        Ok(self.read_u64()? as i64)
+    }
+
+    /// Read a sixteen bytes long value, or return an error if not enough bytes are available.
+    /// _Note_: This method resets the read and write cursor for bitwise reading.
+    ///
+    /// #Example
+    ///
+    /// ```
+    /// #  use bytebuffer::*;
+    /// let mut buffer = ByteBuffer::from_bytes(&vec![0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x1]);
+    /// let value = buffer.read_u128().unwrap(); //Value contains 1
+    /// ```
+    pub fn read_u128(&mut self) -> Result<u128> {
+        read_number!(self, read_u128, 16)
+    }
+
+    /// Same as `read_u128()` but for signed values
+    /// _Note_: This method resets the read and write cursor for bitwise reading.
+    pub fn read_i128(&mut self) -> Result<i128> {
+        Ok(self.read_u128()? as i128)
+    }
+
+    /// Read a 32 bits floating point value, or return an error if not enough bytes are available.
+    /// _Note_: This method resets the read and write cursor for bitwise reading.
+    pub fn read_f32(&mut self) -> Result<f32> {
+        read_number!(self, read_f32, 4)
+    }
+
+    /// Read a 64 bits floating point value, or return an error if not enough bytes are available.
+    /// _Note_: This method resets the read and write cursor for bitwise reading.
+    pub fn read_f64(&mut self) -> Result<f64> {
+        read_number!(self, read_f64, 8)
+    }
+
+    /// Read a string.
+    ///
+    /// _Note_: First it reads a 32 bits value representing the size, then 'size' raw bytes
+    ///         that  must be encoded as UTF8.
+    /// _Note_: This method resets the read and write cursor for bitwise reading.
+    pub fn read_string(&mut self) -> Result<String> {
+        self.read_prefixed_string(LengthPrefix::U32)
+    }
+
+    /// Read one byte without moving the reading cursor, or return an error if not enough
+    /// bytes are available.
+    /// _Note_: As with `read_u8()`, the byte is read after the bits being read, if any.
+    ///
+    /// #Example
+    ///
+    /// ```
+    /// #  use bytebuffer::*;
+    /// let mut buffer = ByteBuffer::from_bytes(&[0x1]);
+    /// let tag = buffer.peek_u8().unwrap(); // tag contains 1
+    /// assert_eq!(buffer.read_u8().unwrap(), tag);
+    /// ```
+    pub fn peek_u8(&self) -> Result<u8> {
+        let pos = self.byte_rpos();
+        if pos >= self.data.len() {
+            return Err(Error::eof(1, pos, self.data.len()));
+        }
+        Ok(self.data[pos])
+    }
+
+    /// Same as `peek_u8()` but for signed values
+    pub fn peek_i8(&self) -> Result<i8> {
+        Ok(self.peek_u8()? as i8)
+    }
+
+    /// Same as `read_u16()` but without moving the reading cursor
+    pub fn peek_u16(&self) -> Result<u16> {
+        Ok(peek_number!(self, read_u16, 2))
+    }
+
+    /// Same as `read_i16()` but without moving the reading cursor
+    pub fn peek_i16(&self) -> Result<i16> {
+        Ok(self.peek_u16()? as i16)
+    }
+
+    /// Same as `read_u32()` but without moving the reading cursor
+    pub fn peek_u32(&self) -> Result<u32> {
+        Ok(peek_number!(self, read_u32, 4))
+    }
+
+    /// Same as `read_i32()` but without moving the reading cursor
+    pub fn peek_i32(&self) -> Result<i32> {
+        Ok(self.peek_u32()? as i32)
+    }
+
+    /// Same as `read_u64()` but without moving the reading cursor
+    pub fn peek_u64(&self) -> Result<u64> {
+        Ok(peek_number!(self, read_u64, 8))
+    }
+
+    /// Same as `read_i64()` but without moving the reading cursor
+    pub fn peek_i64(&self) -> Result<i64> {
+        Ok(self.peek_u64()? as i64)
+    }
+
+    /// Same as `read_u128()` but without moving the reading cursor
+    pub fn peek_u128(&self) -> Result<u128> {
+        Ok(peek_number!(self, read_u128, 16))
+    }
+
+    /// Same as `read_i128()` but without moving the reading cursor
+    pub fn peek_i128(&self) -> Result<i128> {
+        Ok(self.peek_u128()? as i128)
+    }
+
+    /// Same as `read_f32()` but without moving the reading cursor
+    pub fn peek_f32(&self) -> Result<f32> {
+        Ok(peek_number!(self, read_f32, 4))
+    }
+
+    /// Same as `read_f64()` but without moving the reading cursor
+    pub fn peek_f64(&self) -> Result<f64> {
+        Ok(peek_number!(self, read_f64, 8))
+    }
+
+    /// Same as `read_bytes()` but without moving the reading cursor
+    pub fn peek_bytes(&self, size: usize) -> Result<Vec<u8>> {
+        let pos = self.byte_rpos();
+        if size > self.data.len().saturating_sub(pos) {
+            return Err(Error::eof(size, pos, self.data.len()));
+        }
+        Ok(self.data[pos..pos + size].to_vec())
+    }
+
+    /// Same as `read_string()` but without moving the reading cursor
+    ///
+    /// #Example
+    ///
+    /// ```
+    /// #  use bytebuffer::*;
+    /// let buffer = ByteBuffer::from_bytes(&[0x0, 0x0, 0x0, 0x2, 0x48, 0x69]);
+    /// let value = buffer.peek_string().unwrap(); // value contains "Hi"
+    /// assert_eq!(buffer.get_rpos(), 0);
+    /// ```
+    pub fn peek_string(&self) -> Result<String> {
+        let mut reader = ByteReader::from_bytes(&self.data);
+        reader.set_endian(self.endian);
+        reader.set_bit_rpos(self.get_bit_rpos());
+        reader.read_string()
+    }
+
+    /// Read an unsigned LEB128 variable-length value, or return an error if not enough bytes
+    /// are available or if the value does not fit in 32 bits.
+    /// _Note_: This method resets the read and write cursor for bitwise reading.
+    ///
+    /// #Example
+    ///
+    /// ```
+    /// #  use bytebuffer::*;
+    /// let mut buffer = ByteBuffer::from_bytes(&[0xAC, 0x02]);
+    /// let value = buffer.read_var_u32().unwrap(); // Value contains 300
+    /// ```
+    pub fn read_var_u32(&mut self) -> Result<u32> {
+        Ok(varint::read_unsigned(self, 32)? as u32)
+    }
+
+    /// Same as `read_var_u32()` but for 64 bits values
+    /// _Note_: This method resets the read and write cursor for bitwise reading.
+    pub fn read_var_u64(&mut self) -> Result<u64> {
+        Ok(varint::read_unsigned(self, 64)? as u64)
+    }
+
+    /// Same as `read_var_u32()` but for 128 bits values
+    /// _Note_: This method resets the read and write cursor for bitwise reading.
+    pub fn read_var_u128(&mut self) -> Result<u128> {
+        varint::read_unsigned(self, 128)
+    }
+
+    /// Read a signed LEB128 variable-length value, or return an error if not enough bytes
+    /// are available or if the value does not fit in 32 bits.
+    /// _Note_: This method resets the read and write cursor for bitwise reading.
+    pub fn read_var_i32(&mut self) -> Result<i32> {
+        Ok(varint::read_signed(self, 32)? as i32)
+    }
+
+    /// Same as `read_var_i32()` but for 64 bits values
+    /// _Note_: This method resets the read and write cursor for bitwise reading.
+    pub fn read_var_i64(&mut self) -> Result<i64> {
+        Ok(varint::read_signed(self, 64)? as i64)
+    }
+
+    /// Same as `read_var_i32()` but for 128 bits values
+    /// _Note_: This method resets the read and write cursor for bitwise reading.
+    pub fn read_var_i128(&mut self) -> Result<i128> {
+        varint::read_signed(self, 128)
+    }
+
+    /// Read a ZigZag encoded LEB128 variable-length value, or return an error if not enough
+    /// bytes are available or if the value does not fit in 32 bits.
+    /// _Note_: This method resets the read and write cursor for bitwise reading.
+    pub fn read_zigzag_i32(&mut self) -> Result<i32> {
+        Ok(varint::zigzag_decode(varint::read_unsigned(self, 32)?) as i32)
+    }
+
+    /// Same as `read_zigzag_i32()` but for 64 bits values
+    /// _Note_: This method resets the read and write cursor for bitwise reading.
+    pub fn read_zigzag_i64(&mut self) -> Result<i64> {
+        Ok(varint::zigzag_decode(varint::read_unsigned(self, 64)?) as i64)
+    }
+
+    /// Same as `read_zigzag_i32()` but for 128 bits values
+    /// _Note_: This method resets the read and write cursor for bitwise reading.
+    pub fn read_zigzag_i128(&mut self) -> Result<i128> {
+        Ok(varint::zigzag_decode(varint::read_unsigned(self, 128)?))
+    }
+
+    /// Read a byte array whose length is encoded as described by `prefix`, or return an
+    /// error if not enough bytes are available.
+    /// _Note_: With `Fixed(n)`, the `n` bytes are returned, padding included.
+    /// _Note_: This method resets the read and write cursor for bitwise reading.
+    ///
+    /// #Example
+    ///
+    /// ```
+    /// #  use bytebuffer::*;
+    /// let mut buffer = ByteBuffer::from_bytes(&[0x2, 0xFF, 0x45]);
+    /// let value = buffer.read_prefixed_bytes(LengthPrefix::U8).unwrap(); // value contains [0xFF, 0x45]
+    /// ```
+    pub fn read_prefixed_bytes(&mut self, prefix: LengthPrefix) -> Result<Vec<u8>> {
+        self.check_terminated(prefix)?;
+        Ok(prefix::read_prefixed(self, prefix)?.1)
+    }
+
+    /// Read a string whose length is encoded as described by `prefix`, or return an error if
+    /// not enough bytes are available or if they are not valid UTF8.
+    /// _Note_: With `Fixed(n)`, the trailing `0` padding bytes are removed.
+    /// _Note_: This method resets the read and write cursor for bitwise reading.
+    pub fn read_prefixed_string(&mut self, prefix: LengthPrefix) -> Result<String> {
+        self.check_terminated(prefix)?;
+        prefix::read_prefixed_string(self, prefix)
+    }
+
+    /// Reject `NulTerminated` data without its `0` byte before moving the reading cursor
+    fn check_terminated(&mut self, prefix: LengthPrefix) -> Result<()> {
+        ByteRead::flush_bits(self);
+        let unread = self.data.get(self.rpos..).unwrap_or_default();
+        prefix::check_terminated(prefix, unread.iter().copied(), self.data.len())
+    }
+
+    /// Return a reader over the next `len` bytes, with the same byte and bit order, and move
+    /// the reading cursor after them, or return an error if not enough bytes are available.
+    /// See `ByteReader::sub_reader()`.
+    /// _Note_: This method resets the read and write cursor for bitwise reading.
+    ///
+    /// #Example
+    ///
+    /// ```
+    /// #  use bytebuffer::*;
+    /// let mut buffer = ByteBuffer::from_bytes(&[0x0, 0x2, 0x1, 0x2, 0x3]);
+    /// let len = buffer.read_u16().unwrap();
+    /// let mut section = buffer.read_sub_reader(len as usize).unwrap();
+    /// let value = section.read_u8().unwrap(); // value contains 1
+    /// assert!(section.ensure_consumed().is_err()); // 0x2 was not read
+    /// assert_eq!(buffer.read_u8().unwrap(), 0x3);
+    /// ```
+    pub fn read_sub_reader(&mut self, len: usize) -> Result<ByteReader<'_>> {
+        self.flush_bits();
+        if len > self.data.len().saturating_sub(self.rpos) {
+            return Err(Error::eof(len, self.rpos, self.data.len()));
+        }
+        let range = self.rpos..self.rpos + len;
+        self.rpos += len;
+        let mut reader = ByteReader::from_bytes(&self.data[range]);
+        reader.set_endian(self.endian);
+        reader.set_bit_order(self.bit_order);
+        Ok(reader)
+    }
+
+    // Other
+
+    /// Dump the byte buffer to a string.
+    pub fn to_hex_dump(&self) -> String {
+        let mut str = String::new();
+        for b in &self.data {
+            str = str + &format!("0x{:01$x} ", b, 2);
+        }
+        str.pop();
+        str
+    }
+
+    /// Return the position of the reading cursor
+    pub fn get_rpos(&self) -> usize {
+        self.rpos
+    }
+
+    /// Set the reading cursor position.
+    /// _Note_: Sets the reading cursor to `min(newPosition, self.len())` to prevent overflow
+    pub fn set_rpos(&mut self, rpos: usize) {
+        self.rpos = core::cmp::min(rpos, self.data.len());
+    }
+
+    /// Return the position of the reading cursor in bits, counting from the start of the
+    /// data: `get_rpos() * 8` plus the bits already read in the current byte.
+    pub fn get_bit_rpos(&self) -> usize {
+        self.rpos * 8 + self.rbit
+    }
+
+    /// Set the reading cursor position in bits, counting from the start of the data.
+    /// _Note_: Sets the reading cursor to `min(newPosition, self.len() * 8)` to prevent overflow
+    ///
+    /// #Example
+    ///
+    /// ```
+    /// #  use bytebuffer::*;
+    /// let mut buffer = ByteBuffer::from_bytes(&[0xAB, 0xCD]);
+    /// buffer.set_bit_rpos(12);
+    /// assert_eq!(buffer.get_rpos(), 1);
+    /// let value = buffer.read_bits(4).unwrap(); // value contains 0xD
+    /// ```
+    pub fn set_bit_rpos(&mut self, pos: usize) {
+        let pos = core::cmp::min(pos, self.data.len() * 8);
+        self.rpos = pos / 8;
+        self.rbit = pos % 8;
+    }
+
+    /// Return the writing cursor position
+    pub fn get_wpos(&self) -> usize {
+        self.wpos
+    }
+
+    /// Set the writing cursor position.
+    /// _Note_: Sets the writing cursor to `min(newPosition, self.len())` to prevent overflow
+    pub fn set_wpos(&mut self, wpos: usize) {
+        self.wpos = core::cmp::min(wpos, self.data.len());
+    }
+
+    /// Return the position of the writing cursor in bits, counting from the start of the
+    /// data: `get_wpos() * 8` plus the bits already written in the current byte.
+    pub fn get_bit_wpos(&self) -> usize {
+        self.wpos * 8 + self.wbit
+    }
+
+    /// Set the writing cursor position in bits, counting from the start of the data.
+    /// _Note_: Sets the writing cursor to `min(newPosition, self.len() * 8)` to prevent overflow
+    pub fn set_bit_wpos(&mut self, pos: usize) {
+        let pos = core::cmp::min(pos, self.data.len() * 8);
+        self.wpos = pos / 8;
+        self.wbit = pos % 8;
+    }
+
+    /// Save the reading and writing cursors, with their bit cursors, and the byte order, to
+    /// go back to them later with `restore()`.
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            rpos: self.rpos,
+            rbit: self.rbit,
+            wpos: self.wpos,
+            wbit: self.wbit,
+            endian: self.endian,
         }
-        let bit = self.data[self.rpos] & (1 << (7 - self.rbit)) != 0;
+    }
+
+    /// Move the cursors back to a `checkpoint`, and restore its byte order.
+    /// _Note_: Cursors are limited to `self.len()` as with `set_rpos()` and `set_wpos()`
+    pub fn restore(&mut self, checkpoint: Checkpoint) {
+        self.set_bit_rpos(checkpoint.rpos * 8 + checkpoint.rbit);
+        self.set_bit_wpos(checkpoint.wpos * 8 + checkpoint.wbit);
+        self.endian = checkpoint.endian;
+    }
+
+    /// Return a guard giving access to the buffer, which restores the current checkpoint when
+    /// dropped, unless `CheckpointGuard::commit()` is called.
+    ///
+    /// #Example
+    ///
+    /// ```
+    /// #  use bytebuffer::*;
+    /// let mut buffer = ByteBuffer::from_bytes(&[0x1, 0x2]);
+    /// {
+    ///     let mut guard = buffer.checkpoint_guard();
+    ///     let value = guard.read_u16().unwrap();
+    ///     if value != 0x0102 {
+    ///         guard.commit();
+    ///     }
+    /// }
+    /// // value contains 0x0102, so the guard restored the cursor when it was dropped
+    /// assert_eq!(buffer.get_rpos(), 0);
+    /// ```
+    pub fn checkpoint_guard(&mut self) -> CheckpointGuard<'_, Self> {
+        let checkpoint = self.checkpoint();
+        CheckpointGuard::new(self, checkpoint, Self::restore)
+    }
+
+    /// Return the raw byte buffer bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Return the raw byte buffer as a Vec<u8>.
+    #[deprecated(
+        since = "2.1.0",
+        note = "use `as_bytes().to_vec()` or `into_vec()` instead"
+    )]
+    pub fn into_bytes(&self) -> Vec<u8> {
+        self.data.to_vec()
+    }
+
+    /// Return the raw byte buffer as a Vec<u8>.
+    pub fn into_vec(self) -> Vec<u8> {
+        self.data
+    }
+
+    /// Run `decode` as a transaction on the buffer. If it fails, the reading cursor is moved
+    /// back to where it was, so that the decode can be retried from the start. Running out of
+    /// data is reported as `Error::Incomplete`, with the number of bytes to append before
+    /// retrying, instead of `Error::UnexpectedEof`.
+    ///
+    /// _Note_: Running out of data in a reader returned by `read_sub_reader()` is still
+    /// reported as `Error::UnexpectedEof`, since appending data cannot fix it.
+    ///
+    /// #Example
+    ///
+    /// ```
+    /// #  use bytebuffer::*;
+    /// let mut buffer = ByteBuffer::from_bytes(&[0x0, 0x0, 0x0, 0x2, b'h']);
+    /// let error = buffer.try_decode(|b| b.read_string()).unwrap_err();
+    /// assert_eq!(error, Error::Incomplete { needed: 1 });
+    /// assert_eq!(buffer.get_rpos(), 0);
+    ///
+    /// buffer.write_u8(b'i');
+    /// assert_eq!(buffer.try_decode(|b| b.read_string()).unwrap(), "hi");
+    /// ```
+    pub fn try_decode<T>(&mut self, decode: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+        let pos = self.get_bit_rpos();
+        decode(self).map_err(|error| {
+            self.set_bit_rpos(pos);
+            match error {
+                // Only the end of this buffer can be fixed by appending data, not the end of a
+                // sub-reader
+                Error::UnexpectedEof {
+                    needed,
+                    available,
+                    offset,
+                } if offset >= pos / 8 && offset + available == self.data.len() => {
+                    Error::Incomplete {
+                        needed: needed.saturating_sub(available),
+                    }
+                }
+                error => error,
+            }
+        })
+    }
+
+    //Bit manipulation functions
+
+    /// Read 1 bit. Return true if the bit is set to 1, otherwhise, return false.
+    ///
+    /// _Note_: Bits are read from left to right, or from right to left with `BitOrder::LsbFirst`
+    ///
+    /// #Example
+    ///
+    /// ```
+    /// #  use bytebuffer::*;
+    /// let mut buffer = ByteBuffer::from_bytes(&vec![128]); // 10000000b
+    /// let value1 = buffer.read_bit().unwrap(); //value1 contains true (eg: bit is 1)
+    /// let value2 = buffer.read_bit().unwrap(); //value2 contains false (eg: bit is 0)
+    /// ```
+    pub fn read_bit(&mut self) -> Result<bool> {
+        if self.rpos >= self.data.len() {
+            return Err(Error::eof(1, self.rpos, self.data.len()));
+        }
+        let bit = self.data[self.rpos] & self.bit_order.mask(self.rbit) != 0;
         self.rbit += 1;
         if self.rbit > 7 {
             self.flush_rbits();
         }
-        Ok(bit)
+        Ok(bit)
+    }
+
+    /// Read n bits. an return the corresponding value an u64.
+    ///
+    /// _Note_: We cannot read more than 64 bits
+    ///
+    /// _Note_: Bits are read from left to right, or from right to left with `BitOrder::LsbFirst`
+    ///
+    /// #Example
+    ///
+    /// ```
+    /// #  use bytebuffer::*;
+    /// let mut buffer = ByteBuffer::from_bytes(&vec![128]); // 10000000b
+    /// let value = buffer.read_bits(3).unwrap(); // value contains 4 (eg: 100b)
+    /// ```
+    #[inline]
+    pub fn read_bits(&mut self, n: u8) -> Result<u64> {
+        if n > 64 {
+            return Err(Error::TooManyBits {
+                requested: n as usize,
+                max: 64,
+            });
+        }
+        Ok(self.read_bits_raw(n.into())? as u64)
+    }
+
+    /// Read n bits. an return the corresponding value an u128.
+    ///
+    /// _Note_: We cannot read more than 128 bits
+    ///
+    /// _Note_: Bits are read from left to right, or from right to left with `BitOrder::LsbFirst`
+    ///
+    /// #Example
+    ///
+    /// ```
+    /// #  use bytebuffer::*;
+    /// let mut buffer = ByteBuffer::from_bytes(&[0xFF; 10]);
+    /// buffer.read_bit().unwrap();
+    /// let value = buffer.read_bits_u128(72).unwrap(); // value contains 2^72 - 1
+    /// ```
+    #[inline]
+    pub fn read_bits_u128(&mut self, n: u8) -> Result<u128> {
+        if n > 128 {
+            return Err(Error::TooManyBits {
+                requested: n as usize,
+                max: 128,
+            });
+        }
+        self.read_bits_raw(n.into())
     }
 
-    /// Read n bits. an return the corresponding value an u64.
+    /// Read n bits as a two's complement value and return it with its sign extended, or
+    /// return an error if not enough bits are available.
     ///
     /// _Note_: We cannot read more than 64 bits
     ///
-    /// _Note_: Bits are read from left to right
+    /// #Example
+    ///
+    /// ```
+    /// #  use bytebuffer::*;
+    /// let mut buffer = ByteBuffer::from_bytes(&[0xC0]); // 11000000b
+    /// let value = buffer.read_bits_signed(3).unwrap(); // value contains -2 (eg: 110b)
+    /// ```
+    pub fn read_bits_signed(&mut self, n: u8) -> Result<i64> {
+        bits::read_signed(self, n)
+    }
+
+    /// Read a unary value: a run of 0 bits terminated by a 1 bit, and return the number of 0
+    /// bits. Return an error if the run is longer than `u32::MAX` bits or reaches the end of
+    /// the data.
     ///
     /// #Example
     ///
     /// ```
     /// #  use bytebuffer::*;
-    /// let mut buffer = ByteBuffer::from_bytes(&vec![128]); // 10000000b
-    /// let value = buffer.read_bits(3).unwrap(); // value contains 4 (eg: 100b)
+    /// let mut buffer = ByteBuffer::from_bytes(&[0x12]); // 00010010b
+    /// let value = buffer.read_unary().unwrap(); // value contains 3
     /// ```
-    pub fn read_bits(&mut self, n: u8) -> Result<u64> {
-        if n > 64 {
-            return Err(Error::new(
-                ErrorKind::InvalidInput,
-                "cannot read more than 64 bits",
+    pub fn read_unary(&mut self) -> Result<u32> {
+        Ok(golomb::read_unary(self, u32::MAX.into())? as u32)
+    }
+
+    /// Read an unsigned Exponential-Golomb value, `ue(v)` in H.264: `z` 0 bits, then the value
+    /// plus one in `z + 1` bits. Return an error if the code has more than 64 leading 0 bits,
+    /// so that long runs of 0 in malformed data are rejected early.
+    ///
+    /// #Example
+    ///
+    /// ```
+    /// #  use bytebuffer::*;
+    /// let mut buffer = ByteBuffer::from_bytes(&[0x20]); // 00100000b
+    /// let value = buffer.read_exp_golomb().unwrap(); // value contains 3
+    /// ```
+    pub fn read_exp_golomb(&mut self) -> Result<u64> {
+        golomb::read_unsigned(self)
+    }
+
+    /// Read a signed Exponential-Golomb value, `se(v)` in H.264: the code numbers 1, 2, 3,
+    /// 4... map to 1, -1, 2, -2...
+    ///
+    /// #Example
+    ///
+    /// ```
+    /// #  use bytebuffer::*;
+    /// let mut buffer = ByteBuffer::from_bytes(&[0x60]); // 01100000b
+    /// let value = buffer.read_signed_exp_golomb().unwrap(); // value contains -1
+    /// ```
+    pub fn read_signed_exp_golomb(&mut self) -> Result<i64> {
+        golomb::read_signed(self)
+    }
+
+    /// Read a Rice value with parameter `k`: a unary quotient followed by a `k` bits
+    /// remainder. Return an error if `k` is greater than 64, or if the quotient does not fit
+    /// in the result.
+    ///
+    /// #Example
+    ///
+    /// ```
+    /// #  use bytebuffer::*;
+    /// let mut buffer = ByteBuffer::from_bytes(&[0x2C]); // 00101100b
+    /// let value = buffer.read_rice(2).unwrap(); // value contains 9 (eg: 2 << 2 | 01b)
+    /// ```
+    pub fn read_rice(&mut self, k: u8) -> Result<u64> {
+        golomb::read_rice(self, k)
+    }
+
+    /// Return the number of bits left to read after the reading cursor.
+    pub fn remaining_bits(&self) -> usize {
+        bits::available(self.data.len(), self.rpos, self.rbit)
+    }
+
+    /// Move the reading cursor n bits forward, or return an error, without moving it, if not
+    /// enough bits are available.
+    ///
+    /// #Example
+    ///
+    /// ```
+    /// #  use bytebuffer::*;
+    /// let mut buffer = ByteBuffer::from_bytes(&[0xAB, 0xCD]);
+    /// buffer.skip_bits(4).unwrap();
+    /// let value = buffer.read_bits(8).unwrap(); // value contains 0xBC
+    /// assert!(buffer.skip_bits(5).is_err());
+    /// ```
+    pub fn skip_bits(&mut self, n: usize) -> Result<()> {
+        if n > self.remaining_bits() {
+            return Err(Error::eof(
+                self.rbit.saturating_add(n).div_ceil(8),
+                self.rpos,
+                self.data.len(),
             ));
         }
+        self.set_bit_rpos(self.get_bit_rpos() + n);
+        Ok(())
+    }
 
-        if n == 0 {
-            Ok(0)
-        } else {
-            Ok((u64::from(self.read_bit()?) << (n - 1)) | self.read_bits(n - 1)?)
+    /// Read n bits, up to 64, without moving the reading cursor.
+    ///
+    /// #Example
+    ///
+    /// ```
+    /// #  use bytebuffer::*;
+    /// let mut buffer = ByteBuffer::from_bytes(&[0xAB, 0xCD]);
+    /// let value = buffer.peek_bits(4).unwrap(); // value contains 0xA
+    /// assert_eq!(buffer.read_bits(4).unwrap(), value);
+    /// ```
+    pub fn peek_bits(&self, n: u8) -> Result<u64> {
+        if n > 64 {
+            return Err(Error::TooManyBits {
+                requested: n as usize,
+                max: 64,
+            });
+        }
+        Ok(self.get_bits_raw(n.into())? as u64)
+    }
+
+    /// Read n bits at the reading cursor, without moving it.
+    #[inline]
+    fn get_bits_raw(&self, n: u32) -> Result<u128> {
+        if n as usize > bits::available(self.data.len(), self.rpos, self.rbit) {
+            return Err(Error::eof(
+                bits::span(self.rbit, n),
+                self.rpos,
+                self.data.len(),
+            ));
         }
+        Ok(bits::read(
+            &self.data,
+            self.rpos,
+            self.rbit,
+            n,
+            self.bit_order,
+        ))
+    }
+
+    #[inline]
+    fn read_bits_raw(&mut self, n: u32) -> Result<u128> {
+        let value = self.get_bits_raw(n)?;
+        let end = self.rbit + n as usize;
+        self.rpos += end / 8;
+        self.rbit = end % 8;
+        Ok(value)
     }
 
     /// Discard all the pending bits available for reading or writing and place the corresponding cursor to the next byte.
@@ -704,13 +1866,13 @@ impl ByteBuffer {
     /// ...| XXXXXXXX | 10000000 |....
     /// ```
     pub fn write_bit(&mut self, bit: bool) {
-        let size = self.wpos + 1;
-        if size > self.data.len() {
-            self.resize(size);
-        }
+        self.reserve_write(1);
 
+        let mask = self.bit_order.mask(self.wbit);
         if bit {
-            self.data[self.wpos] |= 1 << (7 - self.wbit);
+            self.data[self.wpos] |= mask;
+        } else {
+            self.data[self.wpos] &= !mask;
         }
 
         self.wbit += 1;
@@ -723,6 +1885,8 @@ impl ByteBuffer {
 
     /// Write the given value as a sequence of n bits
     ///
+    /// _Note_: We cannot write more than 64 bits, this method panics if `n` is greater
+    ///
     /// #Example
     ///
     /// ```
@@ -730,27 +1894,131 @@ impl ByteBuffer {
     /// let mut buffer = ByteBuffer::new();
     /// buffer.write_bits(4, 3); // append 100b
     /// ```
+    #[inline]
     pub fn write_bits(&mut self, value: u64, n: u8) {
-        if n > 0 {
-            self.write_bit((value >> (n - 1)) & 1 != 0);
-            self.write_bits(value, n - 1);
-        }
+        assert!(n <= 64, "cannot write more than 64 bits at once");
+        self.write_bits_raw(value.into(), n.into());
+    }
+
+    /// Write the given value as a sequence of n bits
+    ///
+    /// _Note_: We cannot write more than 128 bits, this method panics if `n` is greater
+    ///
+    /// #Example
+    ///
+    /// ```
+    /// #  use bytebuffer::*;
+    /// let mut buffer = ByteBuffer::new();
+    /// buffer.write_bits_u128(u128::MAX, 72); // append 72 bits set to 1
+    /// assert_eq!(buffer.len(), 9);
+    /// ```
+    #[inline]
+    pub fn write_bits_u128(&mut self, value: u128, n: u8) {
+        assert!(n <= 128, "cannot write more than 128 bits at once");
+        self.write_bits_raw(value, n.into());
+    }
+
+    /// Write the given value as a two's complement sequence of n bits. Return an error,
+    /// without writing anything, if the value does not fit in n bits.
+    ///
+    /// _Note_: We cannot write more than 64 bits
+    ///
+    /// #Example
+    ///
+    /// ```
+    /// #  use bytebuffer::*;
+    /// let mut buffer = ByteBuffer::new();
+    /// buffer.write_bits_signed(-2, 3).unwrap(); // append 110b
+    /// assert!(buffer.write_bits_signed(4, 3).is_err());
+    /// ```
+    pub fn write_bits_signed(&mut self, value: i64, n: u8) -> Result<()> {
+        bits::write_signed(self, value, n)
+    }
+
+    /// Append a unary value: `n` 0 bits followed by a 1 bit.
+    ///
+    /// #Example
+    ///
+    /// ```
+    /// #  use bytebuffer::*;
+    /// let mut buffer = ByteBuffer::new();
+    /// buffer.write_unary(3); // append 0001b
+    /// ```
+    pub fn write_unary(&mut self, n: u32) {
+        golomb::write_unary(self, n.into()).expect("writing bits to a ByteBuffer cannot fail");
+    }
+
+    /// Append an unsigned Exponential-Golomb value, `ue(v)` in H.264: `z` 0 bits, then the
+    /// value plus one in `z + 1` bits.
+    ///
+    /// #Example
+    ///
+    /// ```
+    /// #  use bytebuffer::*;
+    /// let mut buffer = ByteBuffer::new();
+    /// buffer.write_exp_golomb(3); // append 00100b
+    /// ```
+    pub fn write_exp_golomb(&mut self, val: u64) {
+        golomb::write_unsigned(self, val).expect("writing bits to a ByteBuffer cannot fail");
+    }
+
+    /// Append a signed Exponential-Golomb value, `se(v)` in H.264: 1, -1, 2, -2... are
+    /// written as the code numbers 1, 2, 3, 4...
+    ///
+    /// #Example
+    ///
+    /// ```
+    /// #  use bytebuffer::*;
+    /// let mut buffer = ByteBuffer::new();
+    /// buffer.write_signed_exp_golomb(-1); // append 011b
+    /// ```
+    pub fn write_signed_exp_golomb(&mut self, val: i64) {
+        golomb::write_signed(self, val).expect("writing bits to a ByteBuffer cannot fail");
+    }
+
+    /// Append a Rice value with parameter `k`: the quotient `val >> k` as a unary value,
+    /// followed by the `k` low bits of `val`. Return an error, without writing anything, if
+    /// `k` is greater than 64 or if the quotient is larger than `u32::MAX`.
+    ///
+    /// #Example
+    ///
+    /// ```
+    /// #  use bytebuffer::*;
+    /// let mut buffer = ByteBuffer::new();
+    /// buffer.write_rice(9, 2).unwrap(); // append 00101b
+    /// assert!(buffer.write_rice(u64::MAX, 0).is_err());
+    /// ```
+    pub fn write_rice(&mut self, val: u64, k: u8) -> Result<()> {
+        golomb::write_rice(self, val, k)
+    }
+
+    #[inline]
+    fn write_bits_raw(&mut self, value: u128, n: u32) {
+        self.reserve_write(bits::span(self.wbit, n));
+        bits::write(
+            &mut self.data,
+            self.wpos,
+            self.wbit,
+            value,
+            n,
+            self.bit_order,
+        );
+        let end = self.wbit + n as usize;
+        self.wpos += end / 8;
+        self.wbit = end % 8;
     }
 }
 
 #[cfg(feature = "half")]
 impl ByteBuffer {
-    /// Read a 16 bits floating point value, or return an IO error if not enough bytes are available.
+    /// Read a 16 bits floating point value, or return an error if not enough bytes are available.
     /// _Note_: This method resets the read and write cursor for bitwise reading.
     pub fn read_f16(&mut self) -> Result<half::f16> {
         let offset = 2;
 
         self.flush_bits();
         if self.rpos + offset > self.data.len() {
-            return Err(Error::new(
-                ErrorKind::UnexpectedEof,
-                "could not read enough bits from buffer",
-            ));
+            return Err(Error::eof(offset, self.rpos, self.data.len()));
         }
         let range = self.rpos..self.rpos + offset;
         self.rpos += offset;
@@ -782,17 +2050,14 @@ impl ByteBuffer {
         };
     }
 
-    /// Read a truncated 16 bits floating point value, or return an IO error if not enough bytes are available.
+    /// Read a truncated 16 bits floating point value, or return an error if not enough bytes are available.
     /// _Note_: This method resets the read and write cursor for bitwise reading.
     pub fn read_bf16(&mut self) -> Result<half::bf16> {
         let offset = 2;
 
         self.flush_bits();
         if self.rpos + offset > self.data.len() {
-            return Err(Error::new(
-                ErrorKind::UnexpectedEof,
-                "could not read enough bits from buffer",
-            ));
+            return Err(Error::eof(offset, self.rpos, self.data.len()));
         }
         let range = self.rpos..self.rpos + offset;
         self.rpos += offset;
@@ -824,3 +2089,237 @@ impl ByteBuffer {
         };
     }
 }
+
+impl ByteRead for ByteBuffer {
+    fn endian(&self) -> Endian {
+        self.endian
+    }
+
+    fn set_endian(&mut self, endian: Endian) {
+        self.set_endian(endian)
+    }
+
+    fn get_rpos(&self) -> usize {
+        self.rpos
+    }
+
+    fn read_bytes(&mut self, size: usize) -> Result<Vec<u8>> {
+        self.read_bytes(size)
+    }
+
+    fn read_u8(&mut self) -> Result<u8> {
+        self.read_u8()
+    }
+
+    fn read_i8(&mut self) -> Result<i8> {
+        self.read_i8()
+    }
+
+    fn read_u16(&mut self) -> Result<u16> {
+        self.read_u16()
+    }
+
+    fn read_i16(&mut self) -> Result<i16> {
+        self.read_i16()
+    }
+
+    fn read_u32(&mut self) -> Result<u32> {
+        self.read_u32()
+    }
+
+    fn read_i32(&mut self) -> Result<i32> {
+        self.read_i32()
+    }
+
+    fn read_u64(&mut self) -> Result<u64> {
+        self.read_u64()
+    }
+
+    fn read_i64(&mut self) -> Result<i64> {
+        self.read_i64()
+    }
+
+    fn read_u128(&mut self) -> Result<u128> {
+        self.read_u128()
+    }
+
+    fn read_i128(&mut self) -> Result<i128> {
+        self.read_i128()
+    }
+
+    fn read_f32(&mut self) -> Result<f32> {
+        self.read_f32()
+    }
+
+    fn read_f64(&mut self) -> Result<f64> {
+        self.read_f64()
+    }
+
+    #[cfg(feature = "half")]
+    fn read_f16(&mut self) -> Result<half::f16> {
+        self.read_f16()
+    }
+
+    #[cfg(feature = "half")]
+    fn read_bf16(&mut self) -> Result<half::bf16> {
+        self.read_bf16()
+    }
+
+    fn read_string(&mut self) -> Result<String> {
+        self.read_string()
+    }
+
+    fn read_prefixed_bytes(&mut self, prefix: LengthPrefix) -> Result<Vec<u8>> {
+        self.read_prefixed_bytes(prefix)
+    }
+
+    fn read_prefixed_string(&mut self, prefix: LengthPrefix) -> Result<String> {
+        self.read_prefixed_string(prefix)
+    }
+
+    fn read_bit(&mut self) -> Result<bool> {
+        self.read_bit()
+    }
+
+    fn read_bits(&mut self, n: u8) -> Result<u64> {
+        self.read_bits(n)
+    }
+
+    fn read_bits_u128(&mut self, n: u8) -> Result<u128> {
+        self.read_bits_u128(n)
+    }
+
+    fn flush_bits(&mut self) {
+        if self.rbit > 0 {
+            self.flush_rbits();
+        }
+    }
+}
+
+impl ByteWrite for ByteBuffer {
+    fn endian(&self) -> Endian {
+        self.endian
+    }
+
+    fn set_endian(&mut self, endian: Endian) {
+        self.set_endian(endian)
+    }
+
+    fn get_wpos(&self) -> usize {
+        self.wpos
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<()> {
+        self.write_bytes(bytes);
+        Ok(())
+    }
+
+    fn write_u8(&mut self, val: u8) -> Result<()> {
+        self.write_u8(val);
+        Ok(())
+    }
+
+    fn write_i8(&mut self, val: i8) -> Result<()> {
+        self.write_i8(val);
+        Ok(())
+    }
+
+    fn write_u16(&mut self, val: u16) -> Result<()> {
+        self.write_u16(val);
+        Ok(())
+    }
+
+    fn write_i16(&mut self, val: i16) -> Result<()> {
+        self.write_i16(val);
+        Ok(())
+    }
+
+    fn write_u32(&mut self, val: u32) -> Result<()> {
+        self.write_u32(val);
+        Ok(())
+    }
+
+    fn write_i32(&mut self, val: i32) -> Result<()> {
+        self.write_i32(val);
+        Ok(())
+    }
+
+    fn write_u64(&mut self, val: u64) -> Result<()> {
+        self.write_u64(val);
+        Ok(())
+    }
+
+    fn write_i64(&mut self, val: i64) -> Result<()> {
+        self.write_i64(val);
+        Ok(())
+    }
+
+    fn write_u128(&mut self, val: u128) -> Result<()> {
+        self.write_u128(val);
+        Ok(())
+    }
+
+    fn write_i128(&mut self, val: i128) -> Result<()> {
+        self.write_i128(val);
+        Ok(())
+    }
+
+    fn write_f32(&mut self, val: f32) -> Result<()> {
+        self.write_f32(val);
+        Ok(())
+    }
+
+    fn write_f64(&mut self, val: f64) -> Result<()> {
+        self.write_f64(val);
+        Ok(())
+    }
+
+    #[cfg(feature = "half")]
+    fn write_f16(&mut self, val: half::f16) -> Result<()> {
+        self.write_f16(val);
+        Ok(())
+    }
+
+    #[cfg(feature = "half")]
+    fn write_bf16(&mut self, val: half::bf16) -> Result<()> {
+        self.write_bf16(val);
+        Ok(())
+    }
+
+    fn write_string(&mut self, val: &str) -> Result<()> {
+        self.write_prefixed_string(val, LengthPrefix::U32)
+    }
+
+    fn write_bit(&mut self, bit: bool) -> Result<()> {
+        self.write_bit(bit);
+        Ok(())
+    }
+
+    fn write_bits(&mut self, value: u64, n: u8) -> Result<()> {
+        if n > 64 {
+            return Err(Error::TooManyBits {
+                requested: n as usize,
+                max: 64,
+            });
+        }
+        self.write_bits(value, n);
+        Ok(())
+    }
+
+    fn write_bits_u128(&mut self, value: u128, n: u8) -> Result<()> {
+        if n > 128 {
+            return Err(Error::TooManyBits {
+                requested: n as usize,
+                max: 128,
+            });
+        }
+        self.write_bits_u128(value, n);
+        Ok(())
+    }
+
+    fn flush_bits(&mut self) {
+        if self.wbit > 0 {
+            self.flush_wbits();
+        }
+    }
+}
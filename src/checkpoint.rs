@@ -0,0 +1,112 @@
+use crate::Endian;
+use core::ops::{Deref, DerefMut};
+
+/// A saved state of the cursors of a `ByteBuffer` or a `ByteReader`, including the bit
+/// cursors and the byte order, to go back to it later with `restore()`.
+///
+/// #Example
+///
+/// ```
+/// #  use bytebuffer::*;
+/// let mut buffer = ByteBuffer::from_bytes(&[0x1, 0x2]);
+/// let checkpoint = buffer.checkpoint();
+/// buffer.read_bit().unwrap();
+/// buffer.set_endian(Endian::LittleEndian);
+/// buffer.restore(checkpoint);
+/// assert_eq!(buffer.read_u16().unwrap(), 0x0102);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Checkpoint {
+    pub(crate) rpos: usize,
+    pub(crate) rbit: usize,
+    pub(crate) wpos: usize,
+    pub(crate) wbit: usize,
+    pub(crate) endian: Endian,
+}
+
+impl Checkpoint {
+    /// Return the saved reading cursor position
+    pub fn rpos(&self) -> usize {
+        self.rpos
+    }
+
+    /// Return the saved writing cursor position
+    pub fn wpos(&self) -> usize {
+        self.wpos
+    }
+}
+
+/// A guard returned by `checkpoint_guard()`, giving access to the buffer or reader, and
+/// restoring its checkpoint when dropped unless `commit()` is called.
+///
+/// #Example
+///
+/// ```
+/// #  use bytebuffer::*;
+/// let mut buffer = ByteBuffer::from_bytes(&[0x1, 0x2, 0x3]);
+/// {
+///     let mut guard = buffer.checkpoint_guard();
+///     guard.read_u16().unwrap();
+///     // Dropped without commit: the cursors go back
+/// }
+/// assert_eq!(buffer.get_rpos(), 0);
+///
+/// let mut guard = buffer.checkpoint_guard();
+/// guard.read_u16().unwrap();
+/// guard.commit();
+/// assert_eq!(buffer.get_rpos(), 2);
+/// ```
+#[derive(Debug)]
+pub struct CheckpointGuard<'a, T> {
+    target: &'a mut T,
+    checkpoint: Checkpoint,
+    restore: fn(&mut T, Checkpoint),
+    committed: bool,
+}
+
+impl<'a, T> CheckpointGuard<'a, T> {
+    pub(crate) fn new(
+        target: &'a mut T,
+        checkpoint: Checkpoint,
+        restore: fn(&mut T, Checkpoint),
+    ) -> Self {
+        CheckpointGuard {
+            target,
+            checkpoint,
+            restore,
+            committed: false,
+        }
+    }
+
+    /// Return the checkpoint restored on drop
+    pub fn checkpoint(&self) -> Checkpoint {
+        self.checkpoint
+    }
+
+    /// Keep the current state of the cursors, instead of restoring the checkpoint.
+    pub fn commit(mut self) {
+        self.committed = true;
+    }
+}
+
+impl<T> Deref for CheckpointGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.target
+    }
+}
+
+impl<T> DerefMut for CheckpointGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.target
+    }
+}
+
+impl<T> Drop for CheckpointGuard<'_, T> {
+    fn drop(&mut self) {
+        if !self.committed {
+            (self.restore)(self.target, self.checkpoint);
+        }
+    }
+}
@@ -0,0 +1,329 @@
+//! Traits for types that know how to encode and decode themselves, usually implemented with
+//! `#[derive(ByteEncode, ByteDecode)]`.
+
+use crate::{ByteRead, ByteWrite, Error, Result};
+#[cfg(feature = "alloc")]
+use {
+    crate::LengthPrefix,
+    alloc::{string::String, vec::Vec},
+};
+
+/// A type that can be written to any `ByteWrite` implementation.
+///
+/// #Example
+///
+/// ```
+/// # use bytebuffer::*;
+/// struct Point {
+///     x: u16,
+///     y: u16,
+/// }
+///
+/// impl ByteEncode for Point {
+///     fn encode<W: ByteWrite + ?Sized>(&self, writer: &mut W) -> Result<()> {
+///         self.x.encode(writer)?;
+///         self.y.encode(writer)
+///     }
+/// }
+///
+/// let mut buffer = ByteBuffer::new();
+/// Point { x: 1, y: 2 }.encode(&mut buffer).unwrap();
+/// assert_eq!(buffer.as_bytes(), [0x0, 0x1, 0x0, 0x2]);
+/// ```
+pub trait ByteEncode {
+    /// Write the value with the byte order of `writer`.
+    fn encode<W: ByteWrite + ?Sized>(&self, writer: &mut W) -> Result<()>;
+}
+
+/// A type that can be read from any `ByteRead` implementation.
+///
+/// #Example
+///
+/// ```
+/// # use bytebuffer::*;
+/// #[derive(Debug, PartialEq)]
+/// struct Point {
+///     x: u16,
+///     y: u16,
+/// }
+///
+/// impl ByteDecode for Point {
+///     fn decode<R: ByteRead + ?Sized>(reader: &mut R) -> Result<Self> {
+///         Ok(Point {
+///             x: u16::decode(reader)?,
+///             y: u16::decode(reader)?,
+///         })
+///     }
+/// }
+///
+/// let data = [0x0, 0x1, 0x0, 0x2];
+/// let point = Point::decode(&mut ByteReader::from_bytes(&data)).unwrap();
+/// assert_eq!(point, Point { x: 1, y: 2 });
+/// ```
+pub trait ByteDecode: Sized {
+    /// Read a value with the byte order of `reader`.
+    fn decode<R: ByteRead + ?Sized>(reader: &mut R) -> Result<Self>;
+}
+
+/// A value that can be stored in `n` bits, used by the `#[bytebuffer(bits = n)]` attribute.
+pub trait BitField: Sized {
+    /// Write the value in `n` bits, or return an error if it does not fit.
+    fn encode_bits<W: ByteWrite + ?Sized>(&self, writer: &mut W, n: u8) -> Result<()>;
+
+    /// Read a value stored in `n` bits.
+    fn decode_bits<R: ByteRead + ?Sized>(reader: &mut R, n: u8) -> Result<Self>;
+}
+
+/// A string or a byte array written with a length prefix, used by the
+/// `#[bytebuffer(prefix = ..)]` attribute.
+#[cfg(feature = "alloc")]
+pub trait Prefixed: Sized {
+    /// Write the value after its length, encoded as `prefix`.
+    fn encode_prefixed<W: ByteWrite + ?Sized>(
+        &self,
+        writer: &mut W,
+        prefix: LengthPrefix,
+    ) -> Result<()>;
+
+    /// Read a value preceded by its length, encoded as `prefix`.
+    fn decode_prefixed<R: ByteRead + ?Sized>(reader: &mut R, prefix: LengthPrefix) -> Result<Self>;
+}
+
+macro_rules! impl_number {
+    ($($ty:ty => $read:ident, $write:ident;)*) => {$(
+        impl ByteEncode for $ty {
+            fn encode<W: ByteWrite + ?Sized>(&self, writer: &mut W) -> Result<()> {
+                writer.$write(*self)
+            }
+        }
+
+        impl ByteDecode for $ty {
+            fn decode<R: ByteRead + ?Sized>(reader: &mut R) -> Result<Self> {
+                reader.$read()
+            }
+        }
+    )*};
+}
+
+impl_number! {
+    u8 => read_u8, write_u8;
+    i8 => read_i8, write_i8;
+    u16 => read_u16, write_u16;
+    i16 => read_i16, write_i16;
+    u32 => read_u32, write_u32;
+    i32 => read_i32, write_i32;
+    u64 => read_u64, write_u64;
+    i64 => read_i64, write_i64;
+    u128 => read_u128, write_u128;
+    i128 => read_i128, write_i128;
+    f32 => read_f32, write_f32;
+    f64 => read_f64, write_f64;
+}
+
+#[cfg(feature = "half")]
+impl_number! {
+    half::f16 => read_f16, write_f16;
+    half::bf16 => read_bf16, write_bf16;
+}
+
+macro_rules! impl_bit_field {
+    ($($ty:ty),*) => {$(
+        impl BitField for $ty {
+            fn encode_bits<W: ByteWrite + ?Sized>(&self, writer: &mut W, n: u8) -> Result<()> {
+                let value = u64::from(*self);
+                if n < 64 && value >> n != 0 {
+                    return Err(Error::LimitExceeded {
+                        value,
+                        limit: (1 << n) - 1,
+                        offset: writer.get_wpos(),
+                    });
+                }
+                writer.write_bits(value, n)
+            }
+
+            fn decode_bits<R: ByteRead + ?Sized>(reader: &mut R, n: u8) -> Result<Self> {
+                let offset = reader.get_rpos();
+                let value = reader.read_bits(n)?;
+                Self::try_from(value).map_err(|_| Error::InvalidValue { offset })
+            }
+        }
+    )*};
+}
+
+impl_bit_field!(u8, u16, u32, u64);
+
+impl BitField for bool {
+    fn encode_bits<W: ByteWrite + ?Sized>(&self, writer: &mut W, n: u8) -> Result<()> {
+        u8::from(*self).encode_bits(writer, n)
+    }
+
+    fn decode_bits<R: ByteRead + ?Sized>(reader: &mut R, n: u8) -> Result<Self> {
+        let offset = reader.get_rpos();
+        match reader.read_bits(n)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(Error::InvalidValue { offset }),
+        }
+    }
+}
+
+/// Booleans are written as a `u8`, `0` or `1`
+impl ByteEncode for bool {
+    fn encode<W: ByteWrite + ?Sized>(&self, writer: &mut W) -> Result<()> {
+        writer.write_u8((*self).into())
+    }
+}
+
+impl ByteDecode for bool {
+    fn decode<R: ByteRead + ?Sized>(reader: &mut R) -> Result<Self> {
+        let offset = reader.get_rpos();
+        match reader.read_u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(Error::InvalidValue { offset }),
+        }
+    }
+}
+
+/// Characters are written as their `u32` code point
+impl ByteEncode for char {
+    fn encode<W: ByteWrite + ?Sized>(&self, writer: &mut W) -> Result<()> {
+        writer.write_u32((*self).into())
+    }
+}
+
+impl ByteDecode for char {
+    fn decode<R: ByteRead + ?Sized>(reader: &mut R) -> Result<Self> {
+        let offset = reader.get_rpos();
+        char::from_u32(reader.read_u32()?).ok_or(Error::InvalidValue { offset })
+    }
+}
+
+impl ByteEncode for () {
+    fn encode<W: ByteWrite + ?Sized>(&self, _writer: &mut W) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl ByteDecode for () {
+    fn decode<R: ByteRead + ?Sized>(_reader: &mut R) -> Result<Self> {
+        Ok(())
+    }
+}
+
+/// Options are written as a `u8` tag, `0` for `None` and `1` for `Some`, followed by the value
+impl<T: ByteEncode> ByteEncode for Option<T> {
+    fn encode<W: ByteWrite + ?Sized>(&self, writer: &mut W) -> Result<()> {
+        match self {
+            None => writer.write_u8(0),
+            Some(value) => {
+                writer.write_u8(1)?;
+                value.encode(writer)
+            }
+        }
+    }
+}
+
+impl<T: ByteDecode> ByteDecode for Option<T> {
+    fn decode<R: ByteRead + ?Sized>(reader: &mut R) -> Result<Self> {
+        let offset = reader.get_rpos();
+        match reader.read_u8()? {
+            0 => Ok(None),
+            1 => Ok(Some(T::decode(reader)?)),
+            _ => Err(Error::InvalidValue { offset }),
+        }
+    }
+}
+
+/// Arrays are written element by element, without their length
+impl<T: ByteEncode, const N: usize> ByteEncode for [T; N] {
+    fn encode<W: ByteWrite + ?Sized>(&self, writer: &mut W) -> Result<()> {
+        self.iter().try_for_each(|value| value.encode(writer))
+    }
+}
+
+impl<T: ByteDecode, const N: usize> ByteDecode for [T; N] {
+    fn decode<R: ByteRead + ?Sized>(reader: &mut R) -> Result<Self> {
+        let mut error = None;
+        let values: [Option<T>; N] = core::array::from_fn(|_| match error {
+            Some(_) => None,
+            None => T::decode(reader).map_err(|e| error = Some(e)).ok(),
+        });
+        match error {
+            Some(e) => Err(e),
+            None => Ok(values.map(|value| value.expect("every value was decoded"))),
+        }
+    }
+}
+
+/// Strings are written like `write_string()`: `(u32)size + size * u8`
+#[cfg(feature = "alloc")]
+impl ByteEncode for String {
+    fn encode<W: ByteWrite + ?Sized>(&self, writer: &mut W) -> Result<()> {
+        writer.write_prefixed_string(self, LengthPrefix::U32)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl ByteDecode for String {
+    fn decode<R: ByteRead + ?Sized>(reader: &mut R) -> Result<Self> {
+        reader.read_prefixed_string(LengthPrefix::U32)
+    }
+}
+
+/// Vectors are written as `(u32)count` followed by their elements
+#[cfg(feature = "alloc")]
+impl<T: ByteEncode> ByteEncode for Vec<T> {
+    fn encode<W: ByteWrite + ?Sized>(&self, writer: &mut W) -> Result<()> {
+        let len = u32::try_from(self.len()).map_err(|_| Error::LimitExceeded {
+            value: self.len() as u64,
+            limit: u32::MAX.into(),
+            offset: writer.get_wpos(),
+        })?;
+        writer.write_u32(len)?;
+        self.iter().try_for_each(|value| value.encode(writer))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: ByteDecode> ByteDecode for Vec<T> {
+    fn decode<R: ByteRead + ?Sized>(reader: &mut R) -> Result<Self> {
+        let len = reader.read_u32()?;
+        // Do not trust the length for the allocation, the data may be truncated
+        let mut res = Vec::with_capacity(core::cmp::min(len, 1024) as usize);
+        for _ in 0..len {
+            res.push(T::decode(reader)?);
+        }
+        Ok(res)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Prefixed for String {
+    fn encode_prefixed<W: ByteWrite + ?Sized>(
+        &self,
+        writer: &mut W,
+        prefix: LengthPrefix,
+    ) -> Result<()> {
+        writer.write_prefixed_string(self, prefix)
+    }
+
+    fn decode_prefixed<R: ByteRead + ?Sized>(reader: &mut R, prefix: LengthPrefix) -> Result<Self> {
+        reader.read_prefixed_string(prefix)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Prefixed for Vec<u8> {
+    fn encode_prefixed<W: ByteWrite + ?Sized>(
+        &self,
+        writer: &mut W,
+        prefix: LengthPrefix,
+    ) -> Result<()> {
+        writer.write_prefixed_bytes(self, prefix)
+    }
+
+    fn decode_prefixed<R: ByteRead + ?Sized>(reader: &mut R, prefix: LengthPrefix) -> Result<Self> {
+        reader.read_prefixed_bytes(prefix)
+    }
+}
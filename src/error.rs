@@ -0,0 +1,185 @@
+use core::fmt::{Display, Formatter};
+
+/// The error type returned by the read and write operations of the crate.
+///
+/// Every variant records the byte offset at which the operation failed, so that it can be
+/// reported precisely. With the `std` feature, an `Error` can be converted into a
+/// `std::io::Error` with `?` or `into()`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Error {
+    /// A read needed more bytes than were available.
+    UnexpectedEof {
+        needed: usize,
+        available: usize,
+        offset: usize,
+    },
+    /// A decode with `ByteBuffer::try_decode` ran out of data: at least `needed` more bytes
+    /// must be appended before it can succeed.
+    Incomplete { needed: usize },
+    /// A write needed more space than was left in a fixed-size buffer.
+    BufferFull {
+        needed: usize,
+        available: usize,
+        offset: usize,
+    },
+    /// The bytes of a string were not valid UTF-8. `offset` is the position of the first
+    /// invalid byte.
+    InvalidUtf8 { offset: usize },
+    /// More bits were requested than a single bit operation supports.
+    TooManyBits { requested: usize, max: usize },
+    /// A variable-length integer starting at `offset` does not fit in the requested type.
+    VarIntOverflow { offset: usize },
+    /// A value is too large to be represented by the encoding.
+    LimitExceeded {
+        value: u64,
+        limit: u64,
+        offset: usize,
+    },
+    /// A `0` byte was found at `offset` in data that is written `0` terminated.
+    InteriorNul { offset: usize },
+    /// The value decoded at `offset` is not valid for its type, like an unknown enum
+    /// discriminant.
+    InvalidValue { offset: usize },
+    /// A signed value does not fit in the requested number of bits.
+    OutOfRange {
+        value: i64,
+        bits: usize,
+        offset: usize,
+    },
+    /// A reader expected to be fully consumed still had `remaining` bytes to read from
+    /// `offset`.
+    TrailingBytes { remaining: usize, offset: usize },
+    /// A value patched into a field reserved at `offset` takes `found` bytes instead of the
+    /// `expected` size of the field.
+    SizeMismatch {
+        expected: usize,
+        found: usize,
+        offset: usize,
+    },
+    /// A custom error reported by a `serde` implementation.
+    #[cfg(feature = "serde")]
+    Custom(alloc::string::String),
+}
+
+/// A specialized `Result` type for the operations of the crate.
+pub type Result<T> = core::result::Result<T, Error>;
+
+impl Error {
+    pub(crate) fn eof(needed: usize, offset: usize, len: usize) -> Error {
+        Error::UnexpectedEof {
+            needed,
+            available: len.saturating_sub(offset),
+            offset,
+        }
+    }
+
+    pub(crate) fn full(needed: usize, offset: usize, len: usize) -> Error {
+        Error::BufferFull {
+            needed,
+            available: len.saturating_sub(offset),
+            offset,
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Error::UnexpectedEof {
+                needed,
+                available,
+                offset,
+            } => write!(
+                f,
+                "could not read {} bytes at offset {}: only {} available",
+                needed, offset, available
+            ),
+            Error::Incomplete { needed } => {
+                write!(f, "incomplete data: {} more bytes needed", needed)
+            }
+            Error::BufferFull {
+                needed,
+                available,
+                offset,
+            } => write!(
+                f,
+                "could not write {} bytes at offset {}: only {} available",
+                needed, offset, available
+            ),
+            Error::InvalidUtf8 { offset } => {
+                write!(f, "invalid UTF-8 sequence at offset {}", offset)
+            }
+            Error::VarIntOverflow { offset } => {
+                write!(f, "variable-length integer at offset {} overflows", offset)
+            }
+            Error::TooManyBits { requested, max } => write!(
+                f,
+                "cannot process {} bits at once, maximum is {}",
+                requested, max
+            ),
+            Error::LimitExceeded {
+                value,
+                limit,
+                offset,
+            } => write!(
+                f,
+                "value {} at offset {} exceeds the limit of {}",
+                value, offset, limit
+            ),
+            Error::InteriorNul { offset } => {
+                write!(f, "unexpected nul byte at offset {}", offset)
+            }
+            Error::InvalidValue { offset } => write!(f, "invalid value at offset {}", offset),
+            Error::OutOfRange {
+                value,
+                bits,
+                offset,
+            } => write!(
+                f,
+                "value {} at offset {} does not fit in {} bits",
+                value, offset, bits
+            ),
+            Error::TrailingBytes { remaining, offset } => {
+                write!(f, "{} unread bytes left at offset {}", remaining, offset)
+            }
+            Error::SizeMismatch {
+                expected,
+                found,
+                offset,
+            } => write!(
+                f,
+                "value at offset {} takes {} bytes instead of {}",
+                offset, found, expected
+            ),
+            #[cfg(feature = "serde")]
+            Error::Custom(msg) => f.write_str(msg),
+        }
+    }
+}
+
+impl core::error::Error for Error {}
+
+#[cfg(feature = "std")]
+impl From<Error> for std::io::Error {
+    fn from(val: Error) -> Self {
+        let kind = match val {
+            Error::UnexpectedEof { .. } | Error::Incomplete { .. } => {
+                std::io::ErrorKind::UnexpectedEof
+            }
+            Error::BufferFull { .. } => std::io::ErrorKind::WriteZero,
+            Error::InvalidUtf8 { .. }
+            | Error::VarIntOverflow { .. }
+            | Error::InvalidValue { .. }
+            | Error::TrailingBytes { .. } => std::io::ErrorKind::InvalidData,
+            #[cfg(feature = "serde")]
+            Error::Custom(_) => std::io::ErrorKind::InvalidData,
+            Error::TooManyBits { .. }
+            | Error::LimitExceeded { .. }
+            | Error::InteriorNul { .. }
+            | Error::OutOfRange { .. }
+            | Error::SizeMismatch { .. } => std::io::ErrorKind::InvalidInput,
+        };
+        std::io::Error::new(kind, val)
+    }
+}
@@ -0,0 +1,40 @@
+use core::marker::PhantomData;
+
+/// A field reserved with `ByteBuffer::reserve_field()`, to be filled in once its value is
+/// known, like the length or the checksum of the data written after it.
+///
+/// #Example
+///
+/// ```
+/// #  use bytebuffer::*;
+/// let mut buffer = ByteBuffer::new();
+/// let len = buffer.reserve_field::<u16>().unwrap();
+/// buffer.write_string("Hello");
+/// buffer.patch_u16(len, 9).unwrap(); // buffer contains [0x0, 0x9, 0x0, 0x0, 0x0, 0x5, ...]
+/// ```
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct FieldHandle<T> {
+    pub(crate) offset: usize,
+    pub(crate) size: usize,
+    pub(crate) field: PhantomData<fn() -> T>,
+}
+
+impl<T> Clone for FieldHandle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for FieldHandle<T> {}
+
+impl<T> FieldHandle<T> {
+    /// Return the position of the field in the buffer
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Return the number of bytes reserved for the field
+    pub fn size(&self) -> usize {
+        self.size
+    }
+}
@@ -0,0 +1,43 @@
+//! Async extension traits for [`futures_io`] streams.
+//!
+//! See [`AsyncByteReadExt`] and [`AsyncByteWriteExt`].
+
+use core::{future::poll_fn, pin::Pin};
+use futures_io::{AsyncRead, AsyncWrite};
+
+async fn read_exact<R: AsyncRead + Unpin + ?Sized>(
+    reader: &mut R,
+    buf: &mut [u8],
+) -> std::io::Result<()> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        let n = poll_fn(|cx| Pin::new(&mut *reader).poll_read(cx, &mut buf[filled..])).await?;
+        if n == 0 {
+            return Err(std::io::ErrorKind::UnexpectedEof.into());
+        }
+        filled += n;
+    }
+    Ok(())
+}
+
+async fn write_all<W: AsyncWrite + Unpin + ?Sized>(
+    writer: &mut W,
+    buf: &[u8],
+) -> std::io::Result<()> {
+    let mut written = 0;
+    while written < buf.len() {
+        let n = poll_fn(|cx| Pin::new(&mut *writer).poll_write(cx, &buf[written..])).await?;
+        if n == 0 {
+            return Err(std::io::ErrorKind::WriteZero.into());
+        }
+        written += n;
+    }
+    Ok(())
+}
+
+async_ext!(
+    AsyncRead,
+    AsyncWrite,
+    "use bytebuffer::futures_io::AsyncByteReadExt;",
+    "futures_io::AsyncRead"
+);
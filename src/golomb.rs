@@ -0,0 +1,136 @@
+//! Unary, Exponential-Golomb and Rice codes, as used by H.264/HEVC headers and lossless audio
+//! residuals.
+//!
+//! A unary value `n` is written as `n` zero bits followed by a one bit. Runs of zeros are
+//! bounded by the width of the decoded value, so a malicious input fails as soon as the run
+//! is too long instead of being scanned to its end.
+
+use crate::{ByteRead, ByteWrite, Error, Result};
+
+/// Largest number of leading zeros of an Exp-Golomb code, enough to map every `u64` and `i64`
+const MAX_EXP_GOLOMB_ZEROS: u64 = 64;
+
+/// Read a run of zero bits terminated by a one bit, returning the number of zeros. Return an
+/// error as soon as the run gets longer than `limit`.
+pub(crate) fn read_unary<R: ByteRead + ?Sized>(reader: &mut R, limit: u64) -> Result<u64> {
+    let offset = reader.get_rpos();
+    let mut count = 0;
+    while !reader.read_bit()? {
+        if count == limit {
+            return Err(Error::LimitExceeded {
+                value: count + 1,
+                limit,
+                offset,
+            });
+        }
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// Write `n` zero bits followed by a one bit.
+pub(crate) fn write_unary<W: ByteWrite + ?Sized>(writer: &mut W, mut n: u64) -> Result<()> {
+    while n > 0 {
+        let take = core::cmp::min(n, 64);
+        writer.write_bits(0, take as u8)?;
+        n -= take;
+    }
+    writer.write_bit(true)
+}
+
+/// Read an Exp-Golomb code number, which may be up to `2^65 - 2`.
+fn read_code<R: ByteRead + ?Sized>(reader: &mut R) -> Result<u128> {
+    let zeros = read_unary(reader, MAX_EXP_GOLOMB_ZEROS)? as u8;
+    let info = reader.read_bits_u128(zeros)?;
+    Ok(((1 << zeros) | info) - 1)
+}
+
+/// Number of leading zeros of the Exp-Golomb code of `code`, written before `code + 1`
+pub(crate) fn code_zeros(code: u128) -> u8 {
+    (127 - (code + 1).leading_zeros()) as u8
+}
+
+/// Code number of a signed value: 1, -1, 2, -2... map to 1, 2, 3, 4...
+pub(crate) fn signed_code(value: i64) -> u128 {
+    let magnitude = u128::from(value.unsigned_abs()) * 2;
+    if value > 0 {
+        magnitude - 1
+    } else {
+        magnitude
+    }
+}
+
+/// Write the leading zeros, then `code + 1` as its leading one and the `zeros` bits below it,
+/// so that the one directly follows the zeros with either bit order.
+fn write_code<W: ByteWrite + ?Sized>(writer: &mut W, code: u128) -> Result<()> {
+    let zeros = code_zeros(code);
+    write_unary(writer, zeros.into())?;
+    writer.write_bits_u128(code + 1, zeros)
+}
+
+/// Read an unsigned Exp-Golomb value, `ue(v)`.
+pub(crate) fn read_unsigned<R: ByteRead + ?Sized>(reader: &mut R) -> Result<u64> {
+    let offset = reader.get_rpos();
+    u64::try_from(read_code(reader)?).map_err(|_| Error::InvalidValue { offset })
+}
+
+/// Read a signed Exp-Golomb value, `se(v)`.
+pub(crate) fn read_signed<R: ByteRead + ?Sized>(reader: &mut R) -> Result<i64> {
+    let offset = reader.get_rpos();
+    let code = read_code(reader)?;
+    let value = if code & 1 == 1 {
+        code.div_ceil(2) as i128
+    } else {
+        -((code / 2) as i128)
+    };
+    i64::try_from(value).map_err(|_| Error::InvalidValue { offset })
+}
+
+pub(crate) fn write_unsigned<W: ByteWrite + ?Sized>(writer: &mut W, value: u64) -> Result<()> {
+    write_code(writer, value.into())
+}
+
+pub(crate) fn write_signed<W: ByteWrite + ?Sized>(writer: &mut W, value: i64) -> Result<()> {
+    write_code(writer, signed_code(value))
+}
+
+fn check_rice_parameter(k: u8) -> Result<()> {
+    if k > 64 {
+        return Err(Error::TooManyBits {
+            requested: k as usize,
+            max: 64,
+        });
+    }
+    Ok(())
+}
+
+/// Read a Rice value with parameter `k`: a unary quotient followed by a `k` bits remainder.
+pub(crate) fn read_rice<R: ByteRead + ?Sized>(reader: &mut R, k: u8) -> Result<u64> {
+    check_rice_parameter(k)?;
+    // The quotient must fit both a unary run and the bits left above the remainder
+    let limit = u64::from(u32::MAX).min(u64::MAX.checked_shr(k.into()).unwrap_or(0));
+    let quotient = read_unary(reader, limit)?;
+    let remainder = reader.read_bits(k)?;
+    Ok(((u128::from(quotient) << k) as u64) | remainder)
+}
+
+/// Write a Rice value with parameter `k`, or return an error if its quotient does not fit in a
+/// 32 bits unary run.
+pub(crate) fn write_rice<W: ByteWrite + ?Sized>(writer: &mut W, value: u64, k: u8) -> Result<()> {
+    check_rice_parameter(k)?;
+    let quotient = value.checked_shr(k.into()).unwrap_or(0);
+    if quotient > u64::from(u32::MAX) {
+        return Err(Error::LimitExceeded {
+            value: quotient,
+            limit: u32::MAX.into(),
+            offset: writer.get_wpos(),
+        });
+    }
+    write_unary(writer, quotient)?;
+    let remainder = if k == 0 {
+        0
+    } else {
+        value & (u64::MAX >> (64 - k))
+    };
+    writer.write_bits(remainder, k)
+}
@@ -1,10 +1,92 @@
+#![cfg_attr(not(feature = "std"), no_std)]
 #![deny(clippy::all)]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+macro_rules! get_number {
+    ($self:ident, $name:ident, $offset:expr, $pos:expr) => {{
+        let pos = $pos;
+        if $offset > $self.data.len().saturating_sub(pos) {
+            return Err(Error::eof($offset, pos, $self.data.len()));
+        }
+        let range = pos..pos + $offset;
+
+        match $self.endian {
+            Endian::BigEndian => BigEndian::$name(&$self.data[range]),
+            Endian::LittleEndian => LittleEndian::$name(&$self.data[range]),
+        }
+    }};
+}
+
+macro_rules! peek_number {
+    ($self:ident, $name:ident, $offset:expr) => {
+        get_number!($self, $name, $offset, $self.byte_rpos())
+    };
+}
+
+macro_rules! read_number {
+    ($self:ident, $name:ident, $offset:expr) => {{
+        $self.flush_bits();
+        let value = peek_number!($self, $name, $offset);
+        $self.rpos += $offset;
+        Ok(value)
+    }};
+}
+
+#[cfg(any(feature = "futures-io", feature = "tokio"))]
+#[macro_use]
+mod async_ext;
+mod bits;
+#[cfg(feature = "alloc")]
 mod buffer;
+mod checkpoint;
+mod codec;
+#[cfg(feature = "serde")]
+pub mod de;
+mod error;
+#[cfg(feature = "alloc")]
+mod field;
+#[cfg(feature = "futures-io")]
+pub mod futures_io;
+mod golomb;
+mod prefix;
 mod reader;
+#[cfg(feature = "alloc")]
+mod ring;
+#[cfg(feature = "serde")]
+pub mod ser;
+#[cfg(feature = "tokio")]
+pub mod tokio_codec;
+#[cfg(feature = "tokio")]
+pub mod tokio_io;
+mod traits;
+mod varint;
+mod writer;
 
+#[cfg(feature = "alloc")]
 pub use buffer::ByteBuffer;
+#[cfg(feature = "derive")]
+pub use bytebuffer_derive::{ByteDecode, ByteEncode};
+pub use checkpoint::{Checkpoint, CheckpointGuard};
+#[cfg(feature = "alloc")]
+pub use codec::Prefixed;
+pub use codec::{BitField, ByteDecode, ByteEncode};
+#[cfg(feature = "serde")]
+pub use de::{from_bytes, Deserializer};
+pub use error::{Error, Result};
+#[cfg(feature = "alloc")]
+pub use field::FieldHandle;
+pub use prefix::LengthPrefix;
 pub use reader::ByteReader;
+#[cfg(feature = "alloc")]
+pub use ring::{RingByteBuffer, RingOverflow};
+#[cfg(feature = "serde")]
+pub use ser::{to_vec, Serializer};
+#[cfg(feature = "tokio")]
+pub use tokio_codec::{FrameCodec, FrameMessage};
+pub use traits::{ByteRead, ByteWrite};
+pub use writer::ByteWriter;
 
 /// An enum to represent the byte order of the ByteBuffer object
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -12,3 +94,25 @@ pub enum Endian {
     BigEndian,
     LittleEndian,
 }
+
+/// An enum to represent the order in which the bits of a byte are read and written by the
+/// bit functions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BitOrder {
+    /// Bits are read from left to right, and the first bit of a value is its most significant
+    /// one
+    MsbFirst,
+    /// Bits are read from right to left, and the first bit of a value is its least
+    /// significant one, as in DEFLATE
+    LsbFirst,
+}
+
+impl BitOrder {
+    /// The mask selecting the bit at position `bit` (`0` being the first one) of a byte
+    pub(crate) fn mask(self, bit: usize) -> u8 {
+        match self {
+            BitOrder::MsbFirst => 1 << (7 - bit),
+            BitOrder::LsbFirst => 1 << bit,
+        }
+    }
+}
@@ -0,0 +1,209 @@
+use crate::{varint, ByteRead, ByteWrite, Error, Result};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+/// An enum to represent how the length of a string or a byte array is encoded
+///
+/// Fixed-width prefixes are written with the byte order of the buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LengthPrefix {
+    /// The length is stored as a `u8` before the data
+    U8,
+    /// The length is stored as a `u16` before the data
+    U16,
+    /// The length is stored as a `u32` before the data
+    U32,
+    /// The length is stored as a `u64` before the data
+    U64,
+    /// The length is stored as an unsigned LEB128 variable-length value before the data
+    VarInt,
+    /// No length is stored, the data is followed by a `0` byte and cannot contain any
+    NulTerminated,
+    /// No length is stored, the data always takes `n` bytes and is padded with `0` bytes
+    Fixed(usize),
+}
+
+pub(crate) fn write_prefixed<W: ByteWrite + ?Sized>(
+    writer: &mut W,
+    bytes: &[u8],
+    prefix: LengthPrefix,
+) -> Result<()> {
+    writer.flush_bits();
+    let offset = writer.get_wpos();
+    write_len(writer, bytes, prefix, offset)?;
+    writer.write_bytes(bytes)?;
+    write_trailer(writer, bytes.len(), prefix)
+}
+
+/// Return the number of bytes written by `write_prefixed()` for data of `len` bytes.
+pub(crate) fn encoded_len(len: usize, prefix: LengthPrefix) -> usize {
+    match prefix {
+        LengthPrefix::U8 => len.saturating_add(1),
+        LengthPrefix::U16 => len.saturating_add(2),
+        LengthPrefix::U32 => len.saturating_add(4),
+        LengthPrefix::U64 => len.saturating_add(8),
+        LengthPrefix::VarInt => {
+            let mut buf = [0; varint::MAX_LEN];
+            len.saturating_add(varint::encode_unsigned(len as u128, &mut buf))
+        }
+        LengthPrefix::NulTerminated => len.saturating_add(1),
+        LengthPrefix::Fixed(n) => n,
+    }
+}
+
+/// Write the length of `bytes` as described by `prefix`, or return an error if `bytes` cannot
+/// be written with it. Errors report the data as written at `offset`.
+pub(crate) fn write_len<W: ByteWrite + ?Sized>(
+    writer: &mut W,
+    bytes: &[u8],
+    prefix: LengthPrefix,
+    offset: usize,
+) -> Result<()> {
+    let len = bytes.len();
+    let exceeded = |limit: u64| Error::LimitExceeded {
+        value: len as u64,
+        limit,
+        offset,
+    };
+
+    match prefix {
+        LengthPrefix::U8 => {
+            let len = u8::try_from(len).map_err(|_| exceeded(u8::MAX.into()))?;
+            writer.write_u8(len)?;
+        }
+        LengthPrefix::U16 => {
+            let len = u16::try_from(len).map_err(|_| exceeded(u16::MAX.into()))?;
+            writer.write_u16(len)?;
+        }
+        LengthPrefix::U32 => {
+            let len = u32::try_from(len).map_err(|_| exceeded(u32::MAX.into()))?;
+            writer.write_u32(len)?;
+        }
+        LengthPrefix::U64 => writer.write_u64(len as u64)?,
+        LengthPrefix::VarInt => writer.write_var_u64(len as u64)?,
+        LengthPrefix::NulTerminated => {
+            if let Some(i) = bytes.iter().position(|b| *b == 0) {
+                return Err(Error::InteriorNul { offset: offset + i });
+            }
+        }
+        LengthPrefix::Fixed(n) => {
+            if len > n {
+                return Err(exceeded(n as u64));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Write what follows data of `len` bytes: the `0` terminator or the `0` padding.
+pub(crate) fn write_trailer<W: ByteWrite + ?Sized>(
+    writer: &mut W,
+    len: usize,
+    prefix: LengthPrefix,
+) -> Result<()> {
+    match prefix {
+        LengthPrefix::NulTerminated => writer.write_u8(0),
+        LengthPrefix::Fixed(n) => {
+            let mut padding = n - len;
+            while padding > 0 {
+                let chunk = core::cmp::min(padding, 64);
+                writer.write_bytes(&[0; 64][..chunk])?;
+                padding -= chunk;
+            }
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Read the length stored before prefixed data, or return `None` for `NulTerminated` data,
+/// whose length is only known once its `0` byte is found.
+pub(crate) fn read_len<R: ByteRead + ?Sized>(
+    reader: &mut R,
+    prefix: LengthPrefix,
+) -> Result<Option<usize>> {
+    reader.flush_bits();
+    let offset = reader.get_rpos();
+    let to_usize = |len: u64| {
+        usize::try_from(len).map_err(|_| Error::LimitExceeded {
+            value: len,
+            limit: usize::MAX as u64,
+            offset,
+        })
+    };
+
+    Ok(Some(match prefix {
+        LengthPrefix::U8 => reader.read_u8()?.into(),
+        LengthPrefix::U16 => reader.read_u16()?.into(),
+        LengthPrefix::U32 => to_usize(reader.read_u32()?.into())?,
+        LengthPrefix::U64 => to_usize(reader.read_u64()?)?,
+        LengthPrefix::VarInt => to_usize(reader.read_var_u64()?)?,
+        LengthPrefix::NulTerminated => return Ok(None),
+        LengthPrefix::Fixed(n) => n,
+    }))
+}
+
+/// Return an error if `prefix` is `NulTerminated` and the `unread` bytes, which end at `end`,
+/// contain no `0` byte, so that unterminated data is rejected before anything is read.
+#[cfg(feature = "alloc")]
+pub(crate) fn check_terminated(
+    prefix: LengthPrefix,
+    mut unread: impl Iterator<Item = u8>,
+    end: usize,
+) -> Result<()> {
+    if prefix == LengthPrefix::NulTerminated && !unread.any(|b| b == 0) {
+        return Err(Error::eof(1, end, end));
+    }
+    Ok(())
+}
+
+/// Read prefixed data, returning the offset of its first byte along with it.
+#[cfg(feature = "alloc")]
+pub(crate) fn read_prefixed<R: ByteRead + ?Sized>(
+    reader: &mut R,
+    prefix: LengthPrefix,
+) -> Result<(usize, Vec<u8>)> {
+    let Some(len) = read_len(reader, prefix)? else {
+        let offset = reader.get_rpos();
+        let mut res = Vec::new();
+        loop {
+            match reader.read_u8()? {
+                0 => return Ok((offset, res)),
+                b => res.push(b),
+            }
+        }
+    };
+
+    let start = reader.get_rpos();
+    Ok((start, reader.read_bytes(len)?))
+}
+
+/// Remove the `0` padding of `Fixed` strings.
+pub(crate) fn trim_padding(mut bytes: &[u8], prefix: LengthPrefix) -> &[u8] {
+    if let LengthPrefix::Fixed(_) = prefix {
+        while let [rest @ .., 0] = bytes {
+            bytes = rest;
+        }
+    }
+    bytes
+}
+
+/// Check that the bytes read at `offset` are valid UTF-8.
+pub(crate) fn to_str(bytes: &[u8], offset: usize) -> Result<&str> {
+    core::str::from_utf8(bytes).map_err(|e| Error::InvalidUtf8 {
+        offset: offset + e.valid_up_to(),
+    })
+}
+
+/// Read a prefixed string. `Fixed` strings have their `0` padding removed.
+#[cfg(feature = "alloc")]
+pub(crate) fn read_prefixed_string<R: ByteRead + ?Sized>(
+    reader: &mut R,
+    prefix: LengthPrefix,
+) -> Result<alloc::string::String> {
+    let (offset, mut bytes) = read_prefixed(reader, prefix)?;
+    bytes.truncate(trim_padding(&bytes, prefix).len());
+    alloc::string::String::from_utf8(bytes).map_err(|e| Error::InvalidUtf8 {
+        offset: offset + e.utf8_error().valid_up_to(),
+    })
+}
@@ -1,9 +1,13 @@
-use crate::Endian;
-use byteorder::{BigEndian, ByteOrder, LittleEndian};
-use std::{
-    fmt::Debug,
-    io::{Error, ErrorKind, Read, Result, Write},
+use crate::{
+    bits, golomb, prefix, varint, BitOrder, ByteRead, Checkpoint, CheckpointGuard, Endian, Error,
+    LengthPrefix, Result,
 };
+#[cfg(feature = "alloc")]
+use alloc::{format, string::String, vec::Vec};
+use byteorder::{BigEndian, ByteOrder, LittleEndian};
+use core::fmt::Debug;
+#[cfg(feature = "std")]
+use std::io::Read;
 
 /// A byte buffer object specifically turned to easily read and write binary values
 #[derive(Clone, PartialEq, Eq, Hash)]
@@ -12,6 +16,7 @@ pub struct ByteReader<'a> {
     rpos: usize,
     rbit: usize,
     endian: Endian,
+    bit_order: BitOrder,
 }
 
 impl<'a> From<&'a [u8]> for ByteReader<'a> {
@@ -20,10 +25,11 @@ impl<'a> From<&'a [u8]> for ByteReader<'a> {
     }
 }
 
+#[cfg(feature = "std")]
 impl<'a> Read for ByteReader<'a> {
-    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
         self.flush_bits();
-        let read_len = std::cmp::min(self.data.len() - self.rpos, buf.len());
+        let read_len = core::cmp::min(self.data.len() - self.rpos, buf.len());
         let range = self.rpos..self.rpos + read_len;
         for (i, val) in self.data[range].iter().enumerate() {
             buf[i] = *val;
@@ -33,20 +39,32 @@ impl<'a> Read for ByteReader<'a> {
     }
 }
 
+/// Read the bytes after the reading cursor. Pending bits are discarded as with `read_bytes`.
+#[cfg(feature = "bytes")]
+impl bytes::Buf for ByteReader<'_> {
+    fn remaining(&self) -> usize {
+        self.data.len() - self.byte_rpos()
+    }
+
+    fn chunk(&self) -> &[u8] {
+        &self.data[self.byte_rpos()..]
+    }
+
+    fn advance(&mut self, cnt: usize) {
+        self.flush_bits();
+        assert!(
+            cnt <= self.data.len() - self.rpos,
+            "cannot advance past the end of the data"
+        );
+        self.rpos += cnt;
+    }
+}
+
 impl<'a> Debug for ByteReader<'a> {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        let rpos = if self.rbit > 0 {
-            self.rpos + 1
-        } else {
-            self.rpos
-        };
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        let rpos = self.byte_rpos();
 
-        let read_len = self.data.len() - rpos;
-        let mut remaining_data = vec![0; read_len];
-        let range = rpos..rpos + read_len;
-        for (i, val) in self.data[range].iter().enumerate() {
-            remaining_data[i] = *val;
-        }
+        let remaining_data = &self.data[rpos..];
 
         write!(
             f,
@@ -56,36 +74,23 @@ impl<'a> Debug for ByteReader<'a> {
     }
 }
 
-macro_rules! read_number {
-    ($self:ident, $name:ident, $offset:expr) => {{
-        $self.flush_bits();
-        if $self.rpos + $offset > $self.data.len() {
-            return Err(Error::new(
-                ErrorKind::UnexpectedEof,
-                "could not read enough bits from buffer",
-            ));
-        }
-        let range = $self.rpos..$self.rpos + $offset;
-        $self.rpos += $offset;
-
-        Ok(match $self.endian {
-            Endian::BigEndian => BigEndian::$name(&$self.data[range]),
-            Endian::LittleEndian => LittleEndian::$name(&$self.data[range]),
-        })
-    }};
-}
-
 impl<'a> ByteReader<'a> {
     /// Construct a new ByteReader filled with the data array.
-    pub fn from_bytes(bytes: &[u8]) -> ByteReader {
+    pub fn from_bytes(bytes: &[u8]) -> ByteReader<'_> {
         ByteReader {
             data: bytes,
             rpos: 0,
             rbit: 0,
             endian: Endian::BigEndian,
+            bit_order: BitOrder::MsbFirst,
         }
     }
 
+    /// Position of the first byte without any bit read yet
+    fn byte_rpos(&self) -> usize {
+        self.rpos + usize::from(self.rbit > 0)
+    }
+
     /// Return the buffer size
     pub fn len(&self) -> usize {
         self.data.len()
@@ -95,6 +100,55 @@ impl<'a> ByteReader<'a> {
         self.data.is_empty()
     }
 
+    /// Return a reader over the next `len` bytes, with the same byte and bit order, and move
+    /// the reading cursor after them, or return an error if not enough bytes are available.
+    /// The sub-reader cannot read past these bytes, and the offsets of its errors are relative
+    /// to its start.
+    /// _Note_: This method resets the read cursor for bitwise reading.
+    ///
+    /// #Example
+    ///
+    /// ```
+    /// #  use bytebuffer::*;
+    /// let data = vec![0x2, 0x1, 0x2, 0x3];
+    /// let mut reader = ByteReader::from_bytes(&data);
+    /// let len = reader.read_u8().unwrap();
+    /// let mut section = reader.sub_reader(len as usize).unwrap();
+    /// let value = section.read_u16().unwrap(); // value contains 0x0102
+    /// assert!(section.read_u8().is_err());
+    /// section.ensure_consumed().unwrap();
+    /// assert_eq!(reader.read_u8().unwrap(), 0x3);
+    /// ```
+    pub fn sub_reader(&mut self, len: usize) -> Result<ByteReader<'a>> {
+        self.flush_bits();
+        if len > self.data.len().saturating_sub(self.rpos) {
+            return Err(Error::eof(len, self.rpos, self.data.len()));
+        }
+        let data = &self.data[self.rpos..self.rpos + len];
+        self.rpos += len;
+        Ok(ByteReader {
+            data,
+            rpos: 0,
+            rbit: 0,
+            endian: self.endian,
+            bit_order: self.bit_order,
+        })
+    }
+
+    /// Return an error if whole bytes are left to read, such as when a nested decoder did not
+    /// read all of its section.
+    /// _Note_: The unread bits of a partially read byte are considered padding.
+    pub fn ensure_consumed(&self) -> Result<()> {
+        let rpos = self.byte_rpos();
+        if rpos < self.data.len() {
+            return Err(Error::TrailingBytes {
+                remaining: self.data.len() - rpos,
+                offset: rpos,
+            });
+        }
+        Ok(())
+    }
+
     /// Reinitialize the reading cursor
     pub fn reset_cursors(&mut self) {
         self.rpos = 0;
@@ -117,27 +171,186 @@ impl<'a> ByteReader<'a> {
         self.endian
     }
 
+    /// Set the order of the bits for the bit functions
+    ///
+    /// _Note_: By default the bits are read and written most significant bit first
+    ///
+    /// _Note_: The order applies to whole bytes, so this method resets the read cursor for bitwise reading.
+    pub fn set_bit_order(&mut self, bit_order: BitOrder) {
+        self.flush_bits();
+        self.bit_order = bit_order;
+    }
+
+    /// Returns the current bit order of the buffer
+    pub fn bit_order(&self) -> BitOrder {
+        self.bit_order
+    }
+
     // Read operations
 
-    /// Read a defined amount of raw bytes, or return an IO error if not enough bytes are
+    /// Read a defined amount of raw bytes, or return an error if not enough bytes are
     /// available.
     /// _Note_: This method resets the read and write cursor for bitwise reading.
+    #[cfg(feature = "alloc")]
     pub fn read_bytes(&mut self, size: usize) -> Result<Vec<u8>> {
+        Ok(self.read_slice(size)?.to_vec())
+    }
+
+    /// Same as `read_bytes()` but borrows the bytes from the underlying data instead of
+    /// copying them, so they can outlive the reader.
+    /// _Note_: This method resets the read cursor for bitwise reading.
+    ///
+    /// #Example
+    ///
+    /// ```
+    /// #  use bytebuffer::*;
+    /// let data = vec![0x1, 0x2, 0x3];
+    /// let bytes = {
+    ///     let mut reader = ByteReader::from_bytes(&data);
+    ///     reader.read_u8().unwrap();
+    ///     reader.read_slice(2).unwrap()
+    /// };
+    /// // bytes contains [0x2, 0x3]
+    /// ```
+    pub fn read_slice(&mut self, size: usize) -> Result<&'a [u8]> {
         self.flush_bits();
-        if self.rpos + size > self.data.len() {
-            return Err(Error::new(
-                ErrorKind::UnexpectedEof,
-                "could not read enough bytes from buffer",
-            ));
+        if size > self.data.len().saturating_sub(self.rpos) {
+            return Err(Error::eof(size, self.rpos, self.data.len()));
         }
-        let range = self.rpos..self.rpos + size;
-        let mut res = Vec::<u8>::new();
-        res.write_all(&self.data[range])?;
+        let slice = &self.data[self.rpos..self.rpos + size];
         self.rpos += size;
-        Ok(res)
+        Ok(slice)
     }
 
-    /// Read one byte, or return an IO error if not enough bytes are available.
+    /// Read exactly `N` bytes into an array, or return an error if not enough bytes are
+    /// available.
+    /// _Note_: This method resets the read cursor for bitwise reading.
+    ///
+    /// #Example
+    ///
+    /// ```
+    /// #  use bytebuffer::*;
+    /// let data = vec![0xCA, 0xFE, 0x1];
+    /// let mut reader = ByteReader::from_bytes(&data);
+    /// let magic = reader.read_array::<2>().unwrap(); // magic contains [0xCA, 0xFE]
+    /// ```
+    pub fn read_array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.read_slice(N)?);
+        Ok(array)
+    }
+
+    /// Read `size` bytes as a string borrowed from the underlying data, or return an error if
+    /// not enough bytes are available or if they are not valid UTF8.
+    /// _Note_: This method resets the read cursor for bitwise reading.
+    pub fn read_str(&mut self, size: usize) -> Result<&'a str> {
+        let offset = self.byte_rpos();
+        prefix::to_str(self.read_slice(size)?, offset)
+    }
+
+    /// Same as `read_prefixed_string()` but borrows the string from the underlying data.
+    /// _Note_: This method resets the read cursor for bitwise reading.
+    ///
+    /// #Example
+    ///
+    /// ```
+    /// #  use bytebuffer::*;
+    /// let data = vec![0x5, b'H', b'e', b'l', b'l', b'o'];
+    /// let mut reader = ByteReader::from_bytes(&data);
+    /// let value = reader.read_str_prefixed(LengthPrefix::U8).unwrap(); // value contains "Hello"
+    /// ```
+    pub fn read_str_prefixed(&mut self, prefix: LengthPrefix) -> Result<&'a str> {
+        let len = prefix::read_len(self, prefix)?;
+        let offset = self.rpos;
+        let bytes = match len {
+            Some(len) => self.read_slice(len)?,
+            None => {
+                let Some(len) = self.data[offset..].iter().position(|b| *b == 0) else {
+                    return Err(Error::eof(1, self.data.len(), self.data.len()));
+                };
+                // Skip the `0` byte as well
+                self.rpos += len + 1;
+                &self.data[offset..offset + len]
+            }
+        };
+        prefix::to_str(prefix::trim_padding(bytes, prefix), offset)
+    }
+
+    /// Read one byte at `index`, without moving the reading cursor, or return an error if
+    /// `index` is out of bounds.
+    ///
+    /// #Example
+    ///
+    /// ```
+    /// #  use bytebuffer::*;
+    /// let data = vec![0x1, 0x2, 0x3];
+    /// let buffer = ByteReader::from_bytes(&data);
+    /// let value = buffer.get_u8_at(2).unwrap(); // value contains 3
+    /// assert_eq!(buffer.get_rpos(), 0);
+    /// ```
+    pub fn get_u8_at(&self, index: usize) -> Result<u8> {
+        self.data
+            .get(index)
+            .copied()
+            .ok_or_else(|| Error::eof(1, index, self.data.len()))
+    }
+
+    /// Same as `get_u8_at()` but for signed values
+    pub fn get_i8_at(&self, index: usize) -> Result<i8> {
+        Ok(self.get_u8_at(index)? as i8)
+    }
+
+    /// Same as `read_u16()` but at `index`, without moving the reading cursor
+    pub fn get_u16_at(&self, index: usize) -> Result<u16> {
+        Ok(get_number!(self, read_u16, 2, index))
+    }
+
+    /// Same as `get_u16_at()` but for signed values
+    pub fn get_i16_at(&self, index: usize) -> Result<i16> {
+        Ok(self.get_u16_at(index)? as i16)
+    }
+
+    /// Same as `read_u32()` but at `index`, without moving the reading cursor
+    pub fn get_u32_at(&self, index: usize) -> Result<u32> {
+        Ok(get_number!(self, read_u32, 4, index))
+    }
+
+    /// Same as `get_u32_at()` but for signed values
+    pub fn get_i32_at(&self, index: usize) -> Result<i32> {
+        Ok(self.get_u32_at(index)? as i32)
+    }
+
+    /// Same as `read_u64()` but at `index`, without moving the reading cursor
+    pub fn get_u64_at(&self, index: usize) -> Result<u64> {
+        Ok(get_number!(self, read_u64, 8, index))
+    }
+
+    /// Same as `get_u64_at()` but for signed values
+    pub fn get_i64_at(&self, index: usize) -> Result<i64> {
+        Ok(self.get_u64_at(index)? as i64)
+    }
+
+    /// Same as `read_u128()` but at `index`, without moving the reading cursor
+    pub fn get_u128_at(&self, index: usize) -> Result<u128> {
+        Ok(get_number!(self, read_u128, 16, index))
+    }
+
+    /// Same as `get_u128_at()` but for signed values
+    pub fn get_i128_at(&self, index: usize) -> Result<i128> {
+        Ok(self.get_u128_at(index)? as i128)
+    }
+
+    /// Same as `read_f32()` but at `index`, without moving the reading cursor
+    pub fn get_f32_at(&self, index: usize) -> Result<f32> {
+        Ok(get_number!(self, read_f32, 4, index))
+    }
+
+    /// Same as `read_f64()` but at `index`, without moving the reading cursor
+    pub fn get_f64_at(&self, index: usize) -> Result<f64> {
+        Ok(get_number!(self, read_f64, 8, index))
+    }
+
+    /// Read one byte, or return an error if not enough bytes are available.
     /// _Note_: This method resets the read and write cursor for bitwise reading.
     ///
     /// #Example
@@ -151,10 +364,7 @@ impl<'a> ByteReader<'a> {
     pub fn read_u8(&mut self) -> Result<u8> {
         self.flush_bits();
         if self.rpos >= self.data.len() {
-            return Err(Error::new(
-                ErrorKind::UnexpectedEof,
-                "could not read enough bits from buffer",
-            ));
+            return Err(Error::eof(1, self.rpos, self.data.len()));
         }
         let pos = self.rpos;
         self.rpos += 1;
@@ -166,7 +376,7 @@ impl<'a> ByteReader<'a> {
         Ok(self.read_u8()? as i8)
     }
 
-    /// Read a 2-bytes long value, or return an IO error if not enough bytes are available.
+    /// Read a 2-bytes long value, or return an error if not enough bytes are available.
     /// _Note_: This method resets the read and write cursor for bitwise reading.
     ///
     /// #Example
@@ -187,7 +397,7 @@ impl<'a> ByteReader<'a> {
         Ok(self.read_u16()? as i16)
     }
 
-    /// Read a four-bytes long value, or return an IO error if not enough bytes are available.
+    /// Read a four-bytes long value, or return an error if not enough bytes are available.
     /// _Note_: This method resets the read and write cursor for bitwise reading.
     ///
     /// #Example
@@ -208,7 +418,7 @@ impl<'a> ByteReader<'a> {
         Ok(self.read_u32()? as i32)
     }
 
-    /// Read an eight bytes long value, or return an IO error if not enough bytes are available.
+    /// Read an eight bytes long value, or return an error if not enough bytes are available.
     /// _Note_: This method resets the read and write cursor for bitwise reading.
     ///
     /// #Example
@@ -229,7 +439,7 @@ impl<'a> ByteReader<'a> {
         Ok(self.read_u64()? as i64)
     }
 
-    /// Read a sixteen bytes long value, or return an IO error if not enough bytes are available.
+    /// Read a sixteen bytes long value, or return an error if not enough bytes are available.
     /// _Note_: This method resets the read and write cursor for bitwise reading.
     ///
     /// #Example
@@ -249,13 +459,13 @@ impl<'a> ByteReader<'a> {
         Ok(self.read_u128()? as i128)
     }
 
-    /// Read a 32 bits floating point value, or return an IO error if not enough bytes are available.
+    /// Read a 32 bits floating point value, or return an error if not enough bytes are available.
     /// _Note_: This method resets the read and write cursor for bitwise reading.
     pub fn read_f32(&mut self) -> Result<f32> {
         read_number!(self, read_f32, 4)
     }
 
-    /// Read a 64 bits floating point value, or return an IO error if not enough bytes are available.
+    /// Read a 64 bits floating point value, or return an error if not enough bytes are available.
     /// _Note_: This method resets the read and write cursor for bitwise reading.
     pub fn read_f64(&mut self) -> Result<f64> {
         read_number!(self, read_f64, 8)
@@ -266,17 +476,220 @@ impl<'a> ByteReader<'a> {
     /// _Note_: First it reads a 32 bits value representing the size, then 'size' raw bytes
     ///         that  must be encoded as UTF8.
     /// _Note_: This method resets the read and write cursor for bitwise reading.
+    #[cfg(feature = "alloc")]
     pub fn read_string(&mut self) -> Result<String> {
-        let size = self.read_u32()?;
-        match String::from_utf8(self.read_bytes(size as usize)?) {
-            Ok(string_result) => Ok(string_result),
-            Err(e) => Err(Error::new(ErrorKind::InvalidData, e)),
+        self.read_prefixed_string(LengthPrefix::U32)
+    }
+
+    /// Read one byte without moving the reading cursor, or return an error if not enough
+    /// bytes are available.
+    /// _Note_: As with `read_u8()`, the byte is read after the bits being read, if any.
+    ///
+    /// #Example
+    ///
+    /// ```
+    /// #  use bytebuffer::*;
+    /// let data = vec![0x1];
+    /// let mut buffer = ByteReader::from_bytes(&data);
+    /// let tag = buffer.peek_u8().unwrap(); // tag contains 1
+    /// assert_eq!(buffer.read_u8().unwrap(), tag);
+    /// ```
+    pub fn peek_u8(&self) -> Result<u8> {
+        let pos = self.byte_rpos();
+        if pos >= self.data.len() {
+            return Err(Error::eof(1, pos, self.data.len()));
+        }
+        Ok(self.data[pos])
+    }
+
+    /// Same as `peek_u8()` but for signed values
+    pub fn peek_i8(&self) -> Result<i8> {
+        Ok(self.peek_u8()? as i8)
+    }
+
+    /// Same as `read_u16()` but without moving the reading cursor
+    pub fn peek_u16(&self) -> Result<u16> {
+        Ok(peek_number!(self, read_u16, 2))
+    }
+
+    /// Same as `read_i16()` but without moving the reading cursor
+    pub fn peek_i16(&self) -> Result<i16> {
+        Ok(self.peek_u16()? as i16)
+    }
+
+    /// Same as `read_u32()` but without moving the reading cursor
+    pub fn peek_u32(&self) -> Result<u32> {
+        Ok(peek_number!(self, read_u32, 4))
+    }
+
+    /// Same as `read_i32()` but without moving the reading cursor
+    pub fn peek_i32(&self) -> Result<i32> {
+        Ok(self.peek_u32()? as i32)
+    }
+
+    /// Same as `read_u64()` but without moving the reading cursor
+    pub fn peek_u64(&self) -> Result<u64> {
+        Ok(peek_number!(self, read_u64, 8))
+    }
+
+    /// Same as `read_i64()` but without moving the reading cursor
+    pub fn peek_i64(&self) -> Result<i64> {
+        Ok(self.peek_u64()? as i64)
+    }
+
+    /// Same as `read_u128()` but without moving the reading cursor
+    pub fn peek_u128(&self) -> Result<u128> {
+        Ok(peek_number!(self, read_u128, 16))
+    }
+
+    /// Same as `read_i128()` but without moving the reading cursor
+    pub fn peek_i128(&self) -> Result<i128> {
+        Ok(self.peek_u128()? as i128)
+    }
+
+    /// Same as `read_f32()` but without moving the reading cursor
+    pub fn peek_f32(&self) -> Result<f32> {
+        Ok(peek_number!(self, read_f32, 4))
+    }
+
+    /// Same as `read_f64()` but without moving the reading cursor
+    pub fn peek_f64(&self) -> Result<f64> {
+        Ok(peek_number!(self, read_f64, 8))
+    }
+
+    /// Same as `read_bytes()` but without moving the reading cursor
+    #[cfg(feature = "alloc")]
+    pub fn peek_bytes(&self, size: usize) -> Result<Vec<u8>> {
+        let pos = self.byte_rpos();
+        if size > self.data.len().saturating_sub(pos) {
+            return Err(Error::eof(size, pos, self.data.len()));
         }
+        Ok(self.data[pos..pos + size].to_vec())
+    }
+
+    /// Same as `read_string()` but without moving the reading cursor
+    ///
+    /// #Example
+    ///
+    /// ```
+    /// #  use bytebuffer::*;
+    /// let data = vec![0x0, 0x0, 0x0, 0x2, 0x48, 0x69];
+    /// let buffer = ByteReader::from_bytes(&data);
+    /// let value = buffer.peek_string().unwrap(); // value contains "Hi"
+    /// assert_eq!(buffer.get_rpos(), 0);
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn peek_string(&self) -> Result<String> {
+        self.clone().read_string()
+    }
+
+    /// Read an unsigned LEB128 variable-length value, or return an error if not enough bytes
+    /// are available or if the value does not fit in 32 bits.
+    /// _Note_: This method resets the read and write cursor for bitwise reading.
+    ///
+    /// #Example
+    ///
+    /// ```
+    /// #  use bytebuffer::*;
+    /// let data = vec![0xAC, 0x02];
+    /// let mut buffer = ByteReader::from_bytes(&data);
+    /// let value = buffer.read_var_u32().unwrap(); // Value contains 300
+    /// ```
+    pub fn read_var_u32(&mut self) -> Result<u32> {
+        Ok(varint::read_unsigned(self, 32)? as u32)
+    }
+
+    /// Same as `read_var_u32()` but for 64 bits values
+    /// _Note_: This method resets the read and write cursor for bitwise reading.
+    pub fn read_var_u64(&mut self) -> Result<u64> {
+        Ok(varint::read_unsigned(self, 64)? as u64)
+    }
+
+    /// Same as `read_var_u32()` but for 128 bits values
+    /// _Note_: This method resets the read and write cursor for bitwise reading.
+    pub fn read_var_u128(&mut self) -> Result<u128> {
+        varint::read_unsigned(self, 128)
+    }
+
+    /// Read a signed LEB128 variable-length value, or return an error if not enough bytes
+    /// are available or if the value does not fit in 32 bits.
+    /// _Note_: This method resets the read and write cursor for bitwise reading.
+    pub fn read_var_i32(&mut self) -> Result<i32> {
+        Ok(varint::read_signed(self, 32)? as i32)
+    }
+
+    /// Same as `read_var_i32()` but for 64 bits values
+    /// _Note_: This method resets the read and write cursor for bitwise reading.
+    pub fn read_var_i64(&mut self) -> Result<i64> {
+        Ok(varint::read_signed(self, 64)? as i64)
+    }
+
+    /// Same as `read_var_i32()` but for 128 bits values
+    /// _Note_: This method resets the read and write cursor for bitwise reading.
+    pub fn read_var_i128(&mut self) -> Result<i128> {
+        varint::read_signed(self, 128)
+    }
+
+    /// Read a ZigZag encoded LEB128 variable-length value, or return an error if not enough
+    /// bytes are available or if the value does not fit in 32 bits.
+    /// _Note_: This method resets the read and write cursor for bitwise reading.
+    pub fn read_zigzag_i32(&mut self) -> Result<i32> {
+        Ok(varint::zigzag_decode(varint::read_unsigned(self, 32)?) as i32)
+    }
+
+    /// Same as `read_zigzag_i32()` but for 64 bits values
+    /// _Note_: This method resets the read and write cursor for bitwise reading.
+    pub fn read_zigzag_i64(&mut self) -> Result<i64> {
+        Ok(varint::zigzag_decode(varint::read_unsigned(self, 64)?) as i64)
+    }
+
+    /// Same as `read_zigzag_i32()` but for 128 bits values
+    /// _Note_: This method resets the read and write cursor for bitwise reading.
+    pub fn read_zigzag_i128(&mut self) -> Result<i128> {
+        Ok(varint::zigzag_decode(varint::read_unsigned(self, 128)?))
+    }
+
+    /// Read a byte array whose length is encoded as described by `prefix`, or return an
+    /// error if not enough bytes are available.
+    /// _Note_: With `Fixed(n)`, the `n` bytes are returned, padding included.
+    /// _Note_: This method resets the read and write cursor for bitwise reading.
+    ///
+    /// #Example
+    ///
+    /// ```
+    /// #  use bytebuffer::*;
+    /// let data = vec![0x2, 0xFF, 0x45];
+    /// let mut buffer = ByteReader::from_bytes(&data);
+    /// let value = buffer.read_prefixed_bytes(LengthPrefix::U8).unwrap(); // value contains [0xFF, 0x45]
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn read_prefixed_bytes(&mut self, prefix: LengthPrefix) -> Result<Vec<u8>> {
+        self.check_terminated(prefix)?;
+        Ok(prefix::read_prefixed(self, prefix)?.1)
+    }
+
+    /// Read a string whose length is encoded as described by `prefix`, or return an error if
+    /// not enough bytes are available or if they are not valid UTF8.
+    /// _Note_: With `Fixed(n)`, the trailing `0` padding bytes are removed.
+    /// _Note_: This method resets the read and write cursor for bitwise reading.
+    #[cfg(feature = "alloc")]
+    pub fn read_prefixed_string(&mut self, prefix: LengthPrefix) -> Result<String> {
+        self.check_terminated(prefix)?;
+        prefix::read_prefixed_string(self, prefix)
+    }
+
+    /// Reject `NulTerminated` data without its `0` byte before moving the reading cursor
+    #[cfg(feature = "alloc")]
+    fn check_terminated(&mut self, prefix: LengthPrefix) -> Result<()> {
+        self.flush_bits();
+        let unread = self.data.get(self.rpos..).unwrap_or_default();
+        prefix::check_terminated(prefix, unread.iter().copied(), self.data.len())
     }
 
     // Other
 
     /// Dump the byte buffer to a string.
+    #[cfg(feature = "alloc")]
     pub fn to_hex_dump(&self) -> String {
         let mut str = String::new();
         for b in self.data {
@@ -294,7 +707,75 @@ impl<'a> ByteReader<'a> {
     /// Set the reading cursor position.
     /// _Note_: Sets the reading cursor to `min(newPosition, self.len())` to prevent overflow
     pub fn set_rpos(&mut self, rpos: usize) {
-        self.rpos = std::cmp::min(rpos, self.data.len());
+        self.rpos = core::cmp::min(rpos, self.data.len());
+    }
+
+    /// Return the position of the reading cursor in bits, counting from the start of the
+    /// data: `get_rpos() * 8` plus the bits already read in the current byte.
+    pub fn get_bit_rpos(&self) -> usize {
+        self.rpos * 8 + self.rbit
+    }
+
+    /// Set the reading cursor position in bits, counting from the start of the data.
+    /// _Note_: Sets the reading cursor to `min(newPosition, self.len() * 8)` to prevent overflow
+    ///
+    /// #Example
+    ///
+    /// ```
+    /// #  use bytebuffer::*;
+    /// let data = vec![0xAB, 0xCD];
+    /// let mut buffer = ByteReader::from_bytes(&data);
+    /// buffer.set_bit_rpos(12);
+    /// assert_eq!(buffer.get_rpos(), 1);
+    /// let value = buffer.read_bits(4).unwrap(); // value contains 0xD
+    /// ```
+    pub fn set_bit_rpos(&mut self, pos: usize) {
+        let pos = core::cmp::min(pos, self.data.len() * 8);
+        self.rpos = pos / 8;
+        self.rbit = pos % 8;
+    }
+
+    /// Save the reading cursor, with its bit cursor, and the byte order, to
+    /// go back to them later with `restore()`.
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            rpos: self.rpos,
+            rbit: self.rbit,
+            wpos: 0,
+            wbit: 0,
+            endian: self.endian,
+        }
+    }
+
+    /// Move the reading cursor back to a `checkpoint`, and restore its byte order.
+    /// _Note_: Cursors are limited to `self.len()` as with `set_rpos()`
+    pub fn restore(&mut self, checkpoint: Checkpoint) {
+        self.set_bit_rpos(checkpoint.rpos * 8 + checkpoint.rbit);
+        self.endian = checkpoint.endian;
+    }
+
+    /// Return a guard giving access to the reader, which restores the current checkpoint when
+    /// dropped, unless `CheckpointGuard::commit()` is called.
+    ///
+    /// #Example
+    ///
+    /// ```
+    /// #  use bytebuffer::*;
+    /// let data = vec![0x1, 0x2];
+    /// let mut buffer = ByteReader::from_bytes(&data);
+    /// {
+    ///     let mut guard = buffer.checkpoint_guard();
+    ///     let value = guard.read_u16().unwrap();
+    ///     if value != 0x0102 {
+    ///         guard.commit();
+    ///     }
+    /// }
+    /// // value contains 0x0102, so the guard restored the cursor when it was dropped
+    /// assert_eq!(buffer.get_rpos(), 0);
+    /// ```
+    pub fn checkpoint_guard(&mut self) -> CheckpointGuard<'_, Self> {
+        let checkpoint = self.checkpoint();
+        CheckpointGuard::new(self, checkpoint, Self::restore)
     }
 
     /// Return the raw byte buffer bytes.
@@ -306,7 +787,7 @@ impl<'a> ByteReader<'a> {
 
     /// Read 1 bit. Return true if the bit is set to 1, otherwhise, return false.
     ///
-    /// _Note_: Bits are read from left to right
+    /// _Note_: Bits are read from left to right, or from right to left with `BitOrder::LsbFirst`
     ///
     /// #Example
     ///
@@ -319,12 +800,9 @@ impl<'a> ByteReader<'a> {
     /// ```
     pub fn read_bit(&mut self) -> Result<bool> {
         if self.rpos >= self.data.len() {
-            return Err(Error::new(
-                ErrorKind::UnexpectedEof,
-                "could not read enough bits from buffer",
-            ));
+            return Err(Error::eof(1, self.rpos, self.data.len()));
         }
-        let bit = self.data[self.rpos] & (1 << (7 - self.rbit)) != 0;
+        let bit = self.data[self.rpos] & self.bit_order.mask(self.rbit) != 0;
         self.rbit += 1;
         if self.rbit > 7 {
             self.flush_rbits();
@@ -336,7 +814,7 @@ impl<'a> ByteReader<'a> {
     ///
     /// _Note_: We cannot read more than 64 bits
     ///
-    /// _Note_: Bits are read from left to right
+    /// _Note_: Bits are read from left to right, or from right to left with `BitOrder::LsbFirst`
     ///
     /// #Example
     ///
@@ -346,19 +824,201 @@ impl<'a> ByteReader<'a> {
     /// let mut buffer = ByteReader::from_bytes(&data); // 10000000b
     /// let value = buffer.read_bits(3).unwrap(); // value contains 4 (eg: 100b)
     /// ```
+    #[inline]
     pub fn read_bits(&mut self, n: u8) -> Result<u64> {
         if n > 64 {
-            return Err(Error::new(
-                ErrorKind::InvalidInput,
-                "cannot read more than 64 bits",
+            return Err(Error::TooManyBits {
+                requested: n as usize,
+                max: 64,
+            });
+        }
+
+        Ok(self.read_bits_raw(n.into())? as u64)
+    }
+
+    /// Read n bits. an return the corresponding value an u128.
+    ///
+    /// _Note_: We cannot read more than 128 bits
+    ///
+    /// _Note_: Bits are read from left to right, or from right to left with `BitOrder::LsbFirst`
+    ///
+    /// #Example
+    ///
+    /// ```
+    /// #  use bytebuffer::*;
+    /// let data = [0xFF; 10];
+    /// let mut buffer = ByteReader::from_bytes(&data);
+    /// buffer.read_bit().unwrap();
+    /// let value = buffer.read_bits_u128(72).unwrap(); // value contains 2^72 - 1
+    /// ```
+    #[inline]
+    pub fn read_bits_u128(&mut self, n: u8) -> Result<u128> {
+        if n > 128 {
+            return Err(Error::TooManyBits {
+                requested: n as usize,
+                max: 128,
+            });
+        }
+        self.read_bits_raw(n.into())
+    }
+
+    /// Read n bits as a two's complement value and return it with its sign extended, or
+    /// return an error if not enough bits are available.
+    ///
+    /// _Note_: We cannot read more than 64 bits
+    ///
+    /// #Example
+    ///
+    /// ```
+    /// #  use bytebuffer::*;
+    /// let data = vec![0xC0];
+    /// let mut buffer = ByteReader::from_bytes(&data); // 11000000b
+    /// let value = buffer.read_bits_signed(3).unwrap(); // value contains -2 (eg: 110b)
+    /// ```
+    pub fn read_bits_signed(&mut self, n: u8) -> Result<i64> {
+        bits::read_signed(self, n)
+    }
+
+    /// Read a unary value: a run of 0 bits terminated by a 1 bit, and return the number of 0
+    /// bits. Return an error if the run is longer than `u32::MAX` bits or reaches the end of
+    /// the data.
+    ///
+    /// #Example
+    ///
+    /// ```
+    /// #  use bytebuffer::*;
+    /// let data = vec![0x12];
+    /// let mut buffer = ByteReader::from_bytes(&data); // 00010010b
+    /// let value = buffer.read_unary().unwrap(); // value contains 3
+    /// ```
+    pub fn read_unary(&mut self) -> Result<u32> {
+        Ok(golomb::read_unary(self, u32::MAX.into())? as u32)
+    }
+
+    /// Read an unsigned Exponential-Golomb value, `ue(v)` in H.264: `z` 0 bits, then the value
+    /// plus one in `z + 1` bits. Return an error if the code has more than 64 leading 0 bits,
+    /// so that long runs of 0 in malformed data are rejected early.
+    ///
+    /// #Example
+    ///
+    /// ```
+    /// #  use bytebuffer::*;
+    /// let data = vec![0x20];
+    /// let mut buffer = ByteReader::from_bytes(&data); // 00100000b
+    /// let value = buffer.read_exp_golomb().unwrap(); // value contains 3
+    /// ```
+    pub fn read_exp_golomb(&mut self) -> Result<u64> {
+        golomb::read_unsigned(self)
+    }
+
+    /// Read a signed Exponential-Golomb value, `se(v)` in H.264: the code numbers 1, 2, 3,
+    /// 4... map to 1, -1, 2, -2...
+    ///
+    /// #Example
+    ///
+    /// ```
+    /// #  use bytebuffer::*;
+    /// let data = vec![0x60];
+    /// let mut buffer = ByteReader::from_bytes(&data); // 01100000b
+    /// let value = buffer.read_signed_exp_golomb().unwrap(); // value contains -1
+    /// ```
+    pub fn read_signed_exp_golomb(&mut self) -> Result<i64> {
+        golomb::read_signed(self)
+    }
+
+    /// Read a Rice value with parameter `k`: a unary quotient followed by a `k` bits
+    /// remainder. Return an error if `k` is greater than 64, or if the quotient does not fit
+    /// in the result.
+    ///
+    /// #Example
+    ///
+    /// ```
+    /// #  use bytebuffer::*;
+    /// let data = vec![0x2C];
+    /// let mut buffer = ByteReader::from_bytes(&data); // 00101100b
+    /// let value = buffer.read_rice(2).unwrap(); // value contains 9 (eg: 2 << 2 | 01b)
+    /// ```
+    pub fn read_rice(&mut self, k: u8) -> Result<u64> {
+        golomb::read_rice(self, k)
+    }
+
+    /// Return the number of bits left to read after the reading cursor.
+    pub fn remaining_bits(&self) -> usize {
+        bits::available(self.data.len(), self.rpos, self.rbit)
+    }
+
+    /// Move the reading cursor n bits forward, or return an error, without moving it, if not
+    /// enough bits are available.
+    ///
+    /// #Example
+    ///
+    /// ```
+    /// #  use bytebuffer::*;
+    /// let data = vec![0xAB, 0xCD];
+    /// let mut buffer = ByteReader::from_bytes(&data);
+    /// buffer.skip_bits(4).unwrap();
+    /// let value = buffer.read_bits(8).unwrap(); // value contains 0xBC
+    /// assert!(buffer.skip_bits(5).is_err());
+    /// ```
+    pub fn skip_bits(&mut self, n: usize) -> Result<()> {
+        if n > self.remaining_bits() {
+            return Err(Error::eof(
+                self.rbit.saturating_add(n).div_ceil(8),
+                self.rpos,
+                self.data.len(),
             ));
         }
+        self.set_bit_rpos(self.get_bit_rpos() + n);
+        Ok(())
+    }
 
-        if n == 0 {
-            Ok(0)
-        } else {
-            Ok((u64::from(self.read_bit()?) << (n - 1)) | self.read_bits(n - 1)?)
+    /// Read n bits, up to 64, without moving the reading cursor.
+    ///
+    /// #Example
+    ///
+    /// ```
+    /// #  use bytebuffer::*;
+    /// let data = vec![0xAB, 0xCD];
+    /// let mut buffer = ByteReader::from_bytes(&data);
+    /// let value = buffer.peek_bits(4).unwrap(); // value contains 0xA
+    /// assert_eq!(buffer.read_bits(4).unwrap(), value);
+    /// ```
+    pub fn peek_bits(&self, n: u8) -> Result<u64> {
+        if n > 64 {
+            return Err(Error::TooManyBits {
+                requested: n as usize,
+                max: 64,
+            });
         }
+        Ok(self.get_bits_raw(n.into())? as u64)
+    }
+
+    /// Read n bits at the reading cursor, without moving it.
+    #[inline]
+    fn get_bits_raw(&self, n: u32) -> Result<u128> {
+        if n as usize > bits::available(self.data.len(), self.rpos, self.rbit) {
+            return Err(Error::eof(
+                bits::span(self.rbit, n),
+                self.rpos,
+                self.data.len(),
+            ));
+        }
+        Ok(bits::read(
+            self.data,
+            self.rpos,
+            self.rbit,
+            n,
+            self.bit_order,
+        ))
+    }
+
+    #[inline]
+    fn read_bits_raw(&mut self, n: u32) -> Result<u128> {
+        let value = self.get_bits_raw(n)?;
+        let end = self.rbit + n as usize;
+        self.rpos += end / 8;
+        self.rbit = end % 8;
+        Ok(value)
     }
 
     /// Discard all the pending bits available for reading and place the corresponding cursor to the next byte.
@@ -389,17 +1049,14 @@ impl<'a> ByteReader<'a> {
 
 #[cfg(feature = "half")]
 impl ByteReader<'_> {
-    /// Read a 16 bits floating point value, or return an IO error if not enough bytes are available.
+    /// Read a 16 bits floating point value, or return an error if not enough bytes are available.
     /// _Note_: This method resets the read and write cursor for bitwise reading.
     pub fn read_f16(&mut self) -> Result<half::f16> {
         let offset = 2;
 
         self.flush_bits();
         if self.rpos + offset > self.data.len() {
-            return Err(Error::new(
-                ErrorKind::UnexpectedEof,
-                "could not read enough bits from buffer",
-            ));
+            return Err(Error::eof(offset, self.rpos, self.data.len()));
         }
         let range = self.rpos..self.rpos + offset;
         self.rpos += offset;
@@ -414,17 +1071,14 @@ impl ByteReader<'_> {
         })
     }
 
-    /// Read a truncated 16 bits floating point value, or return an IO error if not enough bytes are available.
+    /// Read a truncated 16 bits floating point value, or return an error if not enough bytes are available.
     /// _Note_: This method resets the read and write cursor for bitwise reading.
     pub fn read_bf16(&mut self) -> Result<half::bf16> {
         let offset = 2;
 
         self.flush_bits();
         if self.rpos + offset > self.data.len() {
-            return Err(Error::new(
-                ErrorKind::UnexpectedEof,
-                "could not read enough bits from buffer",
-            ));
+            return Err(Error::eof(offset, self.rpos, self.data.len()));
         }
         let range = self.rpos..self.rpos + offset;
         self.rpos += offset;
@@ -439,3 +1093,111 @@ impl ByteReader<'_> {
         })
     }
 }
+
+impl ByteRead for ByteReader<'_> {
+    fn endian(&self) -> Endian {
+        self.endian
+    }
+
+    fn set_endian(&mut self, endian: Endian) {
+        self.set_endian(endian)
+    }
+
+    fn get_rpos(&self) -> usize {
+        self.rpos
+    }
+
+    #[cfg(feature = "alloc")]
+    fn read_bytes(&mut self, size: usize) -> Result<Vec<u8>> {
+        self.read_bytes(size)
+    }
+
+    fn read_u8(&mut self) -> Result<u8> {
+        self.read_u8()
+    }
+
+    fn read_i8(&mut self) -> Result<i8> {
+        self.read_i8()
+    }
+
+    fn read_u16(&mut self) -> Result<u16> {
+        self.read_u16()
+    }
+
+    fn read_i16(&mut self) -> Result<i16> {
+        self.read_i16()
+    }
+
+    fn read_u32(&mut self) -> Result<u32> {
+        self.read_u32()
+    }
+
+    fn read_i32(&mut self) -> Result<i32> {
+        self.read_i32()
+    }
+
+    fn read_u64(&mut self) -> Result<u64> {
+        self.read_u64()
+    }
+
+    fn read_i64(&mut self) -> Result<i64> {
+        self.read_i64()
+    }
+
+    fn read_u128(&mut self) -> Result<u128> {
+        self.read_u128()
+    }
+
+    fn read_i128(&mut self) -> Result<i128> {
+        self.read_i128()
+    }
+
+    fn read_f32(&mut self) -> Result<f32> {
+        self.read_f32()
+    }
+
+    fn read_f64(&mut self) -> Result<f64> {
+        self.read_f64()
+    }
+
+    #[cfg(feature = "half")]
+    fn read_f16(&mut self) -> Result<half::f16> {
+        self.read_f16()
+    }
+
+    #[cfg(feature = "half")]
+    fn read_bf16(&mut self) -> Result<half::bf16> {
+        self.read_bf16()
+    }
+
+    #[cfg(feature = "alloc")]
+    fn read_string(&mut self) -> Result<String> {
+        self.read_string()
+    }
+
+    #[cfg(feature = "alloc")]
+    fn read_prefixed_bytes(&mut self, prefix: LengthPrefix) -> Result<Vec<u8>> {
+        self.read_prefixed_bytes(prefix)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn read_prefixed_string(&mut self, prefix: LengthPrefix) -> Result<String> {
+        self.read_prefixed_string(prefix)
+    }
+
+    fn read_bit(&mut self) -> Result<bool> {
+        self.read_bit()
+    }
+
+    fn read_bits(&mut self, n: u8) -> Result<u64> {
+        self.read_bits(n)
+    }
+
+    fn read_bits_u128(&mut self, n: u8) -> Result<u128> {
+        self.read_bits_u128(n)
+    }
+
+    fn flush_bits(&mut self) {
+        self.flush_bits()
+    }
+}
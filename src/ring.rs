@@ -0,0 +1,745 @@
+use crate::{
+    bits, prefix, varint, BitOrder, ByteRead, ByteWrite, ByteWriter, Endian, Error, LengthPrefix,
+    Result,
+};
+use alloc::{string::String, vec, vec::Vec};
+use core::fmt::Debug;
+#[cfg(feature = "std")]
+use std::io::{Read, Write};
+
+/// Largest number of bytes touched by a bit operation: 128 bits after 7 bits already used
+const MAX_BITS_SPAN: usize = 17;
+
+/// What a `RingByteBuffer` does with a write that does not fit in its free space
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum RingOverflow {
+    /// The write returns an error and leaves the buffer untouched
+    #[default]
+    Reject,
+    /// The oldest bytes are dropped to make room for the write, as if they had been read
+    Overwrite,
+}
+
+/// A byte buffer backed by a fixed-capacity circular storage.
+///
+/// Values are read in the order they were written, and may wrap across the end of the storage
+/// transparently, so the memory used never grows. When the buffer is full, writes fail or
+/// overwrite the oldest bytes, as set with `set_overflow()`.
+///
+/// The cursors count the bytes read and written since the creation of the buffer, so that the
+/// offsets of the errors are positions in the whole stream. Typed values are read and written
+/// as with `ByteBuffer`, and the other encodings are available through the `ByteRead` and
+/// `ByteWrite` traits.
+///
+/// _Note_: The unary, Exp-Golomb and Rice codes of the `ByteWrite` trait are written in several
+/// bit writes, so the first bits of a code that does not fit may be written before the error.
+///
+/// #Example
+///
+/// ```
+/// #  use bytebuffer::*;
+/// let mut buffer = RingByteBuffer::with_capacity(4);
+/// buffer.write_u16(0x0102).unwrap();
+/// buffer.write_u16(0x0304).unwrap();
+/// assert!(buffer.write_u8(0x5).is_err()); // the buffer is full
+///
+/// assert_eq!(buffer.read_u8().unwrap(), 0x1);
+/// buffer.write_u8(0x5).unwrap(); // wraps to the start of the storage
+/// let value = buffer.read_u32().unwrap(); // value contains 0x02030405
+///
+/// buffer.set_overflow(RingOverflow::Overwrite);
+/// buffer.write_bytes(&[0x1, 0x2, 0x3, 0x4, 0x5]).unwrap_err(); // longer than the capacity
+/// buffer.write_u32(0x06070809).unwrap();
+/// buffer.write_u8(0xA).unwrap(); // drops 0x6
+/// let value = buffer.read_u32().unwrap(); // value contains 0x0708090A
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct RingByteBuffer {
+    data: Vec<u8>,
+    rpos: usize,
+    rbit: usize,
+    wpos: usize,
+    wbit: usize,
+    endian: Endian,
+    bit_order: BitOrder,
+    overflow: RingOverflow,
+}
+
+#[cfg(feature = "std")]
+impl Read for RingByteBuffer {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.flush_bits();
+        let read_len = core::cmp::min(self.wpos - self.rpos, buf.len());
+        self.copy_out(self.rpos, &mut buf[..read_len]);
+        self.rpos += read_len;
+        Ok(read_len)
+    }
+}
+
+/// Write as many bytes as fit, or up to the capacity with `RingOverflow::Overwrite`, dropping
+/// the oldest bytes.
+#[cfg(feature = "std")]
+impl Write for RingByteBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.flush_bits();
+        let write_len = match self.overflow {
+            RingOverflow::Reject => core::cmp::min(self.free(), buf.len()),
+            RingOverflow::Overwrite => core::cmp::min(self.capacity(), buf.len()),
+        };
+        self.write_bytes(&buf[..write_len])?;
+        Ok(write_len)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Debug for RingByteBuffer {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        let rpos = self.rpos + usize::from(self.rbit > 0);
+        let mut remaining_data = vec![0; self.wpos.saturating_sub(rpos)];
+        self.copy_out(rpos, &mut remaining_data);
+
+        write!(
+            f,
+            "RingByteBuffer {{ remaining_data: {:?}, capacity: {:?}, wpos: {:?}, rpos: {:?}, endian: {:?} }}",
+            remaining_data,
+            self.capacity(),
+            self.wpos,
+            self.rpos,
+            self.endian
+        )
+    }
+}
+
+macro_rules! ring_number {
+    ($read:ident, $write:ident, $type:ty) => {
+        #[doc = concat!("Read a `", stringify!($type), "` value, or return an error if not enough bytes are available.")]
+        /// _Note_: This method resets the read and write cursor for bitwise reading.
+        pub fn $read(&mut self) -> Result<$type> {
+            let mut buf = [0; core::mem::size_of::<$type>()];
+            self.read_into(&mut buf)?;
+            Ok(match self.endian {
+                Endian::BigEndian => <$type>::from_be_bytes(buf),
+                Endian::LittleEndian => <$type>::from_le_bytes(buf),
+            })
+        }
+
+        #[doc = concat!("Write a `", stringify!($type), "` value, or return an error if not enough space is left.")]
+        /// _Note_: This method resets the read and write cursor for bitwise reading.
+        pub fn $write(&mut self, val: $type) -> Result<()> {
+            match self.endian {
+                Endian::BigEndian => self.write_bytes(&val.to_be_bytes()),
+                Endian::LittleEndian => self.write_bytes(&val.to_le_bytes()),
+            }
+        }
+    };
+}
+
+impl RingByteBuffer {
+    /// Construct a new, empty, RingByteBuffer storing up to `capacity` bytes.
+    pub fn with_capacity(capacity: usize) -> RingByteBuffer {
+        RingByteBuffer {
+            data: vec![0; capacity],
+            rpos: 0,
+            rbit: 0,
+            wpos: 0,
+            wbit: 0,
+            endian: Endian::BigEndian,
+            bit_order: BitOrder::MsbFirst,
+            overflow: RingOverflow::Reject,
+        }
+    }
+
+    /// Return the number of bytes the buffer can store
+    pub fn capacity(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Return the number of bytes stored, including the bytes partially read or written
+    pub fn len(&self) -> usize {
+        self.wpos + usize::from(self.wbit > 0) - self.rpos
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return the number of bytes that can be written before the buffer is full
+    pub fn free(&self) -> usize {
+        self.capacity() - self.len()
+    }
+
+    /// Remove all the bytes and reinitialize the reading and writing cursors
+    pub fn clear(&mut self) {
+        self.rpos = 0;
+        self.rbit = 0;
+        self.wpos = 0;
+        self.wbit = 0;
+    }
+
+    /// Return the number of bytes read since the creation of the buffer
+    pub fn get_rpos(&self) -> usize {
+        self.rpos
+    }
+
+    /// Return the number of bytes written since the creation of the buffer
+    pub fn get_wpos(&self) -> usize {
+        self.wpos
+    }
+
+    /// Set the byte order of the buffer
+    ///
+    /// _Note_: By default the buffer uses big endian order
+    pub fn set_endian(&mut self, endian: Endian) {
+        self.endian = endian;
+    }
+
+    /// Returns the current byte order of the buffer
+    pub fn endian(&self) -> Endian {
+        self.endian
+    }
+
+    /// Set the order of the bits for the bit functions
+    ///
+    /// _Note_: By default the bits are read and written most significant bit first
+    ///
+    /// _Note_: The order applies to whole bytes, so this method resets the read and write cursor for bitwise reading.
+    pub fn set_bit_order(&mut self, bit_order: BitOrder) {
+        self.flush_bits();
+        self.bit_order = bit_order;
+    }
+
+    /// Returns the current bit order of the buffer
+    pub fn bit_order(&self) -> BitOrder {
+        self.bit_order
+    }
+
+    /// Set what a write that does not fit in the free space does
+    ///
+    /// _Note_: By default such writes are rejected
+    pub fn set_overflow(&mut self, overflow: RingOverflow) {
+        self.overflow = overflow;
+    }
+
+    /// Returns what a write that does not fit in the free space does
+    pub fn overflow(&self) -> RingOverflow {
+        self.overflow
+    }
+
+    /// Copy the stored bytes starting at the stream position `pos` into `buf`
+    fn copy_out(&self, pos: usize, buf: &mut [u8]) {
+        if buf.is_empty() {
+            return;
+        }
+        let start = pos % self.capacity();
+        let first = core::cmp::min(buf.len(), self.capacity() - start);
+        let (head, tail) = buf.split_at_mut(first);
+        head.copy_from_slice(&self.data[start..start + first]);
+        tail.copy_from_slice(&self.data[..tail.len()]);
+    }
+
+    /// Copy `bytes` into the storage at the stream position `pos`
+    fn copy_in(&mut self, pos: usize, bytes: &[u8]) {
+        if bytes.is_empty() {
+            return;
+        }
+        let start = pos % self.capacity();
+        let first = core::cmp::min(bytes.len(), self.capacity() - start);
+        let (head, tail) = bytes.split_at(first);
+        self.data[start..start + first].copy_from_slice(head);
+        self.data[..tail.len()].copy_from_slice(tail);
+    }
+
+    /// Make sure that `size` bytes can be written at the writing cursor, dropping the oldest
+    /// bytes if the buffer overwrites them, or return an error.
+    fn make_room(&mut self, size: usize) -> Result<()> {
+        let end = self.rpos + self.capacity();
+        if size <= end - self.wpos {
+            return Ok(());
+        }
+        match self.overflow {
+            RingOverflow::Overwrite if size <= self.capacity() => {
+                self.rpos = self.wpos + size - self.capacity();
+                self.rbit = 0;
+                Ok(())
+            }
+            _ => Err(Error::full(size, self.wpos, end)),
+        }
+    }
+
+    fn read_into(&mut self, buf: &mut [u8]) -> Result<()> {
+        self.flush_bits();
+        if buf.len() > self.wpos - self.rpos {
+            return Err(Error::eof(buf.len(), self.rpos, self.wpos));
+        }
+        self.copy_out(self.rpos, buf);
+        self.rpos += buf.len();
+        Ok(())
+    }
+
+    // Write operations
+
+    /// Write a byte array to the buffer, or return an error if not enough space is left.
+    /// _Note_: This method resets the read and write cursor for bitwise reading.
+    pub fn write_bytes(&mut self, bytes: &[u8]) -> Result<()> {
+        self.flush_bits();
+        self.make_room(bytes.len())?;
+        self.copy_in(self.wpos, bytes);
+        self.wpos += bytes.len();
+        Ok(())
+    }
+
+    /// Write a byte (8 bits value) to the buffer, or return an error if not enough space is
+    /// left.
+    /// _Note_: This method resets the read and write cursor for bitwise reading.
+    pub fn write_u8(&mut self, val: u8) -> Result<()> {
+        self.write_bytes(&[val])
+    }
+
+    /// Same as `write_u8()` but for signed values
+    /// _Note_: This method resets the read and write cursor for bitwise reading.
+    pub fn write_i8(&mut self, val: i8) -> Result<()> {
+        self.write_u8(val as u8)
+    }
+
+    /// Write a string, as a 32 bits value representing the size followed by the raw bytes,
+    /// or return an error if not enough space is left.
+    /// _Note_: This method resets the read and write cursor for bitwise reading.
+    pub fn write_string(&mut self, val: &str) -> Result<()> {
+        self.write_prefixed(val.as_bytes(), LengthPrefix::U32)
+    }
+
+    /// Write prefixed data, making room for all of it before writing anything, so that a write
+    /// that does not fit is rejected as a whole.
+    fn write_prefixed(&mut self, bytes: &[u8], prefix: LengthPrefix) -> Result<()> {
+        self.flush_bits();
+        let mut header = [0; varint::MAX_LEN];
+        let mut writer = ByteWriter::from_bytes(&mut header);
+        writer.set_endian(self.endian);
+        prefix::write_len(&mut writer, bytes, prefix, self.wpos)?;
+        let header_len = writer.get_wpos();
+
+        self.make_room(prefix::encoded_len(bytes.len(), prefix))?;
+        self.write_bytes(&header[..header_len])?;
+        self.write_bytes(bytes)?;
+        prefix::write_trailer(self, bytes.len(), prefix)
+    }
+
+    // Read operations
+
+    /// Read a defined amount of raw bytes, or return an error if not enough bytes are
+    /// available.
+    /// _Note_: This method resets the read and write cursor for bitwise reading.
+    pub fn read_bytes(&mut self, size: usize) -> Result<Vec<u8>> {
+        self.flush_bits();
+        // Check before allocating, so that a bogus size does not exhaust the memory
+        if size > self.wpos - self.rpos {
+            return Err(Error::eof(size, self.rpos, self.wpos));
+        }
+        let mut bytes = vec![0; size];
+        self.read_into(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Read one byte, or return an error if not enough bytes are available.
+    /// _Note_: This method resets the read and write cursor for bitwise reading.
+    pub fn read_u8(&mut self) -> Result<u8> {
+        let mut buf = [0; 1];
+        self.read_into(&mut buf)?;
+        Ok(buf[0])
+    }
+
+    /// Same as `read_u8()` but for signed values
+    pub fn read_i8(&mut self) -> Result<i8> {
+        Ok(self.read_u8()? as i8)
+    }
+
+    ring_number!(read_u16, write_u16, u16);
+    ring_number!(read_i16, write_i16, i16);
+    ring_number!(read_u32, write_u32, u32);
+    ring_number!(read_i32, write_i32, i32);
+    ring_number!(read_u64, write_u64, u64);
+    ring_number!(read_i64, write_i64, i64);
+    ring_number!(read_u128, write_u128, u128);
+    ring_number!(read_i128, write_i128, i128);
+    ring_number!(read_f32, write_f32, f32);
+    ring_number!(read_f64, write_f64, f64);
+
+    /// Read a string.
+    ///
+    /// _Note_: First it reads a 32 bits value representing the size, then 'size' raw bytes
+    ///         that  must be encoded as UTF8.
+    /// _Note_: This method resets the read and write cursor for bitwise reading.
+    pub fn read_string(&mut self) -> Result<String> {
+        prefix::read_prefixed_string(self, LengthPrefix::U32)
+    }
+
+    // Bit operations
+
+    /// Read 1 bit. Return true if the bit is set to 1, otherwhise, return false.
+    ///
+    /// _Note_: Bits are read from left to right, or from right to left with `BitOrder::LsbFirst`
+    pub fn read_bit(&mut self) -> Result<bool> {
+        Ok(self.read_bits_raw(1)? == 1)
+    }
+
+    /// Read n bits, up to 64, or return an error if not enough bits are available.
+    ///
+    /// _Note_: Bits are read from left to right, or from right to left with `BitOrder::LsbFirst`
+    pub fn read_bits(&mut self, n: u8) -> Result<u64> {
+        if n > 64 {
+            return Err(Error::TooManyBits {
+                requested: n as usize,
+                max: 64,
+            });
+        }
+        Ok(self.read_bits_raw(n.into())? as u64)
+    }
+
+    /// Read n bits, up to 128, or return an error if not enough bits are available.
+    ///
+    /// _Note_: Bits are read from left to right, or from right to left with `BitOrder::LsbFirst`
+    pub fn read_bits_u128(&mut self, n: u8) -> Result<u128> {
+        if n > 128 {
+            return Err(Error::TooManyBits {
+                requested: n as usize,
+                max: 128,
+            });
+        }
+        self.read_bits_raw(n.into())
+    }
+
+    fn read_bits_raw(&mut self, n: u32) -> Result<u128> {
+        // The reading cursor may be flushed past a byte still being written
+        let available = (self.wpos * 8 + self.wbit).saturating_sub(self.rpos * 8 + self.rbit);
+        if n as usize > available {
+            return Err(Error::eof(
+                bits::span(self.rbit, n),
+                self.rpos,
+                self.wpos + usize::from(self.wbit > 0),
+            ));
+        }
+        let mut buf = [0; MAX_BITS_SPAN];
+        let buf = &mut buf[..bits::span(self.rbit, n)];
+        self.copy_out(self.rpos, buf);
+        let value = bits::read(buf, 0, self.rbit, n, self.bit_order);
+        let end = self.rbit + n as usize;
+        self.rpos += end / 8;
+        self.rbit = end % 8;
+        Ok(value)
+    }
+
+    /// Write 1 bit, or return an error if not enough space is left.
+    pub fn write_bit(&mut self, bit: bool) -> Result<()> {
+        self.write_bits_raw(bit.into(), 1)
+    }
+
+    /// Write the given value as a sequence of n bits, up to 64, or return an error if not
+    /// enough space is left. Nothing is written in case of error.
+    pub fn write_bits(&mut self, value: u64, n: u8) -> Result<()> {
+        if n > 64 {
+            return Err(Error::TooManyBits {
+                requested: n as usize,
+                max: 64,
+            });
+        }
+        self.write_bits_raw(value.into(), n.into())
+    }
+
+    /// Write the given value as a sequence of n bits, up to 128, or return an error if not
+    /// enough space is left. Nothing is written in case of error.
+    pub fn write_bits_u128(&mut self, value: u128, n: u8) -> Result<()> {
+        if n > 128 {
+            return Err(Error::TooManyBits {
+                requested: n as usize,
+                max: 128,
+            });
+        }
+        self.write_bits_raw(value, n.into())
+    }
+
+    fn write_bits_raw(&mut self, value: u128, n: u32) -> Result<()> {
+        let span = bits::span(self.wbit, n);
+        self.make_room(span)?;
+        let mut buf = [0; MAX_BITS_SPAN];
+        let buf = &mut buf[..span];
+        // Keep the bits already written in the first byte
+        self.copy_out(self.wpos, &mut buf[..usize::from(self.wbit > 0)]);
+        bits::write(buf, 0, self.wbit, value, n, self.bit_order);
+        self.copy_in(self.wpos, buf);
+        let end = self.wbit + n as usize;
+        self.wpos += end / 8;
+        self.wbit = end % 8;
+        Ok(())
+    }
+
+    /// Discard all the pending bits available for reading or writing and place the
+    /// corresponding cursor to the next byte.
+    ///
+    /// _Note_: If no bits are currently read or written, this function does nothing.
+    pub fn flush_bits(&mut self) {
+        self.flush_wbits();
+        self.flush_rbits();
+    }
+
+    fn flush_rbits(&mut self) {
+        if self.rbit > 0 {
+            self.rpos += 1;
+            self.rbit = 0;
+        }
+    }
+
+    /// Reject `NulTerminated` data without its `0` byte before moving the reading cursor
+    fn check_terminated(&mut self, prefix: LengthPrefix) -> Result<()> {
+        self.flush_rbits();
+        let cap = self.capacity();
+        let unread = (self.rpos..self.wpos).map(|pos| self.data[pos % cap]);
+        prefix::check_terminated(prefix, unread, self.wpos)
+    }
+
+    fn flush_wbits(&mut self) {
+        if self.wbit > 0 {
+            self.wpos += 1;
+            self.wbit = 0;
+        }
+    }
+}
+
+#[cfg(feature = "half")]
+impl RingByteBuffer {
+    /// Read a 16 bits floating point value, or return an error if not enough bytes are available.
+    /// _Note_: This method resets the read and write cursor for bitwise reading.
+    pub fn read_f16(&mut self) -> Result<half::f16> {
+        Ok(half::f16::from_bits(self.read_u16()?))
+    }
+
+    /// Read a truncated 16 bits floating point value, or return an error if not enough bytes are available.
+    /// _Note_: This method resets the read and write cursor for bitwise reading.
+    pub fn read_bf16(&mut self) -> Result<half::bf16> {
+        Ok(half::bf16::from_bits(self.read_u16()?))
+    }
+
+    /// Write a 16 bits floating point number, or return an error if not enough space is left.
+    /// _Note_: This method resets the read and write cursor for bitwise reading.
+    pub fn write_f16(&mut self, val: half::f16) -> Result<()> {
+        self.write_u16(val.to_bits())
+    }
+
+    /// Write a truncated 16 bits floating point number, or return an error if not enough space is left.
+    /// _Note_: This method resets the read and write cursor for bitwise reading.
+    pub fn write_bf16(&mut self, val: half::bf16) -> Result<()> {
+        self.write_u16(val.to_bits())
+    }
+}
+
+impl ByteRead for RingByteBuffer {
+    fn endian(&self) -> Endian {
+        self.endian
+    }
+
+    fn set_endian(&mut self, endian: Endian) {
+        self.set_endian(endian)
+    }
+
+    fn get_rpos(&self) -> usize {
+        self.rpos
+    }
+
+    fn read_bytes(&mut self, size: usize) -> Result<Vec<u8>> {
+        self.read_bytes(size)
+    }
+
+    fn read_u8(&mut self) -> Result<u8> {
+        self.read_u8()
+    }
+
+    fn read_i8(&mut self) -> Result<i8> {
+        self.read_i8()
+    }
+
+    fn read_u16(&mut self) -> Result<u16> {
+        self.read_u16()
+    }
+
+    fn read_i16(&mut self) -> Result<i16> {
+        self.read_i16()
+    }
+
+    fn read_u32(&mut self) -> Result<u32> {
+        self.read_u32()
+    }
+
+    fn read_i32(&mut self) -> Result<i32> {
+        self.read_i32()
+    }
+
+    fn read_u64(&mut self) -> Result<u64> {
+        self.read_u64()
+    }
+
+    fn read_i64(&mut self) -> Result<i64> {
+        self.read_i64()
+    }
+
+    fn read_u128(&mut self) -> Result<u128> {
+        self.read_u128()
+    }
+
+    fn read_i128(&mut self) -> Result<i128> {
+        self.read_i128()
+    }
+
+    fn read_f32(&mut self) -> Result<f32> {
+        self.read_f32()
+    }
+
+    fn read_f64(&mut self) -> Result<f64> {
+        self.read_f64()
+    }
+
+    #[cfg(feature = "half")]
+    fn read_f16(&mut self) -> Result<half::f16> {
+        self.read_f16()
+    }
+
+    #[cfg(feature = "half")]
+    fn read_bf16(&mut self) -> Result<half::bf16> {
+        self.read_bf16()
+    }
+
+    fn read_string(&mut self) -> Result<String> {
+        self.read_string()
+    }
+
+    fn read_prefixed_bytes(&mut self, prefix: LengthPrefix) -> Result<Vec<u8>> {
+        self.check_terminated(prefix)?;
+        Ok(prefix::read_prefixed(self, prefix)?.1)
+    }
+
+    fn read_prefixed_string(&mut self, prefix: LengthPrefix) -> Result<String> {
+        self.check_terminated(prefix)?;
+        prefix::read_prefixed_string(self, prefix)
+    }
+
+    fn read_bit(&mut self) -> Result<bool> {
+        self.read_bit()
+    }
+
+    fn read_bits(&mut self, n: u8) -> Result<u64> {
+        self.read_bits(n)
+    }
+
+    fn read_bits_u128(&mut self, n: u8) -> Result<u128> {
+        self.read_bits_u128(n)
+    }
+
+    fn flush_bits(&mut self) {
+        self.flush_rbits()
+    }
+}
+
+impl ByteWrite for RingByteBuffer {
+    fn endian(&self) -> Endian {
+        self.endian
+    }
+
+    fn set_endian(&mut self, endian: Endian) {
+        self.set_endian(endian)
+    }
+
+    fn get_wpos(&self) -> usize {
+        self.wpos
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<()> {
+        self.write_bytes(bytes)
+    }
+
+    fn write_u8(&mut self, val: u8) -> Result<()> {
+        self.write_u8(val)
+    }
+
+    fn write_i8(&mut self, val: i8) -> Result<()> {
+        self.write_i8(val)
+    }
+
+    fn write_u16(&mut self, val: u16) -> Result<()> {
+        self.write_u16(val)
+    }
+
+    fn write_i16(&mut self, val: i16) -> Result<()> {
+        self.write_i16(val)
+    }
+
+    fn write_u32(&mut self, val: u32) -> Result<()> {
+        self.write_u32(val)
+    }
+
+    fn write_i32(&mut self, val: i32) -> Result<()> {
+        self.write_i32(val)
+    }
+
+    fn write_u64(&mut self, val: u64) -> Result<()> {
+        self.write_u64(val)
+    }
+
+    fn write_i64(&mut self, val: i64) -> Result<()> {
+        self.write_i64(val)
+    }
+
+    fn write_u128(&mut self, val: u128) -> Result<()> {
+        self.write_u128(val)
+    }
+
+    fn write_i128(&mut self, val: i128) -> Result<()> {
+        self.write_i128(val)
+    }
+
+    fn write_f32(&mut self, val: f32) -> Result<()> {
+        self.write_f32(val)
+    }
+
+    fn write_f64(&mut self, val: f64) -> Result<()> {
+        self.write_f64(val)
+    }
+
+    #[cfg(feature = "half")]
+    fn write_f16(&mut self, val: half::f16) -> Result<()> {
+        self.write_f16(val)
+    }
+
+    #[cfg(feature = "half")]
+    fn write_bf16(&mut self, val: half::bf16) -> Result<()> {
+        self.write_bf16(val)
+    }
+
+    fn write_string(&mut self, val: &str) -> Result<()> {
+        self.write_string(val)
+    }
+
+    fn write_bit(&mut self, bit: bool) -> Result<()> {
+        self.write_bit(bit)
+    }
+
+    fn write_bits(&mut self, value: u64, n: u8) -> Result<()> {
+        self.write_bits(value, n)
+    }
+
+    fn write_bits_u128(&mut self, value: u128, n: u8) -> Result<()> {
+        self.write_bits_u128(value, n)
+    }
+
+    fn flush_bits(&mut self) {
+        self.flush_wbits()
+    }
+
+    fn write_prefixed_bytes(&mut self, bytes: &[u8], prefix: LengthPrefix) -> Result<()> {
+        self.write_prefixed(bytes, prefix)
+    }
+
+    fn write_prefixed_string(&mut self, val: &str, prefix: LengthPrefix) -> Result<()> {
+        self.write_prefixed(val.as_bytes(), prefix)
+    }
+}
@@ -0,0 +1,342 @@
+//! A compact binary `serde` serializer writing into any `ByteWrite` implementation.
+//!
+//! The format is not self-describing:
+//! - integers and floats are written with their fixed size, in the byte order of the writer
+//! - `bool` is a `u8` (`0` or `1`), `char` is a `u32`
+//! - strings and byte arrays are written like `write_string()`: `(u32)size + size * u8`
+//! - sequences and maps are prefixed by their number of elements as a `u32`
+//! - `Option` is a `u8` tag (`0` for `None`, `1` for `Some`) followed by the value
+//! - enum variants are prefixed by their index as a `u32`
+//! - structs and tuples are written field by field, without any prefix
+
+use crate::{ByteBuffer, ByteWrite, Error, LengthPrefix, Result};
+use alloc::{string::ToString, vec::Vec};
+use core::fmt::Display;
+use serde::{ser, Serialize};
+
+impl ser::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Error::Custom(msg.to_string())
+    }
+}
+
+/// A `serde` serializer writing values into a `ByteWrite` implementation.
+///
+/// #Example
+///
+/// ```
+/// # use bytebuffer::*;
+/// use serde::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct Point {
+///     x: u16,
+///     y: u16,
+/// }
+///
+/// let mut buffer = ByteBuffer::new();
+/// Point { x: 1, y: 2 }
+///     .serialize(&mut Serializer::new(&mut buffer))
+///     .unwrap();
+/// assert_eq!(buffer.as_bytes(), [0x0, 0x1, 0x0, 0x2]);
+/// ```
+pub struct Serializer<'a, W: ?Sized> {
+    writer: &'a mut W,
+}
+
+impl<'a, W: ByteWrite + ?Sized> Serializer<'a, W> {
+    /// Construct a new Serializer writing into `writer`, using its byte order.
+    pub fn new(writer: &'a mut W) -> Self {
+        Serializer { writer }
+    }
+
+    fn write_len(&mut self, len: Option<usize>) -> Result<()> {
+        let len = len.ok_or_else(|| ser::Error::custom("the length must be known"))?;
+        let offset = self.writer.get_wpos();
+        let len = u32::try_from(len).map_err(|_| Error::LimitExceeded {
+            value: len as u64,
+            limit: u32::MAX.into(),
+            offset,
+        })?;
+        self.writer.write_u32(len)
+    }
+}
+
+/// Serialize `value` into a new vector, using big endian byte order.
+pub fn to_vec<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>> {
+    let mut buffer = ByteBuffer::new();
+    value.serialize(&mut Serializer::new(&mut buffer))?;
+    Ok(buffer.into_vec())
+}
+
+impl<'b, W: ByteWrite + ?Sized> ser::Serializer for &mut Serializer<'b, W> {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+
+    fn serialize_bool(self, v: bool) -> Result<()> {
+        self.writer.write_u8(v.into())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<()> {
+        self.writer.write_i8(v)
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<()> {
+        self.writer.write_i16(v)
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<()> {
+        self.writer.write_i32(v)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<()> {
+        self.writer.write_i64(v)
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<()> {
+        self.writer.write_i128(v)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<()> {
+        self.writer.write_u8(v)
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<()> {
+        self.writer.write_u16(v)
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<()> {
+        self.writer.write_u32(v)
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<()> {
+        self.writer.write_u64(v)
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<()> {
+        self.writer.write_u128(v)
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<()> {
+        self.writer.write_f32(v)
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<()> {
+        self.writer.write_f64(v)
+    }
+
+    fn serialize_char(self, v: char) -> Result<()> {
+        self.writer.write_u32(v.into())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<()> {
+        self.writer.write_prefixed_string(v, LengthPrefix::U32)
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<()> {
+        self.writer.write_prefixed_bytes(v, LengthPrefix::U32)
+    }
+
+    fn serialize_none(self) -> Result<()> {
+        self.writer.write_u8(0)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<()> {
+        self.writer.write_u8(1)?;
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<()> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<()> {
+        Ok(())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+    ) -> Result<()> {
+        self.writer.write_u32(variant_index)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        self.writer.write_u32(variant_index)?;
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self> {
+        self.write_len(len)?;
+        Ok(self)
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<Self> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self> {
+        self.writer.write_u32(variant_index)?;
+        Ok(self)
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self> {
+        self.write_len(len)?;
+        Ok(self)
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self> {
+        Ok(self)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self> {
+        self.writer.write_u32(variant_index)?;
+        Ok(self)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+impl<'b, W: ByteWrite + ?Sized> ser::SerializeSeq for &mut Serializer<'b, W> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'b, W: ByteWrite + ?Sized> ser::SerializeTuple for &mut Serializer<'b, W> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'b, W: ByteWrite + ?Sized> ser::SerializeTupleStruct for &mut Serializer<'b, W> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'b, W: ByteWrite + ?Sized> ser::SerializeTupleVariant for &mut Serializer<'b, W> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'b, W: ByteWrite + ?Sized> ser::SerializeMap for &mut Serializer<'b, W> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<()> {
+        key.serialize(&mut **self)
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'b, W: ByteWrite + ?Sized> ser::SerializeStruct for &mut Serializer<'b, W> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        _key: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'b, W: ByteWrite + ?Sized> ser::SerializeStructVariant for &mut Serializer<'b, W> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        _key: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,192 @@
+//! [`tokio_util::codec`] adapters splitting a byte stream into length-delimited frames.
+//!
+//! Each frame body is decoded from a `ByteReader` and encoded into a `ByteBuffer` by a
+//! [`FrameMessage`] implementation, so the same code works on top of `Framed`,
+//! `FramedRead` and `FramedWrite`.
+
+use crate::{ByteBuffer, ByteReader, Endian, Error, LengthPrefix, Result};
+use bytes::{Buf, BytesMut};
+use core::{marker::PhantomData, ops::Range};
+use tokio_util::codec::{Decoder, Encoder};
+
+/// A message carried in the body of a frame.
+///
+/// #Example
+///
+/// ```
+/// # use bytebuffer::*;
+/// struct Ping {
+///     id: u32,
+/// }
+///
+/// impl FrameMessage for Ping {
+///     fn decode_frame(reader: &mut ByteReader<'_>) -> Result<Self> {
+///         Ok(Ping { id: reader.read_u32()? })
+///     }
+///
+///     fn encode_frame(&self, buffer: &mut ByteBuffer) -> Result<()> {
+///         buffer.write_u32(self.id);
+///         Ok(())
+///     }
+/// }
+/// ```
+pub trait FrameMessage: Sized {
+    /// Decode a message from a reader over the frame body, with the byte order of the codec.
+    fn decode_frame(reader: &mut ByteReader<'_>) -> Result<Self>;
+
+    /// Encode the message into an empty buffer, with the byte order of the codec. The buffer
+    /// content becomes the frame body.
+    fn encode_frame(&self, buffer: &mut ByteBuffer) -> Result<()>;
+}
+
+/// A `Decoder` and `Encoder` for frames made of a length, encoded as described by a
+/// `LengthPrefix`, followed by a body holding one `FrameMessage`.
+///
+/// `LengthPrefix::Fixed(n)` splits the stream into frames of `n` bytes, and
+/// `LengthPrefix::NulTerminated` into frames ending with a `0` byte.
+///
+/// #Example
+///
+/// ```ignore
+/// let mut framed = Framed::new(stream, FrameCodec::<Ping>::new(LengthPrefix::U16));
+/// framed.send(Ping { id: 1 }).await?;
+/// let ping = framed.next().await.unwrap()?;
+/// ```
+#[derive(Debug)]
+pub struct FrameCodec<M> {
+    prefix: LengthPrefix,
+    endian: Endian,
+    max_frame_length: usize,
+    message: PhantomData<fn() -> M>,
+}
+
+impl<M> Clone for FrameCodec<M> {
+    fn clone(&self) -> Self {
+        FrameCodec {
+            prefix: self.prefix,
+            endian: self.endian,
+            max_frame_length: self.max_frame_length,
+            message: PhantomData,
+        }
+    }
+}
+
+impl<M> FrameCodec<M> {
+    /// Construct a new codec whose frame lengths are encoded as described by `prefix`, in big
+    /// endian, with frame bodies of up to 8 MiB.
+    pub fn new(prefix: LengthPrefix) -> FrameCodec<M> {
+        FrameCodec {
+            prefix,
+            endian: Endian::BigEndian,
+            max_frame_length: 8 * 1024 * 1024,
+            message: PhantomData,
+        }
+    }
+
+    /// Return the byte order used for the frame lengths and the messages
+    pub fn endian(&self) -> Endian {
+        self.endian
+    }
+
+    /// Set the byte order used for the frame lengths and the messages
+    pub fn set_endian(&mut self, endian: Endian) {
+        self.endian = endian;
+    }
+
+    /// Return the largest accepted frame body length, in bytes
+    pub fn max_frame_length(&self) -> usize {
+        self.max_frame_length
+    }
+
+    /// Set the largest accepted frame body length, in bytes. Longer frames are rejected
+    /// before being buffered when decoding, and before being sent when encoding.
+    pub fn set_max_frame_length(&mut self, max_frame_length: usize) {
+        self.max_frame_length = max_frame_length;
+    }
+
+    fn check_length(&self, len: u64, offset: usize) -> Result<usize> {
+        match usize::try_from(len) {
+            Ok(len) if len <= self.max_frame_length => Ok(len),
+            _ => Err(Error::LimitExceeded {
+                value: len,
+                limit: self.max_frame_length as u64,
+                offset,
+            }),
+        }
+    }
+
+    /// Return the range of the body of the first frame of `src` and the length of the whole
+    /// frame, or `None` if the length itself is not complete yet.
+    fn frame(&self, src: &[u8]) -> Result<Option<(Range<usize>, usize)>> {
+        if let LengthPrefix::NulTerminated = self.prefix {
+            return Ok(match src.iter().position(|b| *b == 0) {
+                Some(len) => {
+                    self.check_length(len as u64, 0)?;
+                    Some((0..len, len + 1))
+                }
+                None => {
+                    self.check_length(src.len() as u64, 0)?;
+                    None
+                }
+            });
+        }
+
+        let mut reader = ByteReader::from_bytes(src);
+        reader.set_endian(self.endian);
+        let len = match self.prefix {
+            LengthPrefix::U8 => reader.read_u8().map(u64::from),
+            LengthPrefix::U16 => reader.read_u16().map(u64::from),
+            LengthPrefix::U32 => reader.read_u32().map(u64::from),
+            LengthPrefix::U64 => reader.read_u64(),
+            LengthPrefix::VarInt => reader.read_var_u64(),
+            LengthPrefix::Fixed(n) => Ok(n as u64),
+            LengthPrefix::NulTerminated => unreachable!(),
+        };
+        let len = match len {
+            Ok(len) => self.check_length(len, 0)?,
+            Err(Error::UnexpectedEof { .. }) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        let start = reader.get_rpos();
+        Ok(Some((start..start + len, start + len)))
+    }
+}
+
+impl<M: FrameMessage> Decoder for FrameCodec<M> {
+    type Item = M;
+    type Error = std::io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> std::io::Result<Option<M>> {
+        let Some((body, len)) = self.frame(src)? else {
+            return Ok(None);
+        };
+        if src.len() < len {
+            // Wait for the rest of the frame
+            src.reserve(len - src.len());
+            return Ok(None);
+        }
+
+        let mut reader = ByteReader::from_bytes(&src[body]);
+        reader.set_endian(self.endian);
+        let message = M::decode_frame(&mut reader);
+        src.advance(len);
+        Ok(Some(message?))
+    }
+}
+
+impl<M: FrameMessage> Encoder<M> for FrameCodec<M> {
+    type Error = std::io::Error;
+
+    fn encode(&mut self, item: M, dst: &mut BytesMut) -> std::io::Result<()> {
+        let mut body = ByteBuffer::new();
+        body.set_endian(self.endian);
+        item.encode_frame(&mut body)?;
+        self.check_length(body.len() as u64, dst.len())?;
+
+        let mut frame = ByteBuffer::new();
+        frame.set_endian(self.endian);
+        frame.write_prefixed_bytes(body.as_bytes(), self.prefix)?;
+        dst.extend_from_slice(frame.as_bytes());
+        Ok(())
+    }
+}
@@ -0,0 +1,52 @@
+//! Async extension traits for [`tokio::io`] streams.
+//!
+//! See [`AsyncByteReadExt`] and [`AsyncByteWriteExt`].
+
+use core::{
+    future::poll_fn,
+    pin::Pin,
+    task::{ready, Poll},
+};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+async fn read_exact<R: AsyncRead + Unpin + ?Sized>(
+    reader: &mut R,
+    buf: &mut [u8],
+) -> std::io::Result<()> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        let n = poll_fn(|cx| {
+            let mut read_buf = ReadBuf::new(&mut buf[filled..]);
+            ready!(Pin::new(&mut *reader).poll_read(cx, &mut read_buf))?;
+            Poll::Ready(Ok::<_, std::io::Error>(read_buf.filled().len()))
+        })
+        .await?;
+        if n == 0 {
+            return Err(std::io::ErrorKind::UnexpectedEof.into());
+        }
+        filled += n;
+    }
+    Ok(())
+}
+
+async fn write_all<W: AsyncWrite + Unpin + ?Sized>(
+    writer: &mut W,
+    buf: &[u8],
+) -> std::io::Result<()> {
+    let mut written = 0;
+    while written < buf.len() {
+        let n = poll_fn(|cx| Pin::new(&mut *writer).poll_write(cx, &buf[written..])).await?;
+        if n == 0 {
+            return Err(std::io::ErrorKind::WriteZero.into());
+        }
+        written += n;
+    }
+    Ok(())
+}
+
+async_ext!(
+    AsyncRead,
+    AsyncWrite,
+    "use bytebuffer::tokio_io::AsyncByteReadExt;",
+    "tokio::io::AsyncRead"
+);
@@ -0,0 +1,372 @@
+use crate::{bits, golomb, prefix, varint, Endian, LengthPrefix, Result};
+#[cfg(feature = "alloc")]
+use alloc::{string::String, vec::Vec};
+
+/// Typed read operations shared by every reader of the crate.
+///
+/// This allows a decoder to be written once and used with both an owned
+/// [`ByteBuffer`](crate::ByteBuffer) and a borrowed [`ByteReader`](crate::ByteReader).
+///
+/// #Example
+///
+/// ```
+/// # use bytebuffer::*;
+/// fn decode<R: ByteRead>(r: &mut R) -> bytebuffer::Result<(u8, u32)> {
+///     Ok((r.read_u8()?, r.read_u32()?))
+/// }
+///
+/// let data = vec![0x1, 0x0, 0x0, 0x0, 0x2];
+/// assert_eq!(decode(&mut ByteReader::from_bytes(&data)).unwrap(), (1, 2));
+/// assert_eq!(decode(&mut ByteBuffer::from_vec(data)).unwrap(), (1, 2));
+/// ```
+pub trait ByteRead {
+    /// Returns the current byte order of the reader
+    fn endian(&self) -> Endian;
+
+    /// Set the byte order of the reader
+    fn set_endian(&mut self, endian: Endian);
+
+    /// Return the position of the reading cursor
+    fn get_rpos(&self) -> usize;
+
+    /// Read a defined amount of raw bytes, or return an error if not enough bytes are
+    /// available.
+    #[cfg(feature = "alloc")]
+    fn read_bytes(&mut self, size: usize) -> Result<Vec<u8>>;
+
+    /// Read one byte, or return an error if not enough bytes are available.
+    fn read_u8(&mut self) -> Result<u8>;
+
+    /// Same as `read_u8()` but for signed values
+    fn read_i8(&mut self) -> Result<i8>;
+
+    /// Read a 2-bytes long value, or return an error if not enough bytes are available.
+    fn read_u16(&mut self) -> Result<u16>;
+
+    /// Same as `read_u16()` but for signed values
+    fn read_i16(&mut self) -> Result<i16>;
+
+    /// Read a four-bytes long value, or return an error if not enough bytes are available.
+    fn read_u32(&mut self) -> Result<u32>;
+
+    /// Same as `read_u32()` but for signed values
+    fn read_i32(&mut self) -> Result<i32>;
+
+    /// Read an eight bytes long value, or return an error if not enough bytes are available.
+    fn read_u64(&mut self) -> Result<u64>;
+
+    /// Same as `read_u64()` but for signed values
+    fn read_i64(&mut self) -> Result<i64>;
+
+    /// Read a sixteen bytes long value, or return an error if not enough bytes are available.
+    fn read_u128(&mut self) -> Result<u128>;
+
+    /// Same as `read_u128()` but for signed values
+    fn read_i128(&mut self) -> Result<i128>;
+
+    /// Read a 32 bits floating point value, or return an error if not enough bytes are available.
+    fn read_f32(&mut self) -> Result<f32>;
+
+    /// Read a 64 bits floating point value, or return an error if not enough bytes are available.
+    fn read_f64(&mut self) -> Result<f64>;
+
+    /// Read a 16 bits floating point value, or return an error if not enough bytes are available.
+    #[cfg(feature = "half")]
+    fn read_f16(&mut self) -> Result<half::f16>;
+
+    /// Read a truncated 16 bits floating point value, or return an error if not enough bytes are available.
+    #[cfg(feature = "half")]
+    fn read_bf16(&mut self) -> Result<half::bf16>;
+
+    /// Read a string.
+    ///
+    /// _Note_: First it reads a 32 bits value representing the size, then 'size' raw bytes
+    ///         that  must be encoded as UTF8.
+    #[cfg(feature = "alloc")]
+    fn read_string(&mut self) -> Result<String>;
+
+    /// Read 1 bit. Return true if the bit is set to 1, otherwhise, return false.
+    fn read_bit(&mut self) -> Result<bool>;
+
+    /// Read n bits, up to 64. an return the corresponding value an u64.
+    fn read_bits(&mut self, n: u8) -> Result<u64>;
+
+    /// Read n bits. an return the corresponding value an u128.
+    fn read_bits_u128(&mut self, n: u8) -> Result<u128>;
+
+    /// Read n bits, up to 64, as a two's complement value and extend its sign.
+    fn read_bits_signed(&mut self, n: u8) -> Result<i64> {
+        bits::read_signed(self, n)
+    }
+
+    /// Read a unary value: a run of 0 bits terminated by a 1 bit, returning the number of 0
+    /// bits. Fail if the run is longer than `u32::MAX` bits.
+    fn read_unary(&mut self) -> Result<u32> {
+        Ok(golomb::read_unary(self, u32::MAX.into())? as u32)
+    }
+
+    /// Read an unsigned Exponential-Golomb value, `ue(v)`.
+    fn read_exp_golomb(&mut self) -> Result<u64> {
+        golomb::read_unsigned(self)
+    }
+
+    /// Read a signed Exponential-Golomb value, `se(v)`.
+    fn read_signed_exp_golomb(&mut self) -> Result<i64> {
+        golomb::read_signed(self)
+    }
+
+    /// Read a Rice value with parameter `k`, up to 64: a unary quotient followed by a `k` bits
+    /// remainder.
+    fn read_rice(&mut self, k: u8) -> Result<u64> {
+        golomb::read_rice(self, k)
+    }
+
+    /// Discard all the pending bits available for reading and place the cursor to the next byte.
+    ///
+    /// _Note_: Only the reading cursor is flushed, unlike the inherent `ByteBuffer::flush_bits()`
+    /// which flushes both cursors. Use `ByteWrite::flush_bits()` for the writing cursor.
+    fn flush_bits(&mut self);
+
+    /// Read an unsigned LEB128 variable-length value.
+    fn read_var_u32(&mut self) -> Result<u32> {
+        Ok(varint::read_unsigned(self, 32)? as u32)
+    }
+
+    /// Read an unsigned LEB128 variable-length value.
+    fn read_var_u64(&mut self) -> Result<u64> {
+        Ok(varint::read_unsigned(self, 64)? as u64)
+    }
+
+    /// Read an unsigned LEB128 variable-length value.
+    fn read_var_u128(&mut self) -> Result<u128> {
+        varint::read_unsigned(self, 128)
+    }
+
+    /// Read a signed LEB128 variable-length value.
+    fn read_var_i32(&mut self) -> Result<i32> {
+        Ok(varint::read_signed(self, 32)? as i32)
+    }
+
+    /// Read a signed LEB128 variable-length value.
+    fn read_var_i64(&mut self) -> Result<i64> {
+        Ok(varint::read_signed(self, 64)? as i64)
+    }
+
+    /// Read a signed LEB128 variable-length value.
+    fn read_var_i128(&mut self) -> Result<i128> {
+        varint::read_signed(self, 128)
+    }
+
+    /// Read a ZigZag encoded LEB128 variable-length value.
+    fn read_zigzag_i32(&mut self) -> Result<i32> {
+        Ok(varint::zigzag_decode(varint::read_unsigned(self, 32)?) as i32)
+    }
+
+    /// Read a ZigZag encoded LEB128 variable-length value.
+    fn read_zigzag_i64(&mut self) -> Result<i64> {
+        Ok(varint::zigzag_decode(varint::read_unsigned(self, 64)?) as i64)
+    }
+
+    /// Read a ZigZag encoded LEB128 variable-length value.
+    fn read_zigzag_i128(&mut self) -> Result<i128> {
+        Ok(varint::zigzag_decode(varint::read_unsigned(self, 128)?))
+    }
+
+    /// Read a byte array whose length is encoded as described by `prefix`.
+    ///
+    /// _Note_: The readers of the crate look for the terminator of `NulTerminated` data before
+    /// reading it and leave the cursor untouched when it is missing. The default implementation
+    /// reads byte by byte and stops at the end of the data instead.
+    #[cfg(feature = "alloc")]
+    fn read_prefixed_bytes(&mut self, prefix: LengthPrefix) -> Result<Vec<u8>> {
+        Ok(prefix::read_prefixed(self, prefix)?.1)
+    }
+
+    /// Read a string whose length is encoded as described by `prefix`.
+    #[cfg(feature = "alloc")]
+    fn read_prefixed_string(&mut self, prefix: LengthPrefix) -> Result<String> {
+        prefix::read_prefixed_string(self, prefix)
+    }
+}
+
+/// Typed write operations shared by every writer of the crate.
+///
+/// Unlike the inherent methods of [`ByteBuffer`](crate::ByteBuffer), every write
+/// returns a `Result` so that fixed-size writers can report a lack of space.
+///
+/// #Example
+///
+/// ```
+/// # use bytebuffer::*;
+/// fn encode<W: ByteWrite>(w: &mut W) -> bytebuffer::Result<()> {
+///     w.write_u8(1)?;
+///     w.write_u32(2)
+/// }
+///
+/// let mut buffer = ByteBuffer::new();
+/// encode(&mut buffer).unwrap();
+/// assert_eq!(buffer.as_bytes(), [0x1, 0x0, 0x0, 0x0, 0x2]);
+/// ```
+pub trait ByteWrite {
+    /// Returns the current byte order of the writer
+    fn endian(&self) -> Endian;
+
+    /// Set the byte order of the writer
+    fn set_endian(&mut self, endian: Endian);
+
+    /// Return the writing cursor position
+    fn get_wpos(&self) -> usize;
+
+    /// Append a byte array.
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<()>;
+
+    /// Append a byte (8 bits value)
+    fn write_u8(&mut self, val: u8) -> Result<()>;
+
+    /// Same as `write_u8()` but for signed values
+    fn write_i8(&mut self, val: i8) -> Result<()>;
+
+    /// Append a word (16 bits value)
+    fn write_u16(&mut self, val: u16) -> Result<()>;
+
+    /// Same as `write_u16()` but for signed values
+    fn write_i16(&mut self, val: i16) -> Result<()>;
+
+    /// Append a double word (32 bits value)
+    fn write_u32(&mut self, val: u32) -> Result<()>;
+
+    /// Same as `write_u32()` but for signed values
+    fn write_i32(&mut self, val: i32) -> Result<()>;
+
+    /// Append a quad word (64 bits value)
+    fn write_u64(&mut self, val: u64) -> Result<()>;
+
+    /// Same as `write_u64()` but for signed values
+    fn write_i64(&mut self, val: i64) -> Result<()>;
+
+    /// Append an octo word (128 bits value)
+    fn write_u128(&mut self, val: u128) -> Result<()>;
+
+    /// Same as `write_u128()` but for signed values
+    fn write_i128(&mut self, val: i128) -> Result<()>;
+
+    /// Append a 32 bits floating point number.
+    fn write_f32(&mut self, val: f32) -> Result<()>;
+
+    /// Append a 64 bits floating point number.
+    fn write_f64(&mut self, val: f64) -> Result<()>;
+
+    /// Append a 16 bits floating point number.
+    #[cfg(feature = "half")]
+    fn write_f16(&mut self, val: half::f16) -> Result<()>;
+
+    /// Append a truncated 16 bits floating point number.
+    #[cfg(feature = "half")]
+    fn write_bf16(&mut self, val: half::bf16) -> Result<()>;
+
+    /// Append a string.
+    ///
+    /// *Format* The format is `(u32)size + size * (u8)characters`
+    fn write_string(&mut self, val: &str) -> Result<()>;
+
+    /// Append 1 bit value.
+    fn write_bit(&mut self, bit: bool) -> Result<()>;
+
+    /// Write the given value as a sequence of n bits, up to 64
+    fn write_bits(&mut self, value: u64, n: u8) -> Result<()>;
+
+    /// Write the given value as a sequence of n bits, up to 128
+    fn write_bits_u128(&mut self, value: u128, n: u8) -> Result<()>;
+
+    /// Write the given value as a two's complement sequence of n bits, up to 64, or return an
+    /// error if it does not fit.
+    fn write_bits_signed(&mut self, value: i64, n: u8) -> Result<()> {
+        bits::write_signed(self, value, n)
+    }
+
+    /// Write a unary value: `n` 0 bits followed by a 1 bit.
+    fn write_unary(&mut self, n: u32) -> Result<()> {
+        golomb::write_unary(self, n.into())
+    }
+
+    /// Write an unsigned Exponential-Golomb value, `ue(v)`.
+    fn write_exp_golomb(&mut self, val: u64) -> Result<()> {
+        golomb::write_unsigned(self, val)
+    }
+
+    /// Write a signed Exponential-Golomb value, `se(v)`.
+    fn write_signed_exp_golomb(&mut self, val: i64) -> Result<()> {
+        golomb::write_signed(self, val)
+    }
+
+    /// Write a Rice value with parameter `k`, up to 64, or return an error if its quotient is
+    /// larger than `u32::MAX`.
+    fn write_rice(&mut self, val: u64, k: u8) -> Result<()> {
+        golomb::write_rice(self, val, k)
+    }
+
+    /// Discard all the pending bits available for writing and place the cursor to the next byte.
+    ///
+    /// _Note_: Only the writing cursor is flushed, unlike the inherent `ByteBuffer::flush_bits()`
+    /// which flushes both cursors. Use `ByteRead::flush_bits()` for the reading cursor.
+    fn flush_bits(&mut self);
+
+    /// Append an unsigned LEB128 variable-length value.
+    fn write_var_u32(&mut self, val: u32) -> Result<()> {
+        self.write_var_u128(u128::from(val))
+    }
+
+    /// Append an unsigned LEB128 variable-length value.
+    fn write_var_u64(&mut self, val: u64) -> Result<()> {
+        self.write_var_u128(u128::from(val))
+    }
+
+    /// Append an unsigned LEB128 variable-length value.
+    fn write_var_u128(&mut self, val: u128) -> Result<()> {
+        let mut buf = [0; varint::MAX_LEN];
+        let len = varint::encode_unsigned(val, &mut buf);
+        self.write_bytes(&buf[..len])
+    }
+
+    /// Append a signed LEB128 variable-length value.
+    fn write_var_i32(&mut self, val: i32) -> Result<()> {
+        self.write_var_i128(i128::from(val))
+    }
+
+    /// Append a signed LEB128 variable-length value.
+    fn write_var_i64(&mut self, val: i64) -> Result<()> {
+        self.write_var_i128(i128::from(val))
+    }
+
+    /// Append a signed LEB128 variable-length value.
+    fn write_var_i128(&mut self, val: i128) -> Result<()> {
+        let mut buf = [0; varint::MAX_LEN];
+        let len = varint::encode_signed(val, &mut buf);
+        self.write_bytes(&buf[..len])
+    }
+
+    /// Append a ZigZag encoded LEB128 variable-length value.
+    fn write_zigzag_i32(&mut self, val: i32) -> Result<()> {
+        self.write_var_u128(varint::zigzag_encode(i128::from(val), 32))
+    }
+
+    /// Append a ZigZag encoded LEB128 variable-length value.
+    fn write_zigzag_i64(&mut self, val: i64) -> Result<()> {
+        self.write_var_u128(varint::zigzag_encode(i128::from(val), 64))
+    }
+
+    /// Append a ZigZag encoded LEB128 variable-length value.
+    fn write_zigzag_i128(&mut self, val: i128) -> Result<()> {
+        self.write_var_u128(varint::zigzag_encode(val, 128))
+    }
+
+    /// Append a byte array, with its length encoded as described by `prefix`.
+    fn write_prefixed_bytes(&mut self, bytes: &[u8], prefix: LengthPrefix) -> Result<()> {
+        prefix::write_prefixed(self, bytes, prefix)
+    }
+
+    /// Append a string, with its length encoded as described by `prefix`.
+    fn write_prefixed_string(&mut self, val: &str, prefix: LengthPrefix) -> Result<()> {
+        prefix::write_prefixed(self, val.as_bytes(), prefix)
+    }
+}
@@ -0,0 +1,127 @@
+//! LEB128 variable-length integer encoding.
+//!
+//! Values are accumulated in 128 bits and checked against the width of the target type, so
+//! every width shares the same encoder and decoder.
+
+use crate::{ByteRead, Error, Result};
+
+/// Maximum encoded size of a 128 bits value
+pub(crate) const MAX_LEN: usize = 19;
+
+/// Maximum encoded size of a 64 bits value
+#[cfg(feature = "alloc")]
+pub(crate) const MAX_U64_LEN: usize = 10;
+
+/// Encode an unsigned value, returning the number of bytes used in `buf`.
+pub(crate) fn encode_unsigned(mut val: u128, buf: &mut [u8; MAX_LEN]) -> usize {
+    let mut len = 0;
+    loop {
+        let byte = (val & 0x7F) as u8;
+        val >>= 7;
+        if val == 0 {
+            buf[len] = byte;
+            return len + 1;
+        }
+        buf[len] = byte | 0x80;
+        len += 1;
+    }
+}
+
+/// Encode an unsigned value in exactly `buf.len()` bytes, padding it with continuation bytes
+/// holding `0` bits, or return `false` if it does not fit.
+#[cfg(feature = "alloc")]
+pub(crate) fn encode_unsigned_padded(mut val: u128, buf: &mut [u8]) -> bool {
+    let Some(last) = buf.len().checked_sub(1) else {
+        return false;
+    };
+    for (i, byte) in buf.iter_mut().enumerate() {
+        *byte = (val & 0x7F) as u8;
+        val >>= 7;
+        if i < last {
+            *byte |= 0x80;
+        }
+    }
+    val == 0
+}
+
+/// Encode a signed value (SLEB128), returning the number of bytes used in `buf`.
+pub(crate) fn encode_signed(mut val: i128, buf: &mut [u8; MAX_LEN]) -> usize {
+    let mut len = 0;
+    loop {
+        let byte = (val & 0x7F) as u8;
+        val >>= 7;
+        if (val == 0 && byte & 0x40 == 0) || (val == -1 && byte & 0x40 != 0) {
+            buf[len] = byte;
+            return len + 1;
+        }
+        buf[len] = byte | 0x80;
+        len += 1;
+    }
+}
+
+/// Map a signed value to an unsigned one so that small magnitudes stay small.
+pub(crate) fn zigzag_encode(val: i128, bits: u32) -> u128 {
+    ((val << 1) ^ (val >> (bits - 1))) as u128 & (u128::MAX >> (128 - bits))
+}
+
+pub(crate) fn zigzag_decode(val: u128) -> i128 {
+    (val >> 1) as i128 ^ -((val & 1) as i128)
+}
+
+/// Decode an unsigned value that must fit in `bits` bits.
+pub(crate) fn read_unsigned<R: ByteRead + ?Sized>(reader: &mut R, bits: u32) -> Result<u128> {
+    reader.flush_bits();
+    let offset = reader.get_rpos();
+    let mut result = 0u128;
+    let mut shift = 0;
+    loop {
+        let byte = reader.read_u8()?;
+        let payload = u128::from(byte & 0x7F);
+        if shift + 7 > bits && payload >> (bits - shift) != 0 {
+            return Err(Error::VarIntOverflow { offset });
+        }
+        result |= payload << shift;
+        if byte & 0x80 == 0 {
+            return Ok(result);
+        }
+        shift += 7;
+        if shift >= bits {
+            return Err(Error::VarIntOverflow { offset });
+        }
+    }
+}
+
+/// Decode a signed value (SLEB128) that must fit in `bits` bits.
+pub(crate) fn read_signed<R: ByteRead + ?Sized>(reader: &mut R, bits: u32) -> Result<i128> {
+    reader.flush_bits();
+    let offset = reader.get_rpos();
+    let mut result = 0i128;
+    let mut shift = 0;
+    loop {
+        let byte = reader.read_u8()?;
+        let payload = byte & 0x7F;
+        if shift + 7 > bits {
+            // The unused high bits of the last byte must be a sign extension of the value
+            let valid = bits - shift;
+            let extension = if payload >> (valid - 1) & 1 != 0 {
+                0x7F >> valid
+            } else {
+                0
+            };
+            if payload >> valid != extension {
+                return Err(Error::VarIntOverflow { offset });
+            }
+        }
+        result |= i128::from(payload) << shift;
+        shift += 7;
+        if byte & 0x80 == 0 {
+            if shift < 128 && byte & 0x40 != 0 {
+                result |= -1i128 << shift;
+            }
+            return Ok(result);
+        }
+        if shift >= bits {
+            return Err(Error::VarIntOverflow { offset });
+        }
+    }
+}
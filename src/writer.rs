@@ -0,0 +1,540 @@
+use crate::{bits, prefix, BitOrder, ByteWrite, Endian, Error, LengthPrefix, Result};
+use byteorder::{BigEndian, ByteOrder, LittleEndian};
+use core::fmt::Debug;
+#[cfg(feature = "std")]
+use std::io::Write;
+
+/// A byte writer object writing binary values into a fixed-size, borrowed, slice.
+///
+/// Unlike `ByteBuffer`, the underlying storage never grows: a write that does not fit in the
+/// remaining space returns an error and leaves the slice untouched. This includes the
+/// prefixed data and the variable-length values written through the `ByteWrite` trait.
+///
+/// _Note_: The unary, Exp-Golomb and Rice codes of the `ByteWrite` trait are written in several
+/// bit writes, so the first bits of a code that does not fit may be written before the error.
+#[derive(PartialEq, Eq, Hash)]
+pub struct ByteWriter<'a> {
+    data: &'a mut [u8],
+    wpos: usize,
+    wbit: usize,
+    endian: Endian,
+    bit_order: BitOrder,
+}
+
+impl<'a> From<&'a mut [u8]> for ByteWriter<'a> {
+    fn from(val: &'a mut [u8]) -> Self {
+        ByteWriter::from_bytes(val)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Write for ByteWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.flush_bits();
+        let write_len = core::cmp::min(self.data.len() - self.wpos, buf.len());
+        self.data[self.wpos..self.wpos + write_len].copy_from_slice(&buf[..write_len]);
+        self.wpos += write_len;
+        Ok(write_len)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Debug for ByteWriter<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(
+            f,
+            "ByteWriter {{ written_data: {:?}, total_data: {:?}, wpos: {:?}, endian: {:?} }}",
+            &self.data[..self.wpos],
+            self.data,
+            self.wpos,
+            self.endian
+        )
+    }
+}
+
+impl<'a> ByteWriter<'a> {
+    /// Construct a new ByteWriter writing into the given slice.
+    pub fn from_bytes(bytes: &mut [u8]) -> ByteWriter<'_> {
+        ByteWriter {
+            data: bytes,
+            wpos: 0,
+            wbit: 0,
+            endian: Endian::BigEndian,
+            bit_order: BitOrder::MsbFirst,
+        }
+    }
+
+    /// Return the buffer size
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Reinitialize the writing cursor
+    pub fn reset_cursors(&mut self) {
+        self.wpos = 0;
+    }
+
+    /// Reinitialize the bit writing cursor
+    pub fn reset_bits_cursors(&mut self) {
+        self.wbit = 0;
+    }
+
+    /// Set the byte order of the buffer
+    ///
+    /// _Note_: By default the buffer uses big endian order
+    pub fn set_endian(&mut self, endian: Endian) {
+        self.endian = endian;
+    }
+
+    /// Returns the current byte order of the buffer
+    pub fn endian(&self) -> Endian {
+        self.endian
+    }
+
+    /// Set the order of the bits for the bit functions
+    ///
+    /// _Note_: By default the bits are read and written most significant bit first
+    ///
+    /// _Note_: The order applies to whole bytes, so this method resets the write cursor for bitwise writing.
+    pub fn set_bit_order(&mut self, bit_order: BitOrder) {
+        self.flush_bits();
+        self.bit_order = bit_order;
+    }
+
+    /// Returns the current bit order of the buffer
+    pub fn bit_order(&self) -> BitOrder {
+        self.bit_order
+    }
+
+    fn ensure_capacity(&self, size: usize) -> Result<()> {
+        if size > self.data.len() - self.wpos {
+            return Err(Error::full(size, self.wpos, self.data.len()));
+        }
+        Ok(())
+    }
+
+    // Write operations
+
+    /// Write a byte array to the buffer, or return an error if not enough space is left.
+    /// _Note_: This method resets the write cursor for bitwise writing.
+    ///
+    /// #Example
+    ///
+    /// ```
+    /// # use bytebuffer::*;
+    /// let mut data = [0; 3];
+    /// let mut writer = ByteWriter::from_bytes(&mut data);
+    /// writer.write_bytes(&vec![0x1, 0xFF, 0x45]).unwrap(); // data contains [0x1, 0xFF, 0x45]
+    /// assert!(writer.write_bytes(&[0x1]).is_err()); // no space left
+    /// ```
+    pub fn write_bytes(&mut self, bytes: &[u8]) -> Result<()> {
+        self.flush_bits();
+        self.ensure_capacity(bytes.len())?;
+        self.data[self.wpos..self.wpos + bytes.len()].copy_from_slice(bytes);
+        self.wpos += bytes.len();
+        Ok(())
+    }
+
+    /// Write a byte (8 bits value) to the buffer
+    /// _Note_: This method resets the write cursor for bitwise writing.
+    pub fn write_u8(&mut self, val: u8) -> Result<()> {
+        self.write_bytes(&[val])
+    }
+
+    /// Same as `write_u8()` but for signed values
+    /// _Note_: This method resets the write cursor for bitwise writing.
+    pub fn write_i8(&mut self, val: i8) -> Result<()> {
+        self.write_u8(val as u8)
+    }
+
+    /// Write a word (16 bits value) to the buffer
+    /// _Note_: This method resets the write cursor for bitwise writing.
+    pub fn write_u16(&mut self, val: u16) -> Result<()> {
+        let mut buf = [0; 2];
+
+        match self.endian {
+            Endian::BigEndian => BigEndian::write_u16(&mut buf, val),
+            Endian::LittleEndian => LittleEndian::write_u16(&mut buf, val),
+        };
+
+        self.write_bytes(&buf)
+    }
+
+    /// Same as `write_u16()` but for signed values
+    /// _Note_: This method resets the write cursor for bitwise writing.
+    pub fn write_i16(&mut self, val: i16) -> Result<()> {
+        self.write_u16(val as u16)
+    }
+
+    /// Write a double word (32 bits value) to the buffer
+    /// _Note_: This method resets the write cursor for bitwise writing.
+    pub fn write_u32(&mut self, val: u32) -> Result<()> {
+        let mut buf = [0; 4];
+
+        match self.endian {
+            Endian::BigEndian => BigEndian::write_u32(&mut buf, val),
+            Endian::LittleEndian => LittleEndian::write_u32(&mut buf, val),
+        };
+
+        self.write_bytes(&buf)
+    }
+
+    /// Same as `write_u32()` but for signed values
+    /// _Note_: This method resets the write cursor for bitwise writing.
+    pub fn write_i32(&mut self, val: i32) -> Result<()> {
+        self.write_u32(val as u32)
+    }
+
+    /// Write a quad word (64 bits value) to the buffer
+    /// _Note_: This method resets the write cursor for bitwise writing.
+    pub fn write_u64(&mut self, val: u64) -> Result<()> {
+        let mut buf = [0; 8];
+
+        match self.endian {
+            Endian::BigEndian => BigEndian::write_u64(&mut buf, val),
+            Endian::LittleEndian => LittleEndian::write_u64(&mut buf, val),
+        };
+
+        self.write_bytes(&buf)
+    }
+
+    /// Same as `write_u64()` but for signed values
+    /// _Note_: This method resets the write cursor for bitwise writing.
+    pub fn write_i64(&mut self, val: i64) -> Result<()> {
+        self.write_u64(val as u64)
+    }
+
+    /// Write an octo word (128 bits value) to the buffer
+    /// _Note_: This method resets the write cursor for bitwise writing.
+    pub fn write_u128(&mut self, val: u128) -> Result<()> {
+        let mut buf = [0; 16];
+
+        match self.endian {
+            Endian::BigEndian => BigEndian::write_u128(&mut buf, val),
+            Endian::LittleEndian => LittleEndian::write_u128(&mut buf, val),
+        };
+
+        self.write_bytes(&buf)
+    }
+
+    /// Same as `write_u128()` but for signed values
+    /// _Note_: This method resets the write cursor for bitwise writing.
+    pub fn write_i128(&mut self, val: i128) -> Result<()> {
+        self.write_u128(val as u128)
+    }
+
+    /// Write a 32 bits floating point number to the buffer.
+    /// _Note_: This method resets the write cursor for bitwise writing.
+    pub fn write_f32(&mut self, val: f32) -> Result<()> {
+        let mut buf = [0; 4];
+
+        match self.endian {
+            Endian::BigEndian => BigEndian::write_f32(&mut buf, val),
+            Endian::LittleEndian => LittleEndian::write_f32(&mut buf, val),
+        };
+
+        self.write_bytes(&buf)
+    }
+
+    /// Write a 64 bits floating point number to the buffer.
+    /// _Note_: This method resets the write cursor for bitwise writing.
+    pub fn write_f64(&mut self, val: f64) -> Result<()> {
+        let mut buf = [0; 8];
+
+        match self.endian {
+            Endian::BigEndian => BigEndian::write_f64(&mut buf, val),
+            Endian::LittleEndian => LittleEndian::write_f64(&mut buf, val),
+        };
+
+        self.write_bytes(&buf)
+    }
+
+    /// Write a string to the buffer.
+    /// _Note_: This method resets the write cursor for bitwise writing.
+    ///
+    /// *Format* The format is `(u32)size + size * (u8)characters`
+    ///
+    /// #Example
+    ///
+    /// ```
+    /// # use bytebuffer::*;
+    /// let mut data = [0; 9];
+    /// let mut writer = ByteWriter::from_bytes(&mut data);
+    /// writer.write_string("Hello").unwrap();
+    /// ```
+    pub fn write_string(&mut self, val: &str) -> Result<()> {
+        let size = u32::try_from(val.len()).map_err(|_| Error::LimitExceeded {
+            value: val.len() as u64,
+            limit: u32::MAX.into(),
+            offset: self.wpos,
+        })?;
+        self.flush_bits();
+        self.ensure_capacity(4 + val.len())?;
+        self.write_u32(size)?;
+        self.write_bytes(val.as_bytes())
+    }
+
+    // Other
+
+    /// Return the writing cursor position
+    pub fn get_wpos(&self) -> usize {
+        self.wpos
+    }
+
+    /// Set the writing cursor position.
+    /// _Note_: Sets the writing cursor to `min(newPosition, self.len())` to prevent overflow
+    pub fn set_wpos(&mut self, wpos: usize) {
+        self.wpos = core::cmp::min(wpos, self.data.len());
+    }
+
+    /// Return the position of the writing cursor in bits, counting from the start of the
+    /// data: `get_wpos() * 8` plus the bits already written in the current byte.
+    pub fn get_bit_wpos(&self) -> usize {
+        self.wpos * 8 + self.wbit
+    }
+
+    /// Set the writing cursor position in bits, counting from the start of the data.
+    /// _Note_: Sets the writing cursor to `min(newPosition, self.len() * 8)` to prevent overflow
+    pub fn set_bit_wpos(&mut self, pos: usize) {
+        let pos = core::cmp::min(pos, self.data.len() * 8);
+        self.wpos = pos / 8;
+        self.wbit = pos % 8;
+    }
+
+    /// Return the raw bytes of the underlying slice.
+    pub fn as_bytes(&self) -> &[u8] {
+        self.data
+    }
+
+    //Bit manipulation functions
+
+    /// Write 1 bit value to the buffer, or return an error if not enough space is left.
+    /// The bit is written like this :
+    ///
+    /// ```text
+    /// ...| XXXXXXXX | 10000000 |....
+    /// ```
+    pub fn write_bit(&mut self, bit: bool) -> Result<()> {
+        self.ensure_capacity(1)?;
+
+        let mask = self.bit_order.mask(self.wbit);
+        if bit {
+            self.data[self.wpos] |= mask;
+        } else {
+            self.data[self.wpos] &= !mask;
+        }
+
+        self.wbit += 1;
+
+        if self.wbit > 7 {
+            self.flush_wbits();
+        }
+        Ok(())
+    }
+
+    /// Write the given value as a sequence of n bits, or return an error if not enough
+    /// space is left. Nothing is written in case of error.
+    ///
+    /// _Note_: We cannot write more than 64 bits
+    ///
+    /// #Example
+    ///
+    /// ```
+    /// # use bytebuffer::*;
+    /// let mut data = [0; 1];
+    /// let mut writer = ByteWriter::from_bytes(&mut data);
+    /// writer.write_bits(4, 3).unwrap(); // write 100b
+    /// ```
+    #[inline]
+    pub fn write_bits(&mut self, value: u64, n: u8) -> Result<()> {
+        if n > 64 {
+            return Err(Error::TooManyBits {
+                requested: n as usize,
+                max: 64,
+            });
+        }
+        self.write_bits_raw(value.into(), n.into())
+    }
+
+    /// Write the given value as a sequence of n bits, or return an error if not enough
+    /// space is left. Nothing is written in case of error.
+    ///
+    /// _Note_: We cannot write more than 128 bits
+    ///
+    /// #Example
+    ///
+    /// ```
+    /// # use bytebuffer::*;
+    /// let mut data = [0; 9];
+    /// let mut writer = ByteWriter::from_bytes(&mut data);
+    /// writer.write_bits_u128(u128::MAX, 72).unwrap(); // write 72 bits set to 1
+    /// ```
+    #[inline]
+    pub fn write_bits_u128(&mut self, value: u128, n: u8) -> Result<()> {
+        if n > 128 {
+            return Err(Error::TooManyBits {
+                requested: n as usize,
+                max: 128,
+            });
+        }
+        self.write_bits_raw(value, n.into())
+    }
+
+    #[inline]
+    fn write_bits_raw(&mut self, value: u128, n: u32) -> Result<()> {
+        self.ensure_capacity(bits::span(self.wbit, n))?;
+        bits::write(self.data, self.wpos, self.wbit, value, n, self.bit_order);
+        let end = self.wbit + n as usize;
+        self.wpos += end / 8;
+        self.wbit = end % 8;
+        Ok(())
+    }
+
+    /// Discard all the pending bits available for writing and place the cursor to the next byte.
+    ///
+    /// _Note_: If no bits are currently written, this function does nothing.
+    pub fn flush_bits(&mut self) {
+        if self.wbit > 0 {
+            self.flush_wbits();
+        }
+    }
+
+    fn flush_wbits(&mut self) {
+        self.wpos += 1;
+        self.wbit = 0
+    }
+}
+
+#[cfg(feature = "half")]
+impl ByteWriter<'_> {
+    /// Write a 16 bits floating point number to the buffer.
+    /// _Note_: This method resets the write cursor for bitwise writing.
+    pub fn write_f16(&mut self, val: half::f16) -> Result<()> {
+        match self.endian {
+            Endian::BigEndian => self.write_bytes(&val.to_be_bytes()),
+            Endian::LittleEndian => self.write_bytes(&val.to_le_bytes()),
+        }
+    }
+
+    /// Write a truncated 16 bits floating point number to the buffer.
+    /// _Note_: This method resets the write cursor for bitwise writing.
+    pub fn write_bf16(&mut self, val: half::bf16) -> Result<()> {
+        match self.endian {
+            Endian::BigEndian => self.write_bytes(&val.to_be_bytes()),
+            Endian::LittleEndian => self.write_bytes(&val.to_le_bytes()),
+        }
+    }
+}
+
+impl ByteWrite for ByteWriter<'_> {
+    fn endian(&self) -> Endian {
+        self.endian
+    }
+
+    fn set_endian(&mut self, endian: Endian) {
+        self.set_endian(endian)
+    }
+
+    fn get_wpos(&self) -> usize {
+        self.wpos
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<()> {
+        self.write_bytes(bytes)
+    }
+
+    fn write_u8(&mut self, val: u8) -> Result<()> {
+        self.write_u8(val)
+    }
+
+    fn write_i8(&mut self, val: i8) -> Result<()> {
+        self.write_i8(val)
+    }
+
+    fn write_u16(&mut self, val: u16) -> Result<()> {
+        self.write_u16(val)
+    }
+
+    fn write_i16(&mut self, val: i16) -> Result<()> {
+        self.write_i16(val)
+    }
+
+    fn write_u32(&mut self, val: u32) -> Result<()> {
+        self.write_u32(val)
+    }
+
+    fn write_i32(&mut self, val: i32) -> Result<()> {
+        self.write_i32(val)
+    }
+
+    fn write_u64(&mut self, val: u64) -> Result<()> {
+        self.write_u64(val)
+    }
+
+    fn write_i64(&mut self, val: i64) -> Result<()> {
+        self.write_i64(val)
+    }
+
+    fn write_u128(&mut self, val: u128) -> Result<()> {
+        self.write_u128(val)
+    }
+
+    fn write_i128(&mut self, val: i128) -> Result<()> {
+        self.write_i128(val)
+    }
+
+    fn write_f32(&mut self, val: f32) -> Result<()> {
+        self.write_f32(val)
+    }
+
+    fn write_f64(&mut self, val: f64) -> Result<()> {
+        self.write_f64(val)
+    }
+
+    #[cfg(feature = "half")]
+    fn write_f16(&mut self, val: half::f16) -> Result<()> {
+        self.write_f16(val)
+    }
+
+    #[cfg(feature = "half")]
+    fn write_bf16(&mut self, val: half::bf16) -> Result<()> {
+        self.write_bf16(val)
+    }
+
+    fn write_string(&mut self, val: &str) -> Result<()> {
+        self.write_string(val)
+    }
+
+    fn write_bit(&mut self, bit: bool) -> Result<()> {
+        self.write_bit(bit)
+    }
+
+    fn write_bits(&mut self, value: u64, n: u8) -> Result<()> {
+        self.write_bits(value, n)
+    }
+
+    fn write_bits_u128(&mut self, value: u128, n: u8) -> Result<()> {
+        self.write_bits_u128(value, n)
+    }
+
+    fn flush_bits(&mut self) {
+        self.flush_bits()
+    }
+
+    fn write_prefixed_bytes(&mut self, bytes: &[u8], prefix: LengthPrefix) -> Result<()> {
+        self.flush_bits();
+        self.ensure_capacity(prefix::encoded_len(bytes.len(), prefix))?;
+        prefix::write_prefixed(self, bytes, prefix)
+    }
+
+    fn write_prefixed_string(&mut self, val: &str, prefix: LengthPrefix) -> Result<()> {
+        self.write_prefixed_bytes(val.as_bytes(), prefix)
+    }
+}
@@ -0,0 +1,434 @@
+use bytebuffer::{BitOrder, ByteBuffer, ByteRead, ByteReader, ByteWrite, ByteWriter, Error};
+
+#[test]
+fn test_default_bit_order() {
+    assert_eq!(ByteBuffer::new().bit_order(), BitOrder::MsbFirst);
+    assert_eq!(ByteReader::from_bytes(&[]).bit_order(), BitOrder::MsbFirst);
+    assert_eq!(
+        ByteWriter::from_bytes(&mut []).bit_order(),
+        BitOrder::MsbFirst
+    );
+}
+
+#[test]
+fn test_lsb_first_bit() {
+    let mut buffer = ByteBuffer::new();
+    buffer.set_bit_order(BitOrder::LsbFirst);
+    buffer.write_bit(true);
+    buffer.write_bit(false);
+    buffer.write_bit(true);
+    assert_eq!(buffer.as_bytes(), [0b0000_0101]);
+    assert!(buffer.read_bit().unwrap());
+    assert!(!buffer.read_bit().unwrap());
+    assert!(buffer.read_bit().unwrap());
+
+    let data = [0b0000_0001];
+    let mut reader = ByteReader::from_bytes(&data);
+    reader.set_bit_order(BitOrder::LsbFirst);
+    assert!(reader.read_bit().unwrap());
+    assert!(!reader.read_bit().unwrap());
+}
+
+#[test]
+fn test_lsb_first_across_bytes() {
+    // DEFLATE block header: BFINAL = 1, BTYPE = 2, then a 12 bits value
+    let mut buffer = ByteBuffer::new();
+    buffer.set_bit_order(BitOrder::LsbFirst);
+    buffer.write_bits(1, 1);
+    buffer.write_bits(2, 2);
+    buffer.write_bits(0xABC, 12);
+    assert_eq!(buffer.as_bytes(), [0b1110_0101, 0b0101_0101]);
+
+    let mut reader = ByteReader::from_bytes(buffer.as_bytes());
+    reader.set_bit_order(BitOrder::LsbFirst);
+    assert_eq!(reader.read_bits(1).unwrap(), 1);
+    assert_eq!(reader.read_bits(2).unwrap(), 2);
+    assert_eq!(reader.read_bits(12).unwrap(), 0xABC);
+
+    assert_eq!(buffer.read_bits(3).unwrap(), 0b101);
+    assert_eq!(buffer.read_bits(12).unwrap(), 0xABC);
+}
+
+#[test]
+fn test_lsb_first_64_bits() {
+    let mut buffer = ByteBuffer::new();
+    buffer.set_bit_order(BitOrder::LsbFirst);
+    buffer.write_bit(true);
+    buffer.write_bits(u64::MAX - 1, 64);
+    assert_eq!(buffer.read_bits(1).unwrap(), 1);
+    assert_eq!(buffer.read_bits(64).unwrap(), u64::MAX - 1);
+}
+
+#[test]
+fn test_mixed_orders() {
+    let mut buffer = ByteBuffer::new();
+    buffer.write_bits(0b101, 3);
+    buffer.set_bit_order(BitOrder::LsbFirst);
+    buffer.write_bits(0b110, 3);
+    buffer.set_bit_order(BitOrder::MsbFirst);
+    buffer.write_bits(0b1001, 4);
+    // Changing the order moves to the next byte
+    assert_eq!(buffer.as_bytes(), [0b1010_0000, 0b0000_0110, 0b1001_0000]);
+
+    let mut reader = ByteReader::from_bytes(buffer.as_bytes());
+    assert_eq!(reader.read_bits(3).unwrap(), 0b101);
+    reader.set_bit_order(BitOrder::LsbFirst);
+    assert_eq!(reader.read_bits(3).unwrap(), 0b110);
+    reader.set_bit_order(BitOrder::MsbFirst);
+    assert_eq!(reader.read_bits(4).unwrap(), 0b1001);
+}
+
+#[test]
+fn test_byte_values_ignore_bit_order() {
+    let mut buffer = ByteBuffer::new();
+    buffer.set_bit_order(BitOrder::LsbFirst);
+    buffer.write_bit(true);
+    buffer.write_u16(0x1234);
+    assert_eq!(buffer.as_bytes(), [0x1, 0x12, 0x34]);
+}
+
+#[test]
+fn test_writer_bit_order() {
+    let mut data = [0xFF; 2];
+    let mut writer = ByteWriter::from_bytes(&mut data);
+    writer.set_bit_order(BitOrder::LsbFirst);
+    writer.write_bits(0b0110, 4).unwrap();
+    writer.write_bits(0x1F, 5).unwrap();
+    writer.flush_bits();
+    assert_eq!(data, [0b1111_0110, 0b1111_1111]);
+
+    let mut buffer = ByteBuffer::new();
+    buffer.set_bit_order(BitOrder::LsbFirst);
+    buffer.write_bits(0b0110, 4);
+    buffer.write_bits(0x1F, 5);
+    assert_eq!(buffer.as_bytes(), [0b1111_0110, 0b0000_0001]);
+}
+
+/// Reference implementation moving one bit at a time
+fn reference_bits(data: &[u8], start: usize, n: usize, order: BitOrder) -> u128 {
+    let mut value = 0u128;
+    for i in 0..n {
+        let pos = start + i;
+        let bit = match order {
+            BitOrder::MsbFirst => data[pos / 8] >> (7 - pos % 8) & 1,
+            BitOrder::LsbFirst => data[pos / 8] >> (pos % 8) & 1,
+        };
+        value = match order {
+            BitOrder::MsbFirst => (value << 1) | u128::from(bit),
+            BitOrder::LsbFirst => value | (u128::from(bit) << i),
+        };
+    }
+    value
+}
+
+#[test]
+fn test_read_bits_matches_reference() {
+    let data: Vec<u8> = (0..40u32).map(|i| (i * 0x9E + 0x3B) as u8).collect();
+    for order in [BitOrder::MsbFirst, BitOrder::LsbFirst] {
+        for start in 0..16 {
+            for n in 0..=128u8 {
+                let mut reader = ByteReader::from_bytes(&data);
+                reader.set_bit_order(order);
+                reader.read_bits_u128(start as u8).unwrap();
+                let expected = reference_bits(&data, start, n as usize, order);
+                assert_eq!(reader.read_bits_u128(n).unwrap(), expected);
+                if n <= 64 {
+                    let mut buffer = ByteBuffer::from_bytes(&data);
+                    buffer.set_bit_order(order);
+                    buffer.read_bits_u128(start as u8).unwrap();
+                    assert_eq!(buffer.read_bits(n).unwrap(), expected as u64);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_write_bits_roundtrip() {
+    let value = 0x0123_4567_89AB_CDEF_FEDC_BA98_7654_3210u128;
+    for order in [BitOrder::MsbFirst, BitOrder::LsbFirst] {
+        for start in 0..9u8 {
+            for n in [1u8, 7, 8, 9, 31, 56, 57, 63, 64, 65, 100, 127, 128] {
+                let masked = value & (u128::MAX >> (128 - n as u32));
+                let mut buffer = ByteBuffer::new();
+                buffer.set_bit_order(order);
+                buffer.write_bits(u64::MAX, start);
+                buffer.write_bits_u128(value, n);
+                buffer.write_bits(0x5, 3);
+
+                let mut data = [0xFF; 20];
+                let mut writer = ByteWriter::from_bytes(&mut data);
+                writer.set_bit_order(order);
+                writer.write_bits(u64::MAX, start).unwrap();
+                writer.write_bits_u128(value, n).unwrap();
+                writer.write_bits(0x5, 3).unwrap();
+                writer.flush_bits();
+                let len = buffer.len();
+                assert_eq!(writer.get_wpos(), len);
+                // The writer keeps the trailing bits of its last byte
+                assert_eq!(data[..len - 1], buffer.as_bytes()[..len - 1]);
+
+                let total = start as usize + n as usize;
+                assert_eq!(
+                    reference_bits(buffer.as_bytes(), start as usize, n as usize, order),
+                    masked
+                );
+                assert_eq!(
+                    reference_bits(buffer.as_bytes(), total, 3, order),
+                    0x5,
+                    "{:?} {} {}",
+                    order,
+                    start,
+                    n
+                );
+                assert_eq!(buffer.read_bits(start).unwrap(), (1 << start) - 1);
+                assert_eq!(buffer.read_bits_u128(n).unwrap(), masked);
+                assert_eq!(buffer.read_bits(3).unwrap(), 0x5);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_write_bits_overwrites_only_its_bits() {
+    let mut buffer = ByteBuffer::from_bytes(&[0xFF, 0xFF, 0xFF]);
+    buffer.set_wpos(0);
+    buffer.write_bit(true);
+    buffer.write_bits(0, 14);
+    assert_eq!(buffer.as_bytes(), [0x80, 0x01, 0xFF]);
+
+    let mut data = [0x00; 3];
+    let mut writer = ByteWriter::from_bytes(&mut data);
+    writer.set_bit_order(BitOrder::LsbFirst);
+    writer.write_bit(false).unwrap();
+    writer.write_bits(u64::MAX, 14).unwrap();
+    assert_eq!(data, [0xFE, 0x7F, 0x00]);
+}
+
+#[test]
+fn test_bits_u128_limits() {
+    let data = [0xFF; 17];
+    let mut reader = ByteReader::from_bytes(&data);
+    assert!(matches!(
+        reader.read_bits_u128(129).unwrap_err(),
+        Error::TooManyBits {
+            requested: 129,
+            max: 128
+        }
+    ));
+    reader.read_bits(7).unwrap();
+    assert_eq!(reader.read_bits_u128(128).unwrap(), u128::MAX);
+    assert_eq!(reader.get_rpos(), 16);
+
+    let mut data = [0; 16];
+    let mut writer = ByteWriter::from_bytes(&mut data);
+    assert!(matches!(
+        writer.write_bits_u128(0, 129).unwrap_err(),
+        Error::TooManyBits { .. }
+    ));
+    writer.write_bit(true).unwrap();
+    assert!(matches!(
+        writer.write_bits_u128(0, 128).unwrap_err(),
+        Error::BufferFull { needed: 17, .. }
+    ));
+    writer.write_bits_u128(u128::MAX, 127).unwrap();
+    assert_eq!(data, [0xFF; 16]);
+}
+
+#[test]
+fn test_overread_keeps_cursor() {
+    let data = [0xAB, 0xCD];
+    let mut reader = ByteReader::from_bytes(&data);
+    reader.read_bits(4).unwrap();
+    assert_eq!(
+        reader.read_bits(13).unwrap_err(),
+        Error::UnexpectedEof {
+            needed: 3,
+            available: 2,
+            offset: 0
+        }
+    );
+    assert_eq!(reader.read_bits(12).unwrap(), 0xBCD);
+
+    let mut buffer = ByteBuffer::from_bytes(&data);
+    assert!(buffer.read_bits_u128(17).is_err());
+    assert_eq!(buffer.read_bits(16).unwrap(), 0xABCD);
+}
+
+#[test]
+fn test_generic_bits_u128() {
+    fn encode<W: ByteWrite>(w: &mut W) -> bytebuffer::Result<()> {
+        w.write_bits_u128(1 << 99, 100)?;
+        w.write_bits(3, 4)
+    }
+
+    fn decode<R: ByteRead>(r: &mut R) -> bytebuffer::Result<(u128, u64)> {
+        Ok((r.read_bits_u128(100)?, r.read_bits(4)?))
+    }
+
+    let mut buffer = ByteBuffer::new();
+    encode(&mut buffer).unwrap();
+    assert_eq!(buffer.len(), 13);
+    assert_eq!(decode(&mut buffer).unwrap(), (1 << 99, 3));
+
+    let mut buffer = ByteBuffer::new();
+    assert!(matches!(
+        ByteWrite::write_bits(&mut buffer, 0, 65).unwrap_err(),
+        Error::TooManyBits { .. }
+    ));
+}
+
+#[test]
+fn test_bits_signed() {
+    let mut buffer = ByteBuffer::new();
+    buffer.write_bits_signed(-2, 3).unwrap();
+    buffer.write_bits_signed(3, 3).unwrap();
+    buffer.write_bits_signed(-1, 1).unwrap();
+    buffer.write_bits_signed(0, 1).unwrap();
+    buffer.write_bits_signed(i64::MIN, 64).unwrap();
+    buffer.write_bits_signed(0, 0).unwrap();
+    assert_eq!(buffer.as_bytes()[0], 0b1100_1110);
+    assert_eq!(buffer.len(), 9);
+
+    let mut reader = ByteReader::from_bytes(buffer.as_bytes());
+    assert_eq!(reader.read_bits_signed(3).unwrap(), -2);
+    assert_eq!(reader.read_bits_signed(3).unwrap(), 3);
+    assert_eq!(reader.read_bits_signed(1).unwrap(), -1);
+    assert_eq!(reader.read_bits_signed(1).unwrap(), 0);
+    assert_eq!(reader.read_bits_signed(64).unwrap(), i64::MIN);
+    assert_eq!(reader.read_bits_signed(0).unwrap(), 0);
+
+    let mut buffer = ByteBuffer::new();
+    buffer.set_bit_order(BitOrder::LsbFirst);
+    for n in 1..=64u8 {
+        let (min, max) = (-1i64 << (n - 1), !(-1i64 << (n - 1)));
+        for value in [min, min / 2, -1, 0, 1, max / 2, max] {
+            let value = value.clamp(min, max);
+            buffer.write_bits_signed(value, n).unwrap();
+            assert_eq!(buffer.read_bits_signed(n).unwrap(), value);
+        }
+    }
+}
+
+#[test]
+fn test_bits_signed_out_of_range() {
+    let mut buffer = ByteBuffer::new();
+    buffer.write_u8(0);
+    for (value, n) in [(4, 3), (-5, 3), (1, 1), (1, 0), (i64::MIN, 63)] {
+        assert_eq!(
+            buffer.write_bits_signed(value, n).unwrap_err(),
+            Error::OutOfRange {
+                value,
+                bits: n as usize,
+                offset: 1
+            }
+        );
+    }
+    // Nothing is written when the value does not fit
+    assert_eq!(buffer.len(), 1);
+    assert!(matches!(
+        buffer.read_bits_signed(65).unwrap_err(),
+        Error::TooManyBits { .. }
+    ));
+
+    let mut data = [0; 1];
+    let mut writer = ByteWriter::from_bytes(&mut data);
+    writer.write_bits_signed(-4, 3).unwrap();
+    assert!(matches!(
+        writer.write_bits_signed(128, 8).unwrap_err(),
+        Error::OutOfRange { .. }
+    ));
+    assert!(matches!(
+        writer.write_bits_signed(-1, 65).unwrap_err(),
+        Error::TooManyBits { .. }
+    ));
+    writer.write_bits_signed(-1, 5).unwrap();
+    assert_eq!(data, [0b1001_1111]);
+}
+
+#[test]
+fn test_bit_cursors() {
+    let mut buffer = ByteBuffer::from_bytes(&[0xAB, 0xCD, 0xEF]);
+    assert_eq!(buffer.remaining_bits(), 24);
+    buffer.read_bits(5).unwrap();
+    assert_eq!(buffer.get_bit_rpos(), 5);
+    assert_eq!(buffer.remaining_bits(), 19);
+
+    // Backtrack to the middle of the first byte
+    buffer.set_bit_rpos(4);
+    assert_eq!(buffer.read_bits(8).unwrap(), 0xBC);
+    assert_eq!(buffer.get_rpos(), 1);
+    buffer.set_bit_rpos(100);
+    assert_eq!(buffer.get_bit_rpos(), 24);
+    assert_eq!(buffer.remaining_bits(), 0);
+
+    buffer.set_bit_wpos(12);
+    buffer.write_bits(0, 8);
+    assert_eq!(buffer.get_bit_wpos(), 20);
+    assert_eq!(buffer.as_bytes(), [0xAB, 0xC0, 0x0F]);
+    buffer.set_bit_wpos(100);
+    assert_eq!(buffer.get_bit_wpos(), 24);
+
+    let data = [0xAB, 0xCD];
+    let mut reader = ByteReader::from_bytes(&data);
+    reader.set_bit_order(BitOrder::LsbFirst);
+    reader.skip_bits(3).unwrap();
+    assert_eq!(reader.get_bit_rpos(), 3);
+    assert_eq!(reader.peek_bits(5).unwrap(), 0x15);
+    assert_eq!(reader.get_bit_rpos(), 3);
+    assert_eq!(reader.read_bits(9).unwrap(), 0x1B5);
+    assert_eq!(reader.remaining_bits(), 4);
+    reader.set_bit_rpos(0);
+    assert_eq!(reader.read_u8().unwrap(), 0xAB);
+
+    let mut data = [0; 2];
+    let mut writer = ByteWriter::from_bytes(&mut data);
+    writer.set_bit_wpos(6);
+    writer.write_bits(0xF, 4).unwrap();
+    assert_eq!(writer.get_bit_wpos(), 10);
+    assert_eq!(data, [0x03, 0xC0]);
+}
+
+#[test]
+fn test_skip_and_peek_errors() {
+    let data = [0xAB, 0xCD];
+    let mut reader = ByteReader::from_bytes(&data);
+    reader.read_bits(4).unwrap();
+    assert_eq!(
+        reader.skip_bits(13).unwrap_err(),
+        Error::UnexpectedEof {
+            needed: 3,
+            available: 2,
+            offset: 0
+        }
+    );
+    assert!(matches!(
+        reader.peek_bits(65).unwrap_err(),
+        Error::TooManyBits { .. }
+    ));
+    assert!(reader.peek_bits(13).is_err());
+    assert_eq!(reader.get_bit_rpos(), 4);
+    reader.skip_bits(12).unwrap();
+    assert_eq!(reader.remaining_bits(), 0);
+    reader.skip_bits(0).unwrap();
+
+    let mut reader = ByteReader::from_bytes(&data);
+    reader.read_bit().unwrap();
+    assert!(matches!(
+        reader.skip_bits(usize::MAX).unwrap_err(),
+        Error::UnexpectedEof { offset: 0, .. }
+    ));
+
+    let mut buffer = ByteBuffer::from_bytes(&data);
+    buffer.read_bit().unwrap();
+    assert!(matches!(
+        buffer.skip_bits(usize::MAX).unwrap_err(),
+        Error::UnexpectedEof { offset: 0, .. }
+    ));
+    buffer.set_bit_rpos(0);
+    assert!(buffer.skip_bits(17).is_err());
+    let shared = &buffer;
+    assert_eq!(shared.peek_bits(16).unwrap(), 0xABCD);
+    buffer.skip_bits(16).unwrap();
+    assert!(buffer.read_bit().is_err());
+}
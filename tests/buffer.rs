@@ -1,5 +1,5 @@
-use bytebuffer::{ByteBuffer, Endian};
-use std::io::{ErrorKind, Read, Write};
+use bytebuffer::{ByteBuffer, Endian, Error, LengthPrefix};
+use std::io::{Read, Write};
 
 #[test]
 fn test_api() {
@@ -15,7 +15,8 @@ fn test_api() {
     buffer.write_i64(1);
     buffer.write_u128(1);
     buffer.write_i128(1);
-    if cfg!(feature = "half") {
+    #[cfg(feature = "half")]
+    {
         buffer.write_bf16(half::bf16::from_f32(12.5));
         buffer.write_f16(half::f16::from_f32(12.5));
     }
@@ -40,7 +41,8 @@ fn test_api() {
     let _ = buffer.read_i64();
     let _ = buffer.read_u128();
     let _ = buffer.read_i128();
-    if cfg!(feature = "half") {
+    #[cfg(feature = "half")]
+    {
         let _ = buffer.read_bf16();
         let _ = buffer.read_f16();
     }
@@ -183,7 +185,7 @@ fn test_string_overread_protection() {
     let result = buffer.read_string();
     assert!(result.is_err());
     let error = result.err().unwrap();
-    assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+    assert!(matches!(error, Error::UnexpectedEof { .. }));
 }
 
 #[test]
@@ -284,7 +286,7 @@ fn test_cannot_read_bit_outside_data() {
     let result = buffer.read_bit();
     assert!(result.is_err());
     let error = result.err().unwrap();
-    assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+    assert!(matches!(error, Error::UnexpectedEof { .. }));
 }
 
 #[test]
@@ -300,7 +302,7 @@ fn test_cannot_read_more_than_64_bits() {
     let result = buffer.read_bits(73);
     assert!(result.is_err());
     let error = result.err().unwrap();
-    assert_eq!(error.kind(), ErrorKind::InvalidInput);
+    assert!(matches!(error, Error::TooManyBits { .. }));
 }
 
 #[test]
@@ -482,7 +484,7 @@ macro_rules! overread_tests {
                 let result = $value;
                 assert!(result.is_err());
                 let error = result.err().unwrap();
-                assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+                assert!(matches!(error, Error::UnexpectedEof { .. }));
             }
          )*
     }
@@ -522,3 +524,618 @@ fn test_multiple_write_bits() {
 
     assert_eq!(bytes.to_hex_dump(), "0x02 0x00 0x00 0x87");
 }
+
+#[test]
+fn test_try_decode_chunks() {
+    fn decode(buffer: &mut ByteBuffer) -> bytebuffer::Result<(u8, String, u64)> {
+        Ok((
+            buffer.read_bits(3)? as u8,
+            buffer.read_string()?,
+            buffer.read_var_u64()?,
+        ))
+    }
+
+    let mut message = ByteBuffer::new();
+    message.write_bits(5, 3);
+    message.write_string("hello");
+    message.write_var_u64(1 << 20);
+    let message = message.into_vec();
+
+    let mut buffer = ByteBuffer::new();
+    let mut decoded = Vec::new();
+    for chunk in message.chunks(2).chain(message.chunks(3)) {
+        buffer.write_bytes(chunk);
+        loop {
+            match buffer.try_decode(decode) {
+                Ok(value) => decoded.push(value),
+                Err(Error::Incomplete { needed }) => {
+                    assert!(needed > 0);
+                    assert_eq!(buffer.get_bit_rpos(), 0);
+                    break;
+                }
+                Err(e) => panic!("{}", e),
+            }
+            buffer.discard_consumed();
+        }
+    }
+    assert_eq!(decoded, vec![(5, "hello".to_string(), 1 << 20); 2]);
+    assert!(buffer.is_empty());
+}
+
+#[test]
+fn test_try_decode_rollback() {
+    let mut buffer = ByteBuffer::from_bytes(&[0xAA, 0x0, 0x0, 0x0, 0x4, 0xFF, 0xFF]);
+    buffer.read_bit().unwrap();
+
+    // The length is read, then the remaining bytes are missing
+    assert_eq!(
+        buffer.try_decode(|b| b.read_string()).unwrap_err(),
+        Error::Incomplete { needed: 2 }
+    );
+    assert_eq!(buffer.get_bit_rpos(), 1);
+
+    // Other errors are left as is, but rolled back too
+    // Byte writes flush the pending bits
+    buffer.write_bytes(&[0xFF, 0xFF]);
+    assert_eq!(buffer.get_bit_rpos(), 8);
+    assert_eq!(
+        buffer.try_decode(|b| b.read_string()).unwrap_err(),
+        Error::InvalidUtf8 { offset: 5 }
+    );
+    assert_eq!(buffer.get_bit_rpos(), 8);
+    assert_eq!(buffer.try_decode(|b| b.read_u32()).unwrap(), 4);
+    assert_eq!(buffer.get_rpos(), 5);
+}
+
+#[test]
+fn test_try_decode_sub_reader_eof() {
+    let mut buffer = ByteBuffer::from_bytes(&[0x0, 0x2, 0x1, 0x2, 0x9, 0x9]);
+    let error = buffer
+        .try_decode(|b| {
+            let len = b.read_u16()?;
+            b.read_sub_reader(len.into())?.read_u32()
+        })
+        .unwrap_err();
+    assert_eq!(
+        error,
+        Error::UnexpectedEof {
+            needed: 4,
+            available: 2,
+            offset: 0
+        }
+    );
+    assert_eq!(buffer.get_rpos(), 0);
+
+    let error = buffer
+        .try_decode(|b| {
+            b.read_u16()?;
+            b.read_sub_reader(8).map(|reader| reader.len())
+        })
+        .unwrap_err();
+    assert_eq!(error, Error::Incomplete { needed: 4 });
+}
+
+#[test]
+fn test_discard_consumed() {
+    let mut buffer = ByteBuffer::from_bytes(&[0x1, 0x2, 0xF0, 0x4]);
+    buffer.read_u16().unwrap();
+    buffer.read_bits(4).unwrap();
+    buffer.discard_consumed();
+    assert_eq!(buffer.as_bytes(), [0xF0, 0x4]);
+    assert_eq!(buffer.get_bit_rpos(), 4);
+    assert_eq!(buffer.get_wpos(), 2);
+    assert_eq!(buffer.read_bits(4).unwrap(), 0);
+    assert_eq!(buffer.read_u8().unwrap(), 0x4);
+
+    // With a writing cursor before the reading cursor, only the bytes before it are removed
+    let mut buffer = ByteBuffer::from_bytes(&[0x1, 0x2, 0x3]);
+    buffer.set_wpos(1);
+    buffer.set_rpos(2);
+    buffer.discard_consumed();
+    assert_eq!(buffer.as_bytes(), [0x2, 0x3]);
+    assert_eq!(buffer.get_wpos(), 0);
+    assert_eq!(buffer.get_rpos(), 1);
+}
+
+#[test]
+fn test_peek() {
+    let mut buffer = ByteBuffer::new();
+    buffer.write_u8(0xFF);
+    buffer.write_u16(0x0102);
+    buffer.write_u32(0x03040506);
+    buffer.write_u64(1);
+    buffer.write_u128(2);
+    buffer.write_f32(0.5);
+    buffer.write_f64(0.25);
+    buffer.write_string("hello");
+
+    assert_eq!(buffer.peek_u8().unwrap(), 0xFF);
+    assert_eq!(buffer.peek_i8().unwrap(), -1);
+    assert_eq!(buffer.peek_bytes(3).unwrap(), vec![0xFF, 0x1, 0x2]);
+    assert_eq!(buffer.read_u8().unwrap(), 0xFF);
+    assert!(matches!(
+        buffer.peek_bytes(usize::MAX),
+        Err(Error::UnexpectedEof { offset: 1, .. })
+    ));
+    assert_eq!(buffer.peek_u16().unwrap(), 0x0102);
+    assert_eq!(buffer.peek_i16().unwrap(), 0x0102);
+    assert_eq!(buffer.read_u16().unwrap(), 0x0102);
+    assert_eq!(buffer.peek_u32().unwrap(), 0x03040506);
+    assert_eq!(buffer.peek_i32().unwrap(), 0x03040506);
+    assert_eq!(buffer.read_u32().unwrap(), 0x03040506);
+    assert_eq!(buffer.peek_u64().unwrap(), 1);
+    assert_eq!(buffer.peek_i64().unwrap(), 1);
+    assert_eq!(buffer.read_u64().unwrap(), 1);
+    assert_eq!(buffer.peek_u128().unwrap(), 2);
+    assert_eq!(buffer.peek_i128().unwrap(), 2);
+    assert_eq!(buffer.read_u128().unwrap(), 2);
+    assert_eq!(buffer.peek_f32().unwrap(), 0.5);
+    assert_eq!(buffer.read_f32().unwrap(), 0.5);
+    assert_eq!(buffer.peek_f64().unwrap(), 0.25);
+    assert_eq!(buffer.read_f64().unwrap(), 0.25);
+    assert_eq!(buffer.peek_string().unwrap(), "hello");
+    assert_eq!(buffer.read_string().unwrap(), "hello");
+    assert!(buffer.peek_u8().is_err());
+}
+
+#[test]
+fn test_peek_little_endian() {
+    let mut buffer = ByteBuffer::from_bytes(&[0x1, 0x2, 0x3, 0x4]);
+    buffer.set_endian(Endian::LittleEndian);
+    assert_eq!(buffer.peek_u16().unwrap(), 0x0201);
+    assert_eq!(buffer.peek_u32().unwrap(), 0x04030201);
+    assert_eq!(buffer.get_rpos(), 0);
+}
+
+#[test]
+fn test_peek_after_bits() {
+    let mut buffer = ByteBuffer::from_bytes(&[0x80, 0x1, 0x2]);
+    assert!(buffer.read_bit().unwrap());
+    // Peeking reads the next whole byte, as a read would, but keeps the bit cursor
+    assert_eq!(buffer.peek_u16().unwrap(), 0x0102);
+    assert_eq!(buffer.get_bit_rpos(), 1);
+    assert_eq!(buffer.read_u16().unwrap(), 0x0102);
+}
+
+#[test]
+fn test_peek_overread_protection() {
+    let buffer = ByteBuffer::from_bytes(&[0x1, 0x2, 0x3]);
+    assert!(matches!(
+        buffer.peek_u32(),
+        Err(Error::UnexpectedEof {
+            needed: 4,
+            offset: 0,
+            available: 3,
+        })
+    ));
+    assert!(buffer.peek_bytes(4).is_err());
+    assert!(buffer.peek_string().is_err());
+    assert_eq!(buffer.get_rpos(), 0);
+}
+
+#[test]
+fn test_read_sub_reader() {
+    let mut buffer = ByteBuffer::new();
+    buffer.set_endian(Endian::LittleEndian);
+    buffer.write_u8(4);
+    buffer.write_u32(0x01020304);
+    buffer.write_u8(0xFF);
+
+    let len = buffer.read_u8().unwrap() as usize;
+    let mut section = buffer.read_sub_reader(len).unwrap();
+    assert_eq!(section.endian(), Endian::LittleEndian);
+    assert_eq!(section.read_u32().unwrap(), 0x01020304);
+    assert!(section.read_u8().is_err());
+    section.ensure_consumed().unwrap();
+    assert_eq!(buffer.read_u8().unwrap(), 0xFF);
+    assert!(buffer.read_sub_reader(1).is_err());
+    assert!(buffer.read_sub_reader(usize::MAX).is_err());
+    assert_eq!(buffer.read_sub_reader(0).unwrap().len(), 0);
+}
+
+#[test]
+fn test_reserve_field() {
+    let mut buffer = ByteBuffer::new();
+    buffer.set_endian(Endian::LittleEndian);
+    let len = buffer.reserve_field::<u32>().unwrap();
+    assert_eq!(len.offset(), 0);
+    assert_eq!(len.size(), 4);
+    buffer.write_u16(0x0102);
+    let checksum = buffer.reserve_field::<u64>().unwrap();
+    let tag = buffer.reserve_field::<u16>().unwrap();
+    buffer.write_bits(0b101, 3);
+
+    buffer.patch_u32(len, 14).unwrap();
+    buffer.patch_u64(checksum, 0x0A0B).unwrap();
+    buffer.patch_u16(tag, 0xFFFF).unwrap();
+    buffer.patch_field(tag, &0x0C0Du16).unwrap();
+    // Patching keeps the bit writing cursor
+    assert_eq!(buffer.get_bit_wpos(), 16 * 8 + 3);
+    buffer.write_bits(0b11111, 5);
+
+    assert_eq!(
+        buffer.as_bytes(),
+        [14, 0, 0, 0, 0x2, 0x1, 0xB, 0xA, 0, 0, 0, 0, 0, 0, 0xD, 0xC, 0xBF]
+    );
+}
+
+#[test]
+fn test_patch_errors() {
+    let mut buffer = ByteBuffer::new();
+    let field = buffer.reserve_field::<u32>().unwrap();
+    buffer.clear();
+    assert_eq!(
+        buffer.patch_u32(field, 1),
+        Err(Error::BufferFull {
+            needed: 4,
+            available: 0,
+            offset: 0,
+        })
+    );
+
+    let mut buffer = ByteBuffer::new();
+    buffer.write_u8(0x1);
+    let name = buffer.reserve_field::<String>().unwrap();
+    assert_eq!(
+        buffer.patch_field(name, &"ab".to_string()),
+        Err(Error::SizeMismatch {
+            expected: 4,
+            found: 6,
+            offset: 1,
+        })
+    );
+    assert_eq!(buffer.as_bytes(), [0x1, 0, 0, 0, 0]);
+}
+
+#[test]
+fn test_patch_var() {
+    let mut buffer = ByteBuffer::new();
+    let len = buffer.reserve_field::<u32>().unwrap();
+    buffer.write_u8(0xFF);
+    buffer.patch_var(len, 300).unwrap();
+    assert_eq!(buffer.as_bytes(), [0xAC, 0x82, 0x80, 0x00, 0xFF]);
+    assert_eq!(buffer.read_var_u32().unwrap(), 300);
+    assert_eq!(buffer.read_u8().unwrap(), 0xFF);
+
+    buffer.patch_var(len, (1 << 28) - 1).unwrap();
+    assert_eq!(
+        buffer.patch_var(len, 1 << 28),
+        Err(Error::LimitExceeded {
+            value: 1 << 28,
+            limit: (1 << 28) - 1,
+            offset: 0,
+        })
+    );
+
+    let mut buffer = ByteBuffer::new();
+    let len = buffer.reserve_field::<u128>().unwrap();
+    assert!(matches!(
+        buffer.patch_var(len, 0),
+        Err(Error::LimitExceeded {
+            value: 16,
+            limit: 10,
+            ..
+        })
+    ));
+    let len = buffer.reserve_field::<u64>().unwrap();
+    buffer.patch_var(len, u64::MAX >> 8).unwrap();
+    assert!(buffer.patch_var(len, u64::MAX).is_err());
+    buffer.set_rpos(16);
+    assert_eq!(buffer.read_var_u64().unwrap(), u64::MAX >> 8);
+}
+
+#[test]
+fn test_write_length_prefixed() {
+    let prefixes = [
+        LengthPrefix::U8,
+        LengthPrefix::U16,
+        LengthPrefix::U32,
+        LengthPrefix::U64,
+        LengthPrefix::VarInt,
+        LengthPrefix::NulTerminated,
+        LengthPrefix::Fixed(6),
+    ];
+    for prefix in prefixes {
+        let mut buffer = ByteBuffer::new();
+        buffer.set_endian(Endian::LittleEndian);
+        buffer.write_u8(0xAA);
+        let value = buffer
+            .write_length_prefixed(prefix, |buffer| {
+                buffer.write_bits(0b1, 1);
+                buffer.write_u16(0x0102);
+                Ok(3)
+            })
+            .unwrap();
+        assert_eq!(value, 3);
+        buffer.write_u8(0xBB);
+
+        let mut expected = ByteBuffer::new();
+        expected.set_endian(Endian::LittleEndian);
+        expected.write_u8(0xAA);
+        expected
+            .write_prefixed_bytes(&[0x80, 0x2, 0x1], prefix)
+            .unwrap();
+        expected.write_u8(0xBB);
+        assert_eq!(buffer.as_bytes(), expected.as_bytes(), "{:?}", prefix);
+        assert_eq!(buffer.get_wpos(), expected.get_wpos(), "{:?}", prefix);
+    }
+}
+
+#[test]
+fn test_write_length_prefixed_errors() {
+    let mut buffer = ByteBuffer::new();
+    assert!(matches!(
+        buffer.write_length_prefixed(LengthPrefix::U8, |buffer| {
+            buffer.write_bytes(&[0; 256]);
+            Ok(())
+        }),
+        Err(Error::LimitExceeded {
+            value: 256,
+            limit: 255,
+            offset: 0,
+        })
+    ));
+    // The length and the data are removed
+    assert!(buffer.is_empty());
+    assert_eq!(buffer.get_wpos(), 0);
+
+    let mut buffer = ByteBuffer::new();
+    buffer.write_u8(0x1);
+    assert_eq!(
+        buffer.write_length_prefixed(LengthPrefix::NulTerminated, |buffer| {
+            buffer.write_bytes(&[0x1, 0x0]);
+            Ok(())
+        }),
+        Err(Error::InteriorNul { offset: 2 })
+    );
+    assert_eq!(buffer.as_bytes(), [0x1]);
+    assert_eq!(buffer.get_wpos(), 1);
+
+    let mut buffer = ByteBuffer::new();
+    assert_eq!(
+        buffer.write_length_prefixed(LengthPrefix::U16, |_| -> Result<(), Error> {
+            Err(Error::InvalidValue { offset: 2 })
+        }),
+        Err(Error::InvalidValue { offset: 2 })
+    );
+    assert!(buffer.is_empty());
+
+    // A failing body is removed too, and the buffer can be written again
+    let mut buffer = ByteBuffer::new();
+    buffer.write_u8(0x1);
+    assert!(buffer
+        .write_length_prefixed(LengthPrefix::VarInt, |buffer| {
+            buffer.write_bits(0b1, 3);
+            buffer.read_u64()
+        })
+        .is_err());
+    buffer.write_u8(0x2);
+    assert_eq!(buffer.as_bytes(), [0x1, 0x2]);
+
+    // Data with a trailer is rolled back as a whole as well
+    let mut buffer = ByteBuffer::new();
+    buffer.write_u8(0x1);
+    assert!(matches!(
+        buffer.write_length_prefixed(LengthPrefix::Fixed(2), |buffer| {
+            buffer.write_bytes(&[0x2, 0x3, 0x4]);
+            Ok(())
+        }),
+        Err(Error::LimitExceeded { limit: 2, .. })
+    ));
+    assert_eq!(buffer.as_bytes(), [0x1]);
+    buffer
+        .write_length_prefixed(LengthPrefix::Fixed(3), |buffer| {
+            buffer.write_u8(0x2);
+            Ok(())
+        })
+        .unwrap();
+    buffer
+        .write_length_prefixed(LengthPrefix::NulTerminated, |buffer| {
+            buffer.write_u8(0x3);
+            Ok(())
+        })
+        .unwrap();
+    assert_eq!(buffer.as_bytes(), [0x1, 0x2, 0x0, 0x0, 0x3, 0x0]);
+    assert_eq!(buffer.get_wpos(), 6);
+}
+
+#[test]
+fn test_get_at() {
+    let mut buffer = ByteBuffer::new();
+    buffer.write_u8(0xFF);
+    buffer.write_u16(0x0102);
+    buffer.write_u32(0x03040506);
+    buffer.write_u64(0xFFFF_FFFF_FFFF_FFFE);
+    buffer.write_u128(1);
+    buffer.write_f32(0.5);
+    buffer.write_f64(-0.25);
+    buffer.read_bits(3).unwrap();
+
+    assert_eq!(buffer.get_u8_at(0).unwrap(), 0xFF);
+    assert_eq!(buffer.get_i8_at(0).unwrap(), -1);
+    assert_eq!(buffer.get_u16_at(1).unwrap(), 0x0102);
+    assert_eq!(buffer.get_i16_at(1).unwrap(), 0x0102);
+    assert_eq!(buffer.get_u32_at(3).unwrap(), 0x03040506);
+    assert_eq!(buffer.get_i32_at(3).unwrap(), 0x03040506);
+    assert_eq!(buffer.get_u64_at(7).unwrap(), 0xFFFF_FFFF_FFFF_FFFE);
+    assert_eq!(buffer.get_i64_at(7).unwrap(), -2);
+    assert_eq!(buffer.get_u128_at(15).unwrap(), 1);
+    assert_eq!(buffer.get_i128_at(15).unwrap(), 1);
+    assert_eq!(buffer.get_f32_at(31).unwrap(), 0.5);
+    assert_eq!(buffer.get_f64_at(35).unwrap(), -0.25);
+    assert_eq!(buffer.get_bit_rpos(), 3);
+
+    buffer.set_endian(Endian::LittleEndian);
+    assert_eq!(buffer.get_u16_at(1).unwrap(), 0x0201);
+}
+
+#[test]
+fn test_get_at_out_of_bounds() {
+    let buffer = ByteBuffer::from_bytes(&[0x1, 0x2, 0x3]);
+    assert_eq!(
+        buffer.get_u8_at(3),
+        Err(Error::UnexpectedEof {
+            needed: 1,
+            available: 0,
+            offset: 3,
+        })
+    );
+    assert_eq!(
+        buffer.get_u32_at(1),
+        Err(Error::UnexpectedEof {
+            needed: 4,
+            available: 2,
+            offset: 1,
+        })
+    );
+    assert!(buffer.get_u16_at(usize::MAX).is_err());
+}
+
+#[test]
+fn test_put_at() {
+    let mut buffer = ByteBuffer::from_bytes(&[0; 46]);
+    buffer.set_wpos(5);
+    buffer.write_bit(true);
+    buffer.put_u8_at(0, 0xFF).unwrap();
+    buffer.put_i8_at(1, -2).unwrap();
+    buffer.put_u16_at(2, 0x0102).unwrap();
+    buffer.put_i16_at(4, -1).unwrap();
+    buffer.put_u32_at(6, 0x03040506).unwrap();
+    buffer.put_i32_at(10, -3).unwrap();
+    buffer.put_u64_at(14, 7).unwrap();
+    buffer.put_i64_at(22, -4).unwrap();
+    buffer.put_u128_at(30, u128::MAX).unwrap();
+    buffer.put_i128_at(30, 0).unwrap();
+    assert!(buffer.put_i128_at(31, 0).is_err());
+    buffer.put_f32_at(30, 1.5).unwrap();
+    buffer.put_f64_at(34, 2.5).unwrap();
+    assert_eq!(buffer.len(), 46);
+    assert_eq!(buffer.get_bit_wpos(), 5 * 8 + 1);
+
+    assert_eq!(buffer.read_u8().unwrap(), 0xFF);
+    assert_eq!(buffer.read_i8().unwrap(), -2);
+    assert_eq!(buffer.read_u16().unwrap(), 0x0102);
+    assert_eq!(buffer.read_i16().unwrap(), -1);
+    assert_eq!(buffer.read_u32().unwrap(), 0x03040506);
+    assert_eq!(buffer.read_i32().unwrap(), -3);
+    assert_eq!(buffer.read_u64().unwrap(), 7);
+    assert_eq!(buffer.read_i64().unwrap(), -4);
+    assert_eq!(buffer.read_f32().unwrap(), 1.5);
+    assert_eq!(buffer.read_f64().unwrap(), 2.5);
+    assert_eq!(buffer.read_u32().unwrap(), 0);
+}
+
+#[test]
+fn test_put_at_little_endian() {
+    let mut buffer = ByteBuffer::from_bytes(&[0; 4]);
+    buffer.set_endian(Endian::LittleEndian);
+    buffer.put_u32_at(0, 0x01020304).unwrap();
+    assert_eq!(buffer.as_bytes(), [0x4, 0x3, 0x2, 0x1]);
+    assert_eq!(
+        buffer.put_u16_at(3, 1),
+        Err(Error::BufferFull {
+            needed: 2,
+            available: 1,
+            offset: 3,
+        })
+    );
+    assert_eq!(buffer.as_bytes(), [0x4, 0x3, 0x2, 0x1]);
+}
+
+#[test]
+fn test_compact() {
+    let mut buffer = ByteBuffer::from_bytes(&[0x1, 0x2, 0x3, 0x4]);
+    assert_eq!(buffer.consumed(), 0);
+    assert_eq!(buffer.remaining(), 4);
+    buffer.read_u8().unwrap();
+    buffer.read_bits(4).unwrap();
+    assert_eq!(buffer.consumed(), 1);
+    assert_eq!(buffer.remaining(), 2);
+
+    buffer.compact();
+    assert_eq!(buffer.as_bytes(), [0x2, 0x3, 0x4]);
+    assert_eq!(buffer.consumed(), 0);
+    assert_eq!(buffer.remaining(), 2);
+    assert_eq!(buffer.get_wpos(), 3);
+    // The bits already read stay read
+    assert_eq!(buffer.read_bits(4).unwrap(), 0x2);
+    assert_eq!(buffer.read_u16().unwrap(), 0x0304);
+}
+
+#[test]
+fn test_compact_wpos_before_rpos() {
+    let mut buffer = ByteBuffer::from_bytes(&[0x1, 0x2, 0x3, 0x4, 0x5, 0x6]);
+    buffer.set_rpos(4);
+    buffer.set_wpos(2);
+    buffer.compact();
+    assert_eq!(buffer.as_bytes(), [0x3, 0x4, 0x5, 0x6]);
+    assert_eq!(buffer.get_rpos(), 2);
+    assert_eq!(buffer.get_wpos(), 0);
+
+    // The write lands on a consumed byte, as it would have before compacting
+    buffer.write_u8(0x9);
+    assert_eq!(buffer.as_bytes(), [0x9, 0x4, 0x5, 0x6]);
+    assert_eq!(buffer.read_u16().unwrap(), 0x0506);
+}
+
+#[test]
+fn test_auto_compact_bounded() {
+    let mut buffer = ByteBuffer::new();
+    buffer.set_auto_compact(Some(1024));
+    assert_eq!(buffer.auto_compact(), Some(1024));
+
+    let mut next = 0;
+    let mut max_len = 0;
+    for i in 0..10_000u32 {
+        buffer.write_u32(i);
+        buffer.write_string("message");
+        max_len = max_len.max(buffer.len());
+        if i % 3 == 2 {
+            // Read the messages in batches, as they would arrive from a stream
+            while buffer.remaining() > 0 {
+                assert_eq!(buffer.read_u32().unwrap(), next);
+                assert_eq!(buffer.read_string().unwrap(), "message");
+                next += 1;
+            }
+        }
+    }
+    assert_eq!(next, 9_999);
+    // Each message takes 15 bytes, and at most 3 are buffered on top of the consumed bytes
+    assert!(max_len <= 1024 + 3 * 15);
+}
+
+#[test]
+fn test_auto_compact_disabled() {
+    let mut buffer = ByteBuffer::new();
+    for i in 0..1000u32 {
+        buffer.write_u32(i);
+        assert_eq!(buffer.read_u32().unwrap(), i);
+    }
+    assert_eq!(buffer.len(), 4000);
+    assert_eq!(buffer.consumed(), 4000);
+    assert_eq!(buffer.auto_compact(), None);
+}
+
+#[test]
+fn test_auto_compact_keeps_fields() {
+    let mut buffer = ByteBuffer::new();
+    buffer.set_auto_compact(Some(1));
+    buffer.write_u8(0xFF);
+    buffer.read_u8().unwrap();
+    buffer
+        .write_length_prefixed(LengthPrefix::U8, |buffer| {
+            buffer.write_u16(0x0102);
+            Ok(())
+        })
+        .unwrap();
+    assert_eq!(buffer.as_bytes(), [0xFF, 0x2, 0x1, 0x2]);
+    let field = buffer.reserve_field::<u16>().unwrap();
+    buffer.patch_u16(field, 0x0304).unwrap();
+    assert_eq!(buffer.as_bytes(), [0xFF, 0x2, 0x1, 0x2, 0x3, 0x4]);
+
+    // The next growth compacts the buffer
+    buffer.write_u8(0x5);
+    assert_eq!(buffer.as_bytes(), [0x2, 0x1, 0x2, 0x3, 0x4, 0x5]);
+    assert_eq!(buffer.consumed(), 0);
+    assert_eq!(buffer.auto_compact(), Some(1));
+}
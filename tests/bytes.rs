@@ -0,0 +1,92 @@
+#![cfg(feature = "bytes")]
+
+use bytebuffer::{ByteBuffer, ByteReader};
+use bytes::{Buf, BufMut, Bytes};
+
+#[test]
+fn test_buf_buffer() {
+    let mut buffer = ByteBuffer::from_bytes(&[0xF0, 0x1, 0x2, 0x3, 0x4]);
+    buffer.read_bit().unwrap();
+    // The partially read byte is skipped
+    assert_eq!(buffer.remaining(), 4);
+    assert_eq!(buffer.chunk(), [0x1, 0x2, 0x3, 0x4]);
+    assert_eq!(buffer.get_u16(), 0x0102);
+    assert_eq!(buffer.get_rpos(), 3);
+    assert_eq!(buffer.read_u8().unwrap(), 0x3);
+    assert_eq!(buffer.copy_to_bytes(1), Bytes::from_static(&[0x4]));
+    assert!(!buffer.has_remaining());
+}
+
+#[test]
+fn test_buf_reader() {
+    let data = [0x1, 0x2, 0x3, 0x4, 0x5];
+    let mut reader = ByteReader::from_bytes(&data);
+    reader.read_bits(3).unwrap();
+    assert_eq!(reader.remaining(), 4);
+    assert_eq!(reader.get_u32_le(), 0x05040302);
+    assert_eq!(reader.remaining(), 0);
+
+    let mut reader = ByteReader::from_bytes(&data);
+    let mut out = [0; 3];
+    reader.copy_to_slice(&mut out);
+    assert_eq!(out, [0x1, 0x2, 0x3]);
+    assert_eq!(reader.read_u16().unwrap(), 0x0405);
+}
+
+#[test]
+#[should_panic]
+fn test_buf_advance_past_end() {
+    ByteBuffer::from_bytes(&[0x1]).advance(2);
+}
+
+#[test]
+fn test_buf_mut() {
+    let mut buffer = ByteBuffer::new();
+    buffer.write_bit(true);
+    buffer.put_u16(0x0102);
+    buffer.put_slice(&[0x3, 0x4]);
+    buffer.put_bytes(0x5, 100);
+    buffer.write_u8(0x6);
+    assert_eq!(buffer.len(), 106);
+    assert_eq!(buffer.as_bytes()[..5], [0x80, 0x1, 0x2, 0x3, 0x4]);
+    assert_eq!(buffer.as_bytes()[105], 0x6);
+
+    // Overwrite existing data in place
+    buffer.set_wpos(1);
+    buffer.put_u32(0xAABBCCDD);
+    assert_eq!(buffer.get_wpos(), 5);
+    assert_eq!(buffer.len(), 106);
+    assert_eq!(buffer.as_bytes()[..6], [0x80, 0xAA, 0xBB, 0xCC, 0xDD, 0x5]);
+
+    // Generic code writing through the chunks directly
+    let mut buffer = ByteBuffer::new();
+    let source: Vec<u8> = (0..=255).collect();
+    buffer.put(&source[..]);
+    buffer.put(Bytes::from_static(b"end"));
+    assert_eq!(buffer.len(), 259);
+    assert_eq!(buffer.as_bytes()[..256], source);
+    assert_eq!(buffer.get_wpos(), 259);
+    assert!(buffer.chunk_mut().len() > 0);
+    assert_eq!(buffer.len(), 259);
+}
+
+#[test]
+fn test_bytes_conversions() {
+    let bytes = Bytes::from(vec![0x1, 0x2, 0x3]);
+    let pointer = bytes.as_ptr();
+    let mut buffer = ByteBuffer::from(bytes);
+    assert_eq!(buffer.as_bytes().as_ptr(), pointer);
+    assert_eq!(buffer.get_wpos(), 3);
+    assert_eq!(buffer.read_u8().unwrap(), 0x1);
+
+    buffer.write_u8(0x4);
+    let pointer = buffer.as_bytes().as_ptr();
+    let bytes = Bytes::from(buffer);
+    assert_eq!(bytes.as_ptr(), pointer);
+    assert_eq!(bytes, Bytes::from_static(&[0x1, 0x2, 0x3, 0x4]));
+
+    // Shared data is copied
+    let shared = Bytes::from_static(&[0x1, 0x2]);
+    let buffer = ByteBuffer::from(shared.clone());
+    assert_eq!(buffer.as_bytes(), shared);
+}
@@ -0,0 +1,93 @@
+use bytebuffer::{ByteBuffer, ByteReader, Endian};
+
+#[test]
+fn test_buffer_restore() {
+    let mut buffer = ByteBuffer::new();
+    buffer.write_u8(0x1);
+    buffer.write_bit(true);
+    let checkpoint = buffer.checkpoint();
+    assert_eq!(checkpoint.rpos(), 0);
+    assert_eq!(checkpoint.wpos(), 1);
+
+    buffer.read_bit().unwrap();
+    buffer.write_bits(0x7F, 7);
+    buffer.write_u16(0x0203);
+    buffer.set_endian(Endian::LittleEndian);
+    buffer.restore(checkpoint);
+
+    assert_eq!(buffer.get_bit_rpos(), 0);
+    assert_eq!(buffer.get_bit_wpos(), 9);
+    assert_eq!(buffer.endian(), Endian::BigEndian);
+    // The data written after the checkpoint is overwritten
+    buffer.write_bits(0, 7);
+    assert_eq!(buffer.read_u16().unwrap(), 0x0180);
+}
+
+#[test]
+fn test_buffer_restore_clamped() {
+    let mut buffer = ByteBuffer::from_bytes(&[0x1, 0x2, 0x3]);
+    buffer.set_rpos(2);
+    buffer.read_bit().unwrap();
+    let checkpoint = buffer.checkpoint();
+
+    buffer.clear();
+    buffer.write_u8(0x4);
+    buffer.restore(checkpoint);
+    assert_eq!(buffer.get_bit_rpos(), 8);
+    assert_eq!(buffer.get_bit_wpos(), 8);
+}
+
+#[test]
+fn test_buffer_guard() {
+    let mut buffer = ByteBuffer::from_bytes(&[0x1, 0x2, 0x3]);
+    buffer.set_wpos(0);
+    {
+        let mut guard = buffer.checkpoint_guard();
+        assert_eq!(guard.read_u16().unwrap(), 0x0102);
+        guard.write_u8(0x4);
+        assert_eq!(guard.checkpoint().rpos(), 0);
+    }
+    assert_eq!(buffer.get_rpos(), 0);
+    assert_eq!(buffer.get_wpos(), 0);
+
+    let mut guard = buffer.checkpoint_guard();
+    assert_eq!(guard.read_u16().unwrap(), 0x0402);
+    guard.commit();
+    assert_eq!(buffer.get_rpos(), 2);
+    assert_eq!(buffer.get_wpos(), 0);
+    assert_eq!(buffer.read_u8().unwrap(), 0x3);
+}
+
+#[test]
+fn test_reader_restore() {
+    let data = [0x1, 0x2, 0x3];
+    let mut reader = ByteReader::from_bytes(&data);
+    reader.read_bits(4).unwrap();
+    let checkpoint = reader.checkpoint();
+    assert_eq!(checkpoint.rpos(), 0);
+    assert_eq!(checkpoint.wpos(), 0);
+
+    reader.read_u16().unwrap();
+    reader.set_endian(Endian::LittleEndian);
+    reader.restore(checkpoint);
+    assert_eq!(reader.get_bit_rpos(), 4);
+    assert_eq!(reader.endian(), Endian::BigEndian);
+    assert_eq!(reader.read_bits(12).unwrap(), 0x102);
+}
+
+#[test]
+fn test_reader_guard() {
+    let data = [0x1, 0x2, 0x3];
+    let mut reader = ByteReader::from_bytes(&data);
+    {
+        let mut guard = reader.checkpoint_guard();
+        assert_eq!(guard.read_u8().unwrap(), 0x1);
+        assert!(guard.read_u32().is_err());
+    }
+    assert_eq!(reader.get_rpos(), 0);
+
+    let mut guard = reader.checkpoint_guard();
+    guard.read_u8().unwrap();
+    guard.commit();
+    assert_eq!(reader.read_u16().unwrap(), 0x0203);
+}
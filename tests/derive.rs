@@ -0,0 +1,235 @@
+#![cfg(feature = "derive")]
+
+use bytebuffer::{ByteBuffer, ByteDecode, ByteEncode, ByteReader, ByteWriter, Endian, Error};
+
+#[derive(ByteEncode, ByteDecode, Debug, PartialEq)]
+struct Header {
+    version: u8,
+    #[bytebuffer(bits = 3)]
+    kind: u8,
+    #[bytebuffer(bits = 1)]
+    urgent: bool,
+    #[bytebuffer(bits = 4)]
+    flags: u8,
+    #[bytebuffer(endian = "little")]
+    length: u16,
+    #[bytebuffer(prefix = VarInt)]
+    name: String,
+    #[bytebuffer(prefix = Fixed(4))]
+    tag: Vec<u8>,
+    #[bytebuffer(skip)]
+    cache: Option<u32>,
+    #[bytebuffer(skip, default = 7)]
+    retries: u8,
+}
+
+#[derive(ByteEncode, ByteDecode, Debug, PartialEq)]
+#[bytebuffer(tag = u8)]
+#[repr(u8)]
+enum Command {
+    Ping,
+    Move { x: i16, y: i16 },
+    Say(String),
+    Quit = 0x10,
+    Reset,
+}
+
+#[derive(ByteEncode, ByteDecode, Debug, PartialEq)]
+#[bytebuffer(endian = "little")]
+struct Packet<T> {
+    id: u32,
+    payload: T,
+    commands: Vec<Command>,
+}
+
+#[derive(ByteEncode, ByteDecode, Debug, PartialEq)]
+struct Unit;
+
+#[derive(ByteEncode, ByteDecode, Debug, PartialEq)]
+struct Pair(u8, #[bytebuffer(endian = "little")] u16);
+
+fn header() -> Header {
+    Header {
+        version: 1,
+        kind: 5,
+        urgent: true,
+        flags: 0xA,
+        length: 0x0102,
+        name: "ab".to_string(),
+        tag: vec![0xA, 0xB, 0xC, 0xD],
+        cache: None,
+        retries: 7,
+    }
+}
+
+#[test]
+fn test_struct_layout() {
+    let mut buffer = ByteBuffer::new();
+    header().encode(&mut buffer).unwrap();
+    assert_eq!(
+        buffer.as_bytes(),
+        [
+            0x1,
+            0b1011_1010,
+            0x2,
+            0x1,
+            0x2,
+            b'a',
+            b'b',
+            0xA,
+            0xB,
+            0xC,
+            0xD
+        ]
+    );
+    assert_eq!(Header::decode(&mut buffer).unwrap(), header());
+
+    // The byte order of the buffer is restored after the field
+    assert_eq!(buffer.endian(), Endian::BigEndian);
+}
+
+#[test]
+fn test_skip_default() {
+    let mut value = header();
+    value.cache = Some(3);
+    value.retries = 0;
+    let mut buffer = ByteBuffer::new();
+    value.encode(&mut buffer).unwrap();
+    let decoded = Header::decode(&mut ByteReader::from_bytes(buffer.as_bytes())).unwrap();
+    assert_eq!(decoded.cache, None);
+    assert_eq!(decoded.retries, 7);
+}
+
+#[test]
+fn test_enum() {
+    let cases: [(Command, &[u8]); 5] = [
+        (Command::Ping, &[0x0]),
+        (Command::Move { x: -1, y: 2 }, &[0x1, 0xFF, 0xFF, 0x0, 0x2]),
+        (
+            Command::Say("hi".to_string()),
+            &[0x2, 0x0, 0x0, 0x0, 0x2, b'h', b'i'],
+        ),
+        (Command::Quit, &[0x10]),
+        (Command::Reset, &[0x11]),
+    ];
+    for (command, bytes) in cases {
+        let mut buffer = ByteBuffer::new();
+        command.encode(&mut buffer).unwrap();
+        assert_eq!(buffer.as_bytes(), bytes);
+        assert_eq!(
+            Command::decode(&mut ByteReader::from_bytes(bytes)).unwrap(),
+            command
+        );
+    }
+}
+
+#[test]
+fn test_unknown_discriminant() {
+    let data = [0x0, 0x3];
+    let mut reader = ByteReader::from_bytes(&data);
+    reader.read_u8().unwrap();
+    assert_eq!(
+        Command::decode(&mut reader).unwrap_err(),
+        Error::InvalidValue { offset: 1 }
+    );
+}
+
+#[test]
+fn test_generic_container_endian() {
+    let packet = Packet {
+        id: 1,
+        payload: Pair(2, 3),
+        commands: vec![Command::Ping, Command::Move { x: 1, y: 0 }],
+    };
+    let mut data = [0; 21];
+    let mut writer = ByteWriter::from_bytes(&mut data);
+    packet.encode(&mut writer).unwrap();
+    assert_eq!(writer.endian(), Endian::BigEndian);
+    assert_eq!(writer.get_wpos(), 17);
+    assert_eq!(
+        data[..17],
+        [0x1, 0x0, 0x0, 0x0, 0x2, 0x3, 0x0, 0x2, 0x0, 0x0, 0x0, 0x0, 0x1, 0x1, 0x0, 0x0, 0x0]
+    );
+    let decoded = Packet::<Pair>::decode(&mut ByteReader::from_bytes(&data)).unwrap();
+    assert_eq!(decoded, packet);
+
+    let mut buffer = ByteBuffer::new();
+    Unit.encode(&mut buffer).unwrap();
+    assert!(buffer.is_empty());
+    assert_eq!(Unit::decode(&mut buffer).unwrap(), Unit);
+}
+
+#[test]
+fn test_errors() {
+    let mut value = header();
+    value.kind = 8;
+    let mut buffer = ByteBuffer::new();
+    assert_eq!(
+        value.encode(&mut buffer).unwrap_err(),
+        Error::LimitExceeded {
+            value: 8,
+            limit: 7,
+            offset: 1
+        }
+    );
+
+    let mut value = header();
+    value.tag = vec![0; 5];
+    assert!(matches!(
+        value.encode(&mut ByteBuffer::new()).unwrap_err(),
+        Error::LimitExceeded { limit: 4, .. }
+    ));
+
+    let data = [0x1, 0x0];
+    assert!(matches!(
+        Header::decode(&mut ByteReader::from_bytes(&data)).unwrap_err(),
+        Error::UnexpectedEof { offset: 2, .. }
+    ));
+
+    let mut data = [0; 3];
+    let mut writer = ByteWriter::from_bytes(&mut data);
+    assert!(matches!(
+        header().encode(&mut writer).unwrap_err(),
+        Error::BufferFull { .. }
+    ));
+}
+
+#[test]
+fn test_primitive_impls() {
+    let mut buffer = ByteBuffer::new();
+    true.encode(&mut buffer).unwrap();
+    'é'.encode(&mut buffer).unwrap();
+    Some(1u16).encode(&mut buffer).unwrap();
+    None::<u8>.encode(&mut buffer).unwrap();
+    [1i8, -1].encode(&mut buffer).unwrap();
+    vec![2u32].encode(&mut buffer).unwrap();
+    assert_eq!(
+        buffer.as_bytes(),
+        [
+            0x1, 0x0, 0x0, 0x0, 0xE9, 0x1, 0x0, 0x1, 0x0, 0x1, 0xFF, 0x0, 0x0, 0x0, 0x1, 0x0, 0x0,
+            0x0, 0x2
+        ]
+    );
+    assert!(bool::decode(&mut buffer).unwrap());
+    assert_eq!(char::decode(&mut buffer).unwrap(), 'é');
+    assert_eq!(Option::<u16>::decode(&mut buffer).unwrap(), Some(1));
+    assert_eq!(Option::<u8>::decode(&mut buffer).unwrap(), None);
+    assert_eq!(<[i8; 2]>::decode(&mut buffer).unwrap(), [1, -1]);
+    assert_eq!(Vec::<u32>::decode(&mut buffer).unwrap(), [2]);
+
+    let data = [0x2];
+    assert_eq!(
+        bool::decode(&mut ByteReader::from_bytes(&data)).unwrap_err(),
+        Error::InvalidValue { offset: 0 }
+    );
+    let data = [0x0, 0x0, 0xD8, 0x0];
+    assert_eq!(
+        char::decode(&mut ByteReader::from_bytes(&data)).unwrap_err(),
+        Error::InvalidValue { offset: 0 }
+    );
+    let data = [0x1];
+    assert!(matches!(
+        <[u8; 2]>::decode(&mut ByteReader::from_bytes(&data)).unwrap_err(),
+        Error::UnexpectedEof { offset: 1, .. }
+    ));
+}
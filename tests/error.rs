@@ -0,0 +1,222 @@
+use bytebuffer::{ByteBuffer, ByteReader, ByteWriter, Error};
+use std::io::ErrorKind;
+
+#[test]
+fn test_eof_context() {
+    let mut buffer = ByteBuffer::from_bytes(&[0x1, 0x2, 0x3]);
+    buffer.read_u8().unwrap();
+    assert_eq!(
+        buffer.read_u32().unwrap_err(),
+        Error::UnexpectedEof {
+            needed: 4,
+            available: 2,
+            offset: 1
+        }
+    );
+    // A failed read does not move the cursor
+    assert_eq!(buffer.get_rpos(), 1);
+}
+
+#[test]
+fn test_eof_context_bytes() {
+    let data = [0x1, 0x2];
+    let mut reader = ByteReader::from_bytes(&data);
+    assert_eq!(
+        reader.read_bytes(3).unwrap_err(),
+        Error::UnexpectedEof {
+            needed: 3,
+            available: 2,
+            offset: 0
+        }
+    );
+}
+
+#[test]
+fn test_eof_context_bit() {
+    let data = [0xFF];
+    let mut reader = ByteReader::from_bytes(&data);
+    reader.read_bits(8).unwrap();
+    assert_eq!(
+        reader.read_bit().unwrap_err(),
+        Error::UnexpectedEof {
+            needed: 1,
+            available: 0,
+            offset: 1
+        }
+    );
+}
+
+#[test]
+fn test_eof_context_string() {
+    let mut buffer = ByteBuffer::new();
+    buffer.write_u32(10);
+    buffer.write_bytes(b"abc");
+    assert_eq!(
+        buffer.read_string().unwrap_err(),
+        Error::UnexpectedEof {
+            needed: 10,
+            available: 3,
+            offset: 4
+        }
+    );
+}
+
+#[test]
+fn test_invalid_utf8_offset() {
+    let mut buffer = ByteBuffer::new();
+    buffer.write_u8(0);
+    buffer.write_u32(3);
+    buffer.write_bytes(&[b'a', 0xFF, b'b']);
+    buffer.read_u8().unwrap();
+    assert_eq!(
+        buffer.read_string().unwrap_err(),
+        Error::InvalidUtf8 { offset: 6 }
+    );
+
+    let mut reader = ByteReader::from_bytes(buffer.as_bytes());
+    reader.read_u8().unwrap();
+    assert_eq!(
+        reader.read_string().unwrap_err(),
+        Error::InvalidUtf8 { offset: 6 }
+    );
+}
+
+#[test]
+fn test_too_many_bits() {
+    let mut buffer = ByteBuffer::from_bytes(&[0; 16]);
+    assert_eq!(
+        buffer.read_bits(65).unwrap_err(),
+        Error::TooManyBits {
+            requested: 65,
+            max: 64
+        }
+    );
+}
+
+#[test]
+fn test_buffer_full_context() {
+    let mut data = [0; 5];
+    let mut writer = ByteWriter::from_bytes(&mut data);
+    writer.write_u16(1).unwrap();
+    assert_eq!(
+        writer.write_u64(1).unwrap_err(),
+        Error::BufferFull {
+            needed: 8,
+            available: 3,
+            offset: 2
+        }
+    );
+}
+
+#[test]
+fn test_display() {
+    let error = Error::UnexpectedEof {
+        needed: 4,
+        available: 2,
+        offset: 1,
+    };
+    assert_eq!(
+        error.to_string(),
+        "could not read 4 bytes at offset 1: only 2 available"
+    );
+    assert_eq!(
+        Error::InvalidUtf8 { offset: 6 }.to_string(),
+        "invalid UTF-8 sequence at offset 6"
+    );
+    assert_eq!(
+        Error::TrailingBytes {
+            remaining: 2,
+            offset: 6
+        }
+        .to_string(),
+        "2 unread bytes left at offset 6"
+    );
+    assert_eq!(
+        Error::SizeMismatch {
+            expected: 4,
+            found: 6,
+            offset: 1
+        }
+        .to_string(),
+        "value at offset 1 takes 6 bytes instead of 4"
+    );
+}
+
+#[test]
+fn test_into_io_error() {
+    let cases = [
+        (
+            Error::UnexpectedEof {
+                needed: 1,
+                available: 0,
+                offset: 0,
+            },
+            ErrorKind::UnexpectedEof,
+        ),
+        (
+            Error::BufferFull {
+                needed: 1,
+                available: 0,
+                offset: 0,
+            },
+            ErrorKind::WriteZero,
+        ),
+        (Error::Incomplete { needed: 2 }, ErrorKind::UnexpectedEof),
+        (Error::InvalidUtf8 { offset: 0 }, ErrorKind::InvalidData),
+        (Error::InvalidValue { offset: 0 }, ErrorKind::InvalidData),
+        (
+            Error::TooManyBits {
+                requested: 65,
+                max: 64,
+            },
+            ErrorKind::InvalidInput,
+        ),
+        (
+            Error::LimitExceeded {
+                value: 2,
+                limit: 1,
+                offset: 0,
+            },
+            ErrorKind::InvalidInput,
+        ),
+        (
+            Error::OutOfRange {
+                value: 4,
+                bits: 3,
+                offset: 0,
+            },
+            ErrorKind::InvalidInput,
+        ),
+        (
+            Error::TrailingBytes {
+                remaining: 2,
+                offset: 6,
+            },
+            ErrorKind::InvalidData,
+        ),
+        (
+            Error::SizeMismatch {
+                expected: 4,
+                found: 6,
+                offset: 1,
+            },
+            ErrorKind::InvalidInput,
+        ),
+    ];
+    for (error, kind) in cases {
+        let io_error: std::io::Error = error.clone().into();
+        assert_eq!(io_error.kind(), kind);
+        assert_eq!(io_error.to_string(), error.to_string());
+    }
+}
+
+#[test]
+fn test_question_mark_into_io_error() {
+    fn read(buffer: &mut ByteBuffer) -> std::io::Result<u32> {
+        Ok(buffer.read_u32()?)
+    }
+
+    let error = read(&mut ByteBuffer::new()).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+    assert!(error.into_inner().unwrap().downcast::<Error>().is_ok());
+}
@@ -0,0 +1,45 @@
+#![cfg(feature = "futures-io")]
+
+use bytebuffer::{
+    futures_io::{AsyncByteReadExt, AsyncByteWriteExt},
+    Endian,
+};
+use std::io::ErrorKind;
+use tokio_util::compat::TokioAsyncReadCompatExt;
+
+#[tokio::test]
+async fn test_values_over_pipe() {
+    let (client, server) = tokio::io::duplex(3);
+    let (mut client, mut server) = (client.compat(), server.compat());
+
+    let writer = tokio::spawn(async move {
+        client
+            .write_u16_with(0x0102, Endian::LittleEndian)
+            .await
+            .unwrap();
+        client.write_f32_with(1.5, Endian::BigEndian).await.unwrap();
+        client
+            .write_string("hello", Endian::BigEndian)
+            .await
+            .unwrap();
+        client.write_var_u64(1 << 40).await.unwrap();
+        client.write_bytes(&[0x1, 0x2, 0x3, 0x4]).await.unwrap();
+    });
+
+    assert_eq!(
+        server.read_u16_with(Endian::LittleEndian).await.unwrap(),
+        0x0102
+    );
+    assert_eq!(server.read_f32_with(Endian::BigEndian).await.unwrap(), 1.5);
+    assert_eq!(
+        server.read_string(Endian::BigEndian).await.unwrap(),
+        "hello"
+    );
+    assert_eq!(server.read_var_u64().await.unwrap(), 1 << 40);
+    let mut frame = server.read_frame(4).await.unwrap();
+    assert_eq!(frame.read_u32().unwrap(), 0x01020304);
+
+    writer.await.unwrap();
+    let error = server.read_frame(1).await.unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+}
@@ -0,0 +1,195 @@
+use bytebuffer::{BitOrder, ByteBuffer, ByteRead, ByteReader, ByteWrite, ByteWriter, Error};
+
+#[test]
+fn test_exp_golomb_layout() {
+    // ue(v) codes for 0..=8 from the H.264 specification, 9.1
+    let codes = [
+        "1", "010", "011", "00100", "00101", "00110", "00111", "0001000", "0001001",
+    ];
+    for (value, code) in codes.iter().enumerate() {
+        let mut buffer = ByteBuffer::new();
+        buffer.write_exp_golomb(value as u64);
+        let bits = u64::from_str_radix(code, 2).unwrap();
+        let n = code.len() as u8;
+        assert_eq!(buffer.read_bits(n).unwrap(), bits, "ue({})", value);
+        let mut reader = ByteReader::from_bytes(buffer.as_bytes());
+        assert_eq!(reader.read_exp_golomb().unwrap(), value as u64);
+    }
+
+    // se(v): 0, 1, -1, 2, -2 are the code numbers 0, 1, 2, 3, 4
+    let mut buffer = ByteBuffer::new();
+    for value in [0, 1, -1, 2, -2] {
+        buffer.write_signed_exp_golomb(value);
+    }
+    buffer.flush_bits();
+    assert_eq!(buffer.as_bytes(), [0b1010_0110, 0b0100_0010, 0b1000_0000]);
+    for value in [0, 1, -1, 2, -2] {
+        assert_eq!(buffer.read_signed_exp_golomb().unwrap(), value);
+    }
+}
+
+#[test]
+fn test_exp_golomb_extremes() {
+    let mut buffer = ByteBuffer::new();
+    let unsigned = [u64::MAX, u64::MAX - 1, 1 << 63, 0];
+    let signed = [i64::MIN, i64::MAX, i64::MIN + 1, -1];
+    for value in unsigned {
+        buffer.write_exp_golomb(value);
+    }
+    for value in signed {
+        buffer.write_signed_exp_golomb(value);
+    }
+
+    let mut reader = ByteReader::from_bytes(buffer.as_bytes());
+    for value in unsigned {
+        assert_eq!(reader.read_exp_golomb().unwrap(), value);
+    }
+    for value in signed {
+        assert_eq!(reader.read_signed_exp_golomb().unwrap(), value);
+    }
+}
+
+#[test]
+fn test_exp_golomb_errors() {
+    // 65 leading zeros are rejected without reading the rest of the run
+    let data = [0; 64];
+    let mut reader = ByteReader::from_bytes(&data);
+    assert_eq!(
+        reader.read_exp_golomb().unwrap_err(),
+        Error::LimitExceeded {
+            value: 65,
+            limit: 64,
+            offset: 0
+        }
+    );
+    assert_eq!(reader.get_rpos(), 8);
+
+    // 64 leading zeros, then a value larger than u64::MAX
+    let mut buffer = ByteBuffer::new();
+    buffer.write_bits(0, 64);
+    buffer.write_bit(true);
+    buffer.write_bits(1, 64);
+    assert_eq!(
+        buffer.read_exp_golomb().unwrap_err(),
+        Error::InvalidValue { offset: 0 }
+    );
+    let mut reader = ByteReader::from_bytes(buffer.as_bytes());
+    assert_eq!(reader.read_signed_exp_golomb().unwrap(), i64::MIN);
+
+    let data = [0b0000_0001];
+    assert!(matches!(
+        ByteReader::from_bytes(&data).read_exp_golomb().unwrap_err(),
+        Error::UnexpectedEof { .. }
+    ));
+}
+
+#[test]
+fn test_unary() {
+    let mut buffer = ByteBuffer::new();
+    for n in [0, 1, 7, 64, 130] {
+        buffer.write_unary(n);
+    }
+    assert_eq!(buffer.read_bits(3).unwrap(), 0b101);
+    let mut reader = ByteReader::from_bytes(buffer.as_bytes());
+    for n in [0, 1, 7, 64, 130] {
+        assert_eq!(reader.read_unary().unwrap(), n);
+    }
+
+    let data = [0; 4];
+    let mut reader = ByteReader::from_bytes(&data);
+    assert!(matches!(
+        reader.read_unary().unwrap_err(),
+        Error::UnexpectedEof { .. }
+    ));
+}
+
+#[test]
+fn test_rice() {
+    let mut buffer = ByteBuffer::new();
+    buffer.write_rice(9, 2).unwrap();
+    buffer.write_rice(5, 0).unwrap();
+    buffer.write_rice(u64::MAX, 64).unwrap();
+    buffer.write_rice(u64::MAX, 54).unwrap();
+    buffer.flush_bits();
+    assert_eq!(buffer.as_bytes()[..2], [0b0010_1000, 0b0011_1111]);
+
+    let mut reader = ByteReader::from_bytes(buffer.as_bytes());
+    assert_eq!(reader.read_rice(2).unwrap(), 9);
+    assert_eq!(reader.read_rice(0).unwrap(), 5);
+    assert_eq!(reader.read_rice(64).unwrap(), u64::MAX);
+    assert_eq!(reader.read_rice(54).unwrap(), u64::MAX);
+}
+
+#[test]
+fn test_rice_errors() {
+    let mut buffer = ByteBuffer::new();
+    assert_eq!(
+        buffer.write_rice(1 << 40, 8).unwrap_err(),
+        Error::LimitExceeded {
+            value: 1 << 32,
+            limit: u32::MAX.into(),
+            offset: 0
+        }
+    );
+    assert!(matches!(
+        buffer.write_rice(0, 65).unwrap_err(),
+        Error::TooManyBits { .. }
+    ));
+    assert!(buffer.is_empty());
+    assert!(matches!(
+        buffer.read_rice(65).unwrap_err(),
+        Error::TooManyBits { .. }
+    ));
+
+    // With k = 62, only the quotients 0 to 3 fit in 64 bits
+    let data = [0b0000_1000];
+    assert_eq!(
+        ByteReader::from_bytes(&data).read_rice(62).unwrap_err(),
+        Error::LimitExceeded {
+            value: 4,
+            limit: 3,
+            offset: 0
+        }
+    );
+}
+
+#[test]
+fn test_generic_codes() {
+    fn encode<W: ByteWrite>(w: &mut W) -> bytebuffer::Result<()> {
+        w.write_exp_golomb(7)?;
+        w.write_signed_exp_golomb(-3)?;
+        w.write_rice(100, 4)?;
+        w.write_unary(2)
+    }
+
+    fn decode<R: ByteRead>(r: &mut R) -> bytebuffer::Result<(u64, i64, u64, u32)> {
+        Ok((
+            r.read_exp_golomb()?,
+            r.read_signed_exp_golomb()?,
+            r.read_rice(4)?,
+            r.read_unary()?,
+        ))
+    }
+
+    for order in [BitOrder::MsbFirst, BitOrder::LsbFirst] {
+        let mut data = [0; 4];
+        let mut writer = ByteWriter::from_bytes(&mut data);
+        writer.set_bit_order(order);
+        encode(&mut writer).unwrap();
+        let mut reader = ByteReader::from_bytes(&data);
+        reader.set_bit_order(order);
+        assert_eq!(decode(&mut reader).unwrap(), (7, -3, 100, 2));
+
+        let mut buffer = ByteBuffer::new();
+        buffer.set_bit_order(order);
+        encode(&mut buffer).unwrap();
+        assert_eq!(buffer.as_bytes(), data);
+    }
+
+    let mut data = [0; 1];
+    let mut writer = ByteWriter::from_bytes(&mut data);
+    assert!(matches!(
+        writer.write_exp_golomb(1000).unwrap_err(),
+        Error::BufferFull { .. }
+    ));
+}
@@ -0,0 +1,348 @@
+use bytebuffer::{
+    ByteBuffer, ByteRead, ByteReader, ByteWrite, ByteWriter, Endian, Error, LengthPrefix,
+};
+
+#[test]
+fn test_prefix_encodings() {
+    let cases: [(LengthPrefix, &[u8]); 7] = [
+        (LengthPrefix::U8, &[0x2, b'h', b'i']),
+        (LengthPrefix::U16, &[0x0, 0x2, b'h', b'i']),
+        (LengthPrefix::U32, &[0x0, 0x0, 0x0, 0x2, b'h', b'i']),
+        (
+            LengthPrefix::U64,
+            &[0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x2, b'h', b'i'],
+        ),
+        (LengthPrefix::VarInt, &[0x2, b'h', b'i']),
+        (LengthPrefix::NulTerminated, &[b'h', b'i', 0x0]),
+        (LengthPrefix::Fixed(4), &[b'h', b'i', 0x0, 0x0]),
+    ];
+    for (prefix, bytes) in cases {
+        let mut buffer = ByteBuffer::new();
+        buffer.write_prefixed_string("hi", prefix).unwrap();
+        assert_eq!(buffer.as_bytes(), bytes, "{:?}", prefix);
+        assert_eq!(buffer.read_prefixed_string(prefix).unwrap(), "hi");
+
+        let mut reader = ByteReader::from(bytes);
+        assert_eq!(reader.read_prefixed_string(prefix).unwrap(), "hi");
+        assert_eq!(reader.get_rpos(), bytes.len());
+    }
+}
+
+#[test]
+fn test_prefix_little_endian() {
+    let mut buffer = ByteBuffer::new();
+    buffer.set_endian(Endian::LittleEndian);
+    buffer
+        .write_prefixed_bytes(&[0xFF], LengthPrefix::U16)
+        .unwrap();
+    assert_eq!(buffer.as_bytes(), [0x1, 0x0, 0xFF]);
+    assert_eq!(
+        buffer.read_prefixed_bytes(LengthPrefix::U16).unwrap(),
+        [0xFF]
+    );
+}
+
+#[test]
+fn test_string_uses_u32_prefix() {
+    let mut buffer = ByteBuffer::new();
+    buffer.write_string("hello");
+    let mut other = ByteBuffer::new();
+    other
+        .write_prefixed_string("hello", LengthPrefix::U32)
+        .unwrap();
+    assert_eq!(buffer.as_bytes(), other.as_bytes());
+    assert_eq!(
+        other.read_prefixed_string(LengthPrefix::U32).unwrap(),
+        buffer.read_string().unwrap()
+    );
+}
+
+#[test]
+fn test_prefixed_bytes() {
+    let mut buffer = ByteBuffer::new();
+    buffer
+        .write_prefixed_bytes(&[0x1, 0x0, 0x2], LengthPrefix::VarInt)
+        .unwrap();
+    buffer
+        .write_prefixed_bytes(&[0x3, 0x0], LengthPrefix::Fixed(4))
+        .unwrap();
+    buffer
+        .write_prefixed_bytes(&[0x4], LengthPrefix::NulTerminated)
+        .unwrap();
+
+    assert_eq!(
+        buffer.read_prefixed_bytes(LengthPrefix::VarInt).unwrap(),
+        [0x1, 0x0, 0x2]
+    );
+    // Fixed size byte arrays keep their padding
+    assert_eq!(
+        buffer.read_prefixed_bytes(LengthPrefix::Fixed(4)).unwrap(),
+        [0x3, 0x0, 0x0, 0x0]
+    );
+    assert_eq!(
+        buffer
+            .read_prefixed_bytes(LengthPrefix::NulTerminated)
+            .unwrap(),
+        [0x4]
+    );
+}
+
+#[test]
+fn test_varint_prefix_long_value() {
+    let value = "x".repeat(300);
+    let mut buffer = ByteBuffer::new();
+    buffer
+        .write_prefixed_string(&value, LengthPrefix::VarInt)
+        .unwrap();
+    assert_eq!(&buffer.as_bytes()[..2], [0xAC, 0x02]);
+    assert_eq!(buffer.len(), 302);
+    assert_eq!(
+        buffer.read_prefixed_string(LengthPrefix::VarInt).unwrap(),
+        value
+    );
+}
+
+#[test]
+fn test_value_does_not_fit_prefix() {
+    let mut buffer = ByteBuffer::new();
+    buffer.write_u8(0xAA);
+    assert_eq!(
+        buffer
+            .write_prefixed_bytes(&[0; 256], LengthPrefix::U8)
+            .unwrap_err(),
+        Error::LimitExceeded {
+            value: 256,
+            limit: 255,
+            offset: 1
+        }
+    );
+    assert_eq!(
+        buffer
+            .write_prefixed_bytes(&[0; 65536], LengthPrefix::U16)
+            .unwrap_err(),
+        Error::LimitExceeded {
+            value: 65536,
+            limit: 65535,
+            offset: 1
+        }
+    );
+    assert_eq!(
+        buffer
+            .write_prefixed_string("hello", LengthPrefix::Fixed(4))
+            .unwrap_err(),
+        Error::LimitExceeded {
+            value: 5,
+            limit: 4,
+            offset: 1
+        }
+    );
+    // Nothing was written
+    assert_eq!(buffer.as_bytes(), [0xAA]);
+
+    buffer
+        .write_prefixed_bytes(&[0; 255], LengthPrefix::U8)
+        .unwrap();
+    buffer
+        .write_prefixed_string("hell", LengthPrefix::Fixed(4))
+        .unwrap();
+}
+
+#[test]
+fn test_interior_nul() {
+    let mut buffer = ByteBuffer::new();
+    buffer.write_u16(0);
+    assert_eq!(
+        buffer
+            .write_prefixed_string("ab\0c", LengthPrefix::NulTerminated)
+            .unwrap_err(),
+        Error::InteriorNul { offset: 4 }
+    );
+    assert_eq!(buffer.len(), 2);
+}
+
+#[test]
+fn test_missing_nul_terminator() {
+    let data = *b"ab";
+    let mut reader = ByteReader::from(&data[..]);
+    assert!(matches!(
+        reader
+            .read_prefixed_string(LengthPrefix::NulTerminated)
+            .unwrap_err(),
+        Error::UnexpectedEof { offset: 2, .. }
+    ));
+}
+
+#[test]
+fn test_truncated_nul_terminated_untouched() {
+    let data = *b"ab";
+    let eof = Error::UnexpectedEof {
+        needed: 1,
+        available: 0,
+        offset: 2,
+    };
+
+    let mut reader = ByteReader::from(&data[..]);
+    assert_eq!(
+        reader
+            .read_prefixed_bytes(LengthPrefix::NulTerminated)
+            .unwrap_err(),
+        eof
+    );
+    assert_eq!(
+        ByteRead::read_prefixed_string(&mut reader, LengthPrefix::NulTerminated).unwrap_err(),
+        eof
+    );
+    assert_eq!(reader.get_rpos(), 0);
+    assert_eq!(reader.read_bytes(2).unwrap(), b"ab");
+
+    let mut buffer = ByteBuffer::from_bytes(&data);
+    assert_eq!(
+        buffer
+            .read_prefixed_string(LengthPrefix::NulTerminated)
+            .unwrap_err(),
+        eof
+    );
+    assert_eq!(
+        ByteRead::read_prefixed_bytes(&mut buffer, LengthPrefix::NulTerminated).unwrap_err(),
+        eof
+    );
+    assert_eq!(buffer.get_rpos(), 0);
+
+    // Once the terminator arrives, the same read succeeds
+    buffer.write_u8(0);
+    assert_eq!(
+        buffer
+            .read_prefixed_string(LengthPrefix::NulTerminated)
+            .unwrap(),
+        "ab"
+    );
+}
+
+#[test]
+fn test_prefix_overread() {
+    let data = [0x5, b'a', b'b'];
+    let mut reader = ByteReader::from(&data[..]);
+    assert_eq!(
+        reader.read_prefixed_bytes(LengthPrefix::U8).unwrap_err(),
+        Error::UnexpectedEof {
+            needed: 5,
+            available: 2,
+            offset: 1
+        }
+    );
+    let mut reader = ByteReader::from(&data[..]);
+    assert!(matches!(
+        reader.read_prefixed_bytes(LengthPrefix::U16).unwrap_err(),
+        Error::UnexpectedEof { .. }
+    ));
+    let mut reader = ByteReader::from(&data[..]);
+    assert!(matches!(
+        reader
+            .read_prefixed_bytes(LengthPrefix::Fixed(4))
+            .unwrap_err(),
+        Error::UnexpectedEof { .. }
+    ));
+}
+
+#[test]
+fn test_buffer_prefix_huge_length() {
+    let mut buffer = ByteBuffer::from_bytes(&[0xFF; 9]);
+    assert_eq!(
+        buffer.read_prefixed_string(LengthPrefix::U64).unwrap_err(),
+        Error::UnexpectedEof {
+            needed: usize::MAX,
+            available: 1,
+            offset: 8
+        }
+    );
+
+    let mut buffer =
+        ByteBuffer::from_bytes(&[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x1]);
+    assert!(matches!(
+        buffer
+            .read_prefixed_bytes(LengthPrefix::VarInt)
+            .unwrap_err(),
+        Error::UnexpectedEof { offset: 10, .. }
+    ));
+}
+
+#[test]
+fn test_reader_prefix_huge_length() {
+    let data = [0xFF; 9];
+    let mut reader = ByteReader::from(&data[..]);
+    assert_eq!(
+        reader.read_prefixed_bytes(LengthPrefix::U64).unwrap_err(),
+        Error::UnexpectedEof {
+            needed: usize::MAX,
+            available: 1,
+            offset: 8
+        }
+    );
+    let mut reader = ByteReader::from(&data[..]);
+    assert!(reader.read_slice(usize::MAX).is_err());
+
+    let data = [
+        0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x1, b'a',
+    ];
+    let mut reader = ByteReader::from(&data[..]);
+    assert!(matches!(
+        reader.read_str_prefixed(LengthPrefix::VarInt).unwrap_err(),
+        Error::UnexpectedEof {
+            needed: usize::MAX,
+            available: 1,
+            offset: 10
+        }
+    ));
+}
+
+#[test]
+fn test_prefix_invalid_utf8() {
+    let data = [0x0, 0x3, b'a', b'b', 0xFF];
+    let mut reader = ByteReader::from(&data[..]);
+    reader.read_u8().unwrap();
+    assert_eq!(
+        reader.read_prefixed_string(LengthPrefix::U8).unwrap_err(),
+        Error::InvalidUtf8 { offset: 4 }
+    );
+}
+
+#[test]
+fn test_prefix_flushes_bits() {
+    let mut buffer = ByteBuffer::new();
+    buffer.write_bit(true);
+    buffer.write_prefixed_string("a", LengthPrefix::U8).unwrap();
+    assert_eq!(buffer.as_bytes(), [0x80, 0x1, b'a']);
+    buffer.read_bit().unwrap();
+    assert_eq!(buffer.read_prefixed_string(LengthPrefix::U8).unwrap(), "a");
+}
+
+#[test]
+fn test_generic_traits() {
+    fn encode<W: ByteWrite>(w: &mut W) -> bytebuffer::Result<()> {
+        w.write_prefixed_string("hi", LengthPrefix::U8)?;
+        w.write_prefixed_bytes(&[0x1], LengthPrefix::Fixed(2))
+    }
+
+    fn decode<R: ByteRead>(r: &mut R) -> bytebuffer::Result<(String, Vec<u8>)> {
+        Ok((
+            r.read_prefixed_string(LengthPrefix::U8)?,
+            r.read_prefixed_bytes(LengthPrefix::Fixed(2))?,
+        ))
+    }
+
+    let mut data = [0; 5];
+    let mut writer = ByteWriter::from_bytes(&mut data);
+    encode(&mut writer).unwrap();
+    assert_eq!(data, [0x2, b'h', b'i', 0x1, 0x0]);
+    assert_eq!(
+        decode(&mut ByteReader::from(&data[..])).unwrap(),
+        ("hi".to_string(), vec![0x1, 0x0])
+    );
+
+    let mut data = [0; 4];
+    let mut writer = ByteWriter::from_bytes(&mut data);
+    assert!(matches!(
+        encode(&mut writer).unwrap_err(),
+        Error::BufferFull { .. }
+    ));
+}
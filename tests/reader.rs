@@ -1,5 +1,4 @@
-use bytebuffer::{ByteBuffer, ByteReader, Endian};
-use std::io::ErrorKind;
+use bytebuffer::{ByteBuffer, ByteReader, Endian, Error, LengthPrefix};
 
 #[test]
 fn test_api() {
@@ -15,7 +14,8 @@ fn test_api() {
     buffer.write_i64(1);
     buffer.write_u128(1);
     buffer.write_i128(1);
-    if cfg!(feature = "half") {
+    #[cfg(feature = "half")]
+    {
         buffer.write_bf16(half::bf16::from_f32(12.5));
         buffer.write_f16(half::f16::from_f32(12.5));
     }
@@ -38,7 +38,8 @@ fn test_api() {
     let _ = reader.read_i64();
     let _ = reader.read_u128();
     let _ = reader.read_i128();
-    if cfg!(feature = "half") {
+    #[cfg(feature = "half")]
+    {
         let _ = reader.read_bf16();
         let _ = reader.read_f16();
     }
@@ -245,7 +246,7 @@ fn test_string_overread_protection() {
     let result = reader.read_string();
     assert!(result.is_err());
     let error = result.err().unwrap();
-    assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+    assert!(matches!(error, Error::UnexpectedEof { .. }));
 }
 
 #[test]
@@ -300,7 +301,7 @@ fn test_cannot_read_bit_outside_data() {
     let result = reader.read_bit();
     assert!(result.is_err());
     let error = result.err().unwrap();
-    assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+    assert!(matches!(error, Error::UnexpectedEof { .. }));
 }
 
 #[test]
@@ -318,7 +319,7 @@ fn test_cannot_read_more_than_64_bits() {
     let result = reader.read_bits(73);
     assert!(result.is_err());
     let error = result.err().unwrap();
-    assert_eq!(error.kind(), ErrorKind::InvalidInput);
+    assert!(matches!(error, Error::TooManyBits { .. }));
 }
 
 #[test]
@@ -429,7 +430,7 @@ macro_rules! overread_tests {
                 let result = $value;
                 assert!(result.is_err());
                 let error = result.err().unwrap();
-                assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+                assert!(matches!(error, Error::UnexpectedEof { .. }));
             }
          )*
     }
@@ -450,3 +451,287 @@ overread_tests! {
     overread_bit: ByteReader::from(&[] as &[u8]).read_bit(),
     overread_bits: ByteReader::from(&[] as &[u8]).read_bits(1),
 }
+
+#[test]
+fn test_peek() {
+    let mut buffer = ByteBuffer::new();
+    buffer.write_u8(0xFF);
+    buffer.write_u16(0x0102);
+    buffer.write_u32(0x03040506);
+    buffer.write_u64(1);
+    buffer.write_u128(2);
+    buffer.write_f32(0.5);
+    buffer.write_f64(0.25);
+    buffer.write_string("hello");
+
+    let mut reader = ByteReader::from_bytes(buffer.as_bytes());
+    assert_eq!(reader.peek_u8().unwrap(), 0xFF);
+    assert_eq!(reader.peek_i8().unwrap(), -1);
+    assert_eq!(reader.peek_bytes(3).unwrap(), vec![0xFF, 0x1, 0x2]);
+    assert_eq!(reader.read_u8().unwrap(), 0xFF);
+    assert!(matches!(
+        reader.peek_bytes(usize::MAX),
+        Err(Error::UnexpectedEof { offset: 1, .. })
+    ));
+    assert_eq!(reader.peek_u16().unwrap(), 0x0102);
+    assert_eq!(reader.peek_i16().unwrap(), 0x0102);
+    assert_eq!(reader.read_u16().unwrap(), 0x0102);
+    assert_eq!(reader.peek_u32().unwrap(), 0x03040506);
+    assert_eq!(reader.peek_i32().unwrap(), 0x03040506);
+    assert_eq!(reader.read_u32().unwrap(), 0x03040506);
+    assert_eq!(reader.peek_u64().unwrap(), 1);
+    assert_eq!(reader.peek_i64().unwrap(), 1);
+    assert_eq!(reader.read_u64().unwrap(), 1);
+    assert_eq!(reader.peek_u128().unwrap(), 2);
+    assert_eq!(reader.peek_i128().unwrap(), 2);
+    assert_eq!(reader.read_u128().unwrap(), 2);
+    assert_eq!(reader.peek_f32().unwrap(), 0.5);
+    assert_eq!(reader.read_f32().unwrap(), 0.5);
+    assert_eq!(reader.peek_f64().unwrap(), 0.25);
+    assert_eq!(reader.read_f64().unwrap(), 0.25);
+    assert_eq!(reader.peek_string().unwrap(), "hello");
+    assert_eq!(reader.read_string().unwrap(), "hello");
+    assert!(reader.peek_u8().is_err());
+}
+
+#[test]
+fn test_peek_after_bits() {
+    let data = [0x80, 0x1, 0x2];
+    let mut reader = ByteReader::from_bytes(&data);
+    reader.set_endian(Endian::LittleEndian);
+    assert!(reader.read_bit().unwrap());
+    assert_eq!(reader.peek_u16().unwrap(), 0x0201);
+    assert_eq!(reader.get_bit_rpos(), 1);
+    assert!(matches!(
+        reader.peek_u32(),
+        Err(Error::UnexpectedEof {
+            needed: 4,
+            offset: 1,
+            available: 2,
+        })
+    ));
+    assert_eq!(reader.read_u16().unwrap(), 0x0201);
+}
+
+#[test]
+fn test_sub_reader() {
+    let data = [0x3, 0x1, 0x2, 0x3, 0x4];
+    let mut reader = ByteReader::from_bytes(&data);
+    reader.set_endian(Endian::LittleEndian);
+    let len = reader.read_u8().unwrap() as usize;
+    let mut section = reader.sub_reader(len).unwrap();
+    assert_eq!(reader.get_rpos(), 4);
+    assert_eq!(section.len(), 3);
+    assert_eq!(section.endian(), Endian::LittleEndian);
+    assert_eq!(section.read_u16().unwrap(), 0x0201);
+    assert!(matches!(
+        section.read_u16(),
+        Err(Error::UnexpectedEof {
+            needed: 2,
+            offset: 2,
+            available: 1,
+        })
+    ));
+    assert!(matches!(
+        section.ensure_consumed(),
+        Err(Error::TrailingBytes {
+            remaining: 1,
+            offset: 2,
+        })
+    ));
+    assert_eq!(section.read_u8().unwrap(), 0x3);
+    section.ensure_consumed().unwrap();
+    assert_eq!(reader.read_u8().unwrap(), 0x4);
+    reader.ensure_consumed().unwrap();
+}
+
+#[test]
+fn test_sub_reader_overread_protection() {
+    let data = [0x1, 0x2, 0x3];
+    let mut reader = ByteReader::from_bytes(&data);
+    assert!(reader.read_bit().is_ok());
+    // The partially read byte is skipped, as with the other byte reads
+    assert!(matches!(
+        reader.sub_reader(3),
+        Err(Error::UnexpectedEof {
+            needed: 3,
+            offset: 1,
+            available: 2,
+        })
+    ));
+    assert!(matches!(
+        reader.sub_reader(usize::MAX),
+        Err(Error::UnexpectedEof {
+            needed: usize::MAX,
+            offset: 1,
+            available: 2,
+        })
+    ));
+    let mut section = reader.sub_reader(2).unwrap();
+    assert_eq!(section.read_bits(12).unwrap(), 0x020);
+    // The unread bits of the last byte are padding
+    section.ensure_consumed().unwrap();
+}
+
+#[test]
+fn test_nested_sub_readers() {
+    let data = [0x4, 0x2, 0xA, 0xB, 0xC, 0xD];
+    let mut reader = ByteReader::from_bytes(&data);
+    let len = reader.read_u8().unwrap() as usize;
+    let mut outer = reader.sub_reader(len).unwrap();
+    let len = outer.read_u8().unwrap() as usize;
+    let mut inner = outer.sub_reader(len).unwrap();
+    assert_eq!(inner.read_u16().unwrap(), 0x0A0B);
+    inner.ensure_consumed().unwrap();
+    assert_eq!(outer.read_u8().unwrap(), 0xC);
+    outer.ensure_consumed().unwrap();
+    assert_eq!(reader.read_u8().unwrap(), 0xD);
+}
+
+#[test]
+fn test_read_slice() {
+    let data = [0x1, 0x2, 0x3, 0x4];
+    let slice = {
+        let mut reader = ByteReader::from_bytes(&data);
+        assert_eq!(reader.read_slice(1).unwrap(), &[0x1]);
+        let slice = reader.read_slice(3).unwrap();
+        assert!(reader.read_slice(1).is_err());
+        slice
+    };
+    // The slice borrows the data, not the reader
+    assert_eq!(slice, &[0x2, 0x3, 0x4]);
+    assert!(core::ptr::eq(slice, &data[1..]));
+}
+
+#[test]
+fn test_read_slice_after_bits() {
+    let data = [0xFF, 0x2, 0x3];
+    let mut reader = ByteReader::from_bytes(&data);
+    reader.read_bits(3).unwrap();
+    assert_eq!(reader.read_slice(2).unwrap(), &[0x2, 0x3]);
+    assert!(matches!(
+        reader.read_slice(1),
+        Err(Error::UnexpectedEof {
+            needed: 1,
+            offset: 3,
+            available: 0,
+        })
+    ));
+}
+
+#[test]
+fn test_read_array() {
+    let data = [0xCA, 0xFE, 0xBA, 0xBE, 0x1];
+    let mut reader = ByteReader::from_bytes(&data);
+    assert_eq!(reader.read_array::<4>().unwrap(), [0xCA, 0xFE, 0xBA, 0xBE]);
+    assert_eq!(reader.read_array::<0>().unwrap(), [0u8; 0]);
+    assert!(reader.read_array::<2>().is_err());
+    assert_eq!(reader.read_array().unwrap(), [0x1]);
+}
+
+#[test]
+fn test_read_str() {
+    let data = b"Hi\xFFthere";
+    let mut reader = ByteReader::from_bytes(data);
+    assert_eq!(reader.read_str(2).unwrap(), "Hi");
+    assert_eq!(reader.read_str(0).unwrap(), "");
+    assert_eq!(reader.read_str(2), Err(Error::InvalidUtf8 { offset: 2 }));
+    // The invalid bytes were consumed
+    assert_eq!(reader.read_str(4).unwrap(), "here");
+    assert!(reader.read_str(1).is_err());
+}
+
+#[test]
+fn test_read_str_prefixed() {
+    let mut buffer = ByteBuffer::new();
+    buffer
+        .write_prefixed_string("u8", LengthPrefix::U8)
+        .unwrap();
+    buffer
+        .write_prefixed_string("u16", LengthPrefix::U16)
+        .unwrap();
+    buffer.write_string("u32");
+    buffer
+        .write_prefixed_string("u64", LengthPrefix::U64)
+        .unwrap();
+    buffer
+        .write_prefixed_string("varint", LengthPrefix::VarInt)
+        .unwrap();
+    buffer
+        .write_prefixed_string("nul", LengthPrefix::NulTerminated)
+        .unwrap();
+    buffer
+        .write_prefixed_string("fixed", LengthPrefix::Fixed(8))
+        .unwrap();
+
+    let mut reader = ByteReader::from_bytes(buffer.as_bytes());
+    assert_eq!(reader.read_str_prefixed(LengthPrefix::U8).unwrap(), "u8");
+    assert_eq!(reader.read_str_prefixed(LengthPrefix::U16).unwrap(), "u16");
+    assert_eq!(reader.read_str_prefixed(LengthPrefix::U32).unwrap(), "u32");
+    assert_eq!(reader.read_str_prefixed(LengthPrefix::U64).unwrap(), "u64");
+    assert_eq!(
+        reader.read_str_prefixed(LengthPrefix::VarInt).unwrap(),
+        "varint"
+    );
+    assert_eq!(
+        reader
+            .read_str_prefixed(LengthPrefix::NulTerminated)
+            .unwrap(),
+        "nul"
+    );
+    assert_eq!(
+        reader.read_str_prefixed(LengthPrefix::Fixed(8)).unwrap(),
+        "fixed"
+    );
+    reader.ensure_consumed().unwrap();
+}
+
+#[test]
+fn test_read_str_prefixed_errors() {
+    let data = [0x3, b'a', 0xFF, b'b', b'c'];
+    let mut reader = ByteReader::from_bytes(&data);
+    assert_eq!(
+        reader.read_str_prefixed(LengthPrefix::U8),
+        Err(Error::InvalidUtf8 { offset: 2 })
+    );
+    assert!(matches!(
+        reader.read_str_prefixed(LengthPrefix::NulTerminated),
+        Err(Error::UnexpectedEof { .. })
+    ));
+    assert!(matches!(
+        reader.read_str_prefixed(LengthPrefix::U8),
+        Err(Error::UnexpectedEof { .. })
+    ));
+}
+
+#[test]
+fn test_get_at() {
+    let mut buffer = ByteBuffer::new();
+    buffer.write_u8(0xFF);
+    buffer.write_u16(0x0102);
+    buffer.write_u32(0x03040506);
+    buffer.write_u64(0xFFFF_FFFF_FFFF_FFFE);
+    buffer.write_u128(1);
+    buffer.write_f32(0.5);
+    buffer.write_f64(-0.25);
+
+    let mut reader = ByteReader::from_bytes(buffer.as_bytes());
+    reader.read_u8().unwrap();
+    assert_eq!(reader.get_u8_at(0).unwrap(), 0xFF);
+    assert_eq!(reader.get_i8_at(0).unwrap(), -1);
+    assert_eq!(reader.get_u16_at(1).unwrap(), 0x0102);
+    assert_eq!(reader.get_i16_at(1).unwrap(), 0x0102);
+    assert_eq!(reader.get_u32_at(3).unwrap(), 0x03040506);
+    assert_eq!(reader.get_i32_at(3).unwrap(), 0x03040506);
+    assert_eq!(reader.get_u64_at(7).unwrap(), 0xFFFF_FFFF_FFFF_FFFE);
+    assert_eq!(reader.get_i64_at(7).unwrap(), -2);
+    assert_eq!(reader.get_u128_at(15).unwrap(), 1);
+    assert_eq!(reader.get_i128_at(15).unwrap(), 1);
+    assert_eq!(reader.get_f32_at(31).unwrap(), 0.5);
+    assert_eq!(reader.get_f64_at(35).unwrap(), -0.25);
+    assert!(reader.get_f64_at(36).is_err());
+    assert_eq!(reader.get_rpos(), 1);
+
+    reader.set_endian(Endian::LittleEndian);
+    assert_eq!(reader.get_u32_at(3).unwrap(), 0x06050403);
+}
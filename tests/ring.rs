@@ -0,0 +1,215 @@
+use bytebuffer::{
+    BitOrder, ByteRead, ByteWrite, Endian, Error, LengthPrefix, RingByteBuffer, RingOverflow,
+};
+use std::io::{Read, Write};
+
+#[test]
+fn test_wrap_around() {
+    let mut buffer = RingByteBuffer::with_capacity(8);
+    buffer.write_u32(0x01020304).unwrap();
+    buffer.write_u16(0x0506).unwrap();
+    assert_eq!(buffer.read_u32().unwrap(), 0x01020304);
+
+    // The value is split between the end and the start of the storage
+    buffer.write_u32(0x0708090A).unwrap();
+    buffer.write_u16(0x0B0C).unwrap();
+    assert_eq!(buffer.len(), 8);
+    assert_eq!(buffer.free(), 0);
+    assert_eq!(buffer.read_u16().unwrap(), 0x0506);
+    assert_eq!(buffer.read_u32().unwrap(), 0x0708090A);
+    assert_eq!(buffer.read_u16().unwrap(), 0x0B0C);
+    assert!(buffer.is_empty());
+    assert_eq!(buffer.get_rpos(), 12);
+    assert_eq!(buffer.get_wpos(), 12);
+}
+
+#[test]
+fn test_endian() {
+    let mut buffer = RingByteBuffer::with_capacity(6);
+    buffer.set_endian(Endian::LittleEndian);
+    buffer.write_u16(0x0102).unwrap();
+    buffer.write_f32(1.5).unwrap();
+    buffer.read_u8().unwrap();
+    buffer.write_i8(-1).unwrap();
+    assert_eq!(buffer.read_u8().unwrap(), 0x01);
+    assert_eq!(buffer.read_f32().unwrap(), 1.5);
+    buffer.set_endian(Endian::BigEndian);
+    assert_eq!(buffer.read_i8().unwrap(), -1);
+}
+
+#[test]
+fn test_reject_when_full() {
+    let mut buffer = RingByteBuffer::with_capacity(4);
+    buffer.write_u16(0x0102).unwrap();
+    let result = buffer.write_u32(0x03040506);
+    assert!(matches!(
+        result,
+        Err(Error::BufferFull {
+            needed: 4,
+            available: 2,
+            offset: 2
+        })
+    ));
+    assert_eq!(buffer.len(), 2);
+    assert_eq!(buffer.read_u16().unwrap(), 0x0102);
+    assert!(matches!(
+        buffer.read_u8(),
+        Err(Error::UnexpectedEof {
+            needed: 1,
+            available: 0,
+            offset: 2
+        })
+    ));
+}
+
+#[test]
+fn test_reject_prefixed_untouched() {
+    let mut buffer = RingByteBuffer::with_capacity(8);
+    buffer.write_u8(0x1).unwrap();
+    assert!(matches!(
+        buffer.write_string("hello"),
+        Err(Error::BufferFull {
+            needed: 9,
+            available: 7,
+            offset: 1
+        })
+    ));
+    for prefix in [
+        LengthPrefix::U16,
+        LengthPrefix::VarInt,
+        LengthPrefix::NulTerminated,
+        LengthPrefix::Fixed(8),
+    ] {
+        assert!(buffer.write_prefixed_string("hello!!", prefix).is_err());
+    }
+    assert_eq!(buffer.len(), 1);
+    buffer.write_string("abc").unwrap();
+    assert_eq!(buffer.read_u8().unwrap(), 0x1);
+    assert_eq!(buffer.read_string().unwrap(), "abc");
+
+    // With overwrite, a string longer than the storage does not drop anything either
+    buffer.set_overflow(RingOverflow::Overwrite);
+    buffer.write_u8(0x2).unwrap();
+    assert!(buffer.write_string("hello").is_err());
+    assert_eq!(buffer.read_u8().unwrap(), 0x2);
+}
+
+#[test]
+fn test_truncated_nul_terminated_untouched() {
+    let mut buffer = RingByteBuffer::with_capacity(4);
+    buffer.write_bytes(&[0x1, 0x2, 0x3]).unwrap();
+    buffer.read_u16().unwrap();
+    buffer.write_bytes(b"ab").unwrap();
+    buffer.read_u8().unwrap();
+    assert!(matches!(
+        buffer
+            .read_prefixed_string(LengthPrefix::NulTerminated)
+            .unwrap_err(),
+        Error::UnexpectedEof { offset: 5, .. }
+    ));
+    assert_eq!(buffer.get_rpos(), 3);
+
+    // The terminator wraps around to the start of the storage
+    buffer.write_u8(0).unwrap();
+    assert_eq!(
+        buffer
+            .read_prefixed_bytes(LengthPrefix::NulTerminated)
+            .unwrap(),
+        b"ab"
+    );
+}
+
+#[test]
+fn test_overwrite_oldest() {
+    let mut buffer = RingByteBuffer::with_capacity(4);
+    buffer.set_overflow(RingOverflow::Overwrite);
+    assert_eq!(buffer.overflow(), RingOverflow::Overwrite);
+    buffer.write_bytes(&[0x1, 0x2, 0x3]).unwrap();
+    buffer.write_u16(0x0405).unwrap();
+    assert_eq!(buffer.get_rpos(), 1);
+    assert_eq!(buffer.read_bytes(4).unwrap(), vec![0x2, 0x3, 0x4, 0x5]);
+
+    // A write longer than the whole storage is still rejected
+    assert!(buffer.write_bytes(&[0; 5]).is_err());
+    assert!(buffer.is_empty());
+}
+
+#[test]
+fn test_bits_across_wrap() {
+    let mut buffer = RingByteBuffer::with_capacity(3);
+    buffer.write_u16(0).unwrap();
+    buffer.read_u16().unwrap();
+
+    buffer.write_bits(0b101, 3).unwrap();
+    buffer.write_bits(0x1234, 16).unwrap();
+    buffer.write_bit(true).unwrap();
+    assert_eq!(buffer.len(), 3);
+    assert!(buffer.write_bits(0, 5).is_err());
+    assert_eq!(buffer.read_bits(3).unwrap(), 0b101);
+    assert_eq!(buffer.read_bits(16).unwrap(), 0x1234);
+    assert!(buffer.read_bit().unwrap());
+    assert!(buffer.read_bit().is_err());
+
+    buffer.set_bit_order(BitOrder::LsbFirst);
+    buffer.write_bits_u128(0x5, 4).unwrap();
+    assert_eq!(buffer.read_u8().unwrap(), 0x5);
+}
+
+#[test]
+fn test_trait_flush_bits() {
+    let mut buffer = RingByteBuffer::with_capacity(2);
+    buffer.write_bit(true).unwrap();
+    assert!(buffer.read_bit().unwrap());
+    ByteRead::flush_bits(&mut buffer);
+    assert!(buffer.read_bit().is_err());
+    buffer.write_bit(true).unwrap();
+    ByteWrite::flush_bits(&mut buffer);
+    buffer.write_u8(0x3).unwrap();
+    assert_eq!(buffer.read_u8().unwrap(), 0x3);
+    assert!(buffer.is_empty());
+}
+
+#[test]
+fn test_traits() {
+    let mut buffer = RingByteBuffer::with_capacity(16);
+    for _ in 0..3 {
+        buffer.write_string("hello").unwrap();
+        buffer.write_var_u64(300).unwrap();
+        buffer
+            .write_prefixed_string("ring", LengthPrefix::U8)
+            .unwrap();
+        assert_eq!(buffer.read_string().unwrap(), "hello");
+        assert_eq!(buffer.read_var_u64().unwrap(), 300);
+        assert_eq!(
+            buffer.read_prefixed_string(LengthPrefix::U8).unwrap(),
+            "ring"
+        );
+    }
+    assert!(buffer.write_string("a string too long").is_err());
+}
+
+#[test]
+fn test_io() {
+    let mut buffer = RingByteBuffer::with_capacity(4);
+    assert_eq!(buffer.write(&[0x1, 0x2, 0x3, 0x4, 0x5]).unwrap(), 4);
+    let mut buf = [0; 2];
+    assert_eq!(buffer.read(&mut buf).unwrap(), 2);
+    assert_eq!(buf, [0x1, 0x2]);
+
+    buffer.set_overflow(RingOverflow::Overwrite);
+    assert_eq!(buffer.write(&[0x6, 0x7, 0x8]).unwrap(), 3);
+    let mut buf = Vec::new();
+    buffer.read_to_end(&mut buf).unwrap();
+    assert_eq!(buf, vec![0x4, 0x6, 0x7, 0x8]);
+}
+
+#[test]
+fn test_debug() {
+    let mut buffer = RingByteBuffer::with_capacity(2);
+    buffer.write_u16(0x0102).unwrap();
+    buffer.read_u8().unwrap();
+    assert_eq!(
+        format!("{:?}", buffer),
+        "RingByteBuffer { remaining_data: [2], capacity: 2, wpos: 2, rpos: 1, endian: BigEndian }"
+    );
+}
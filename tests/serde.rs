@@ -0,0 +1,178 @@
+#![cfg(feature = "serde")]
+
+use bytebuffer::{
+    from_bytes, to_vec, ByteBuffer, ByteReader, ByteWriter, Deserializer, Endian, Error, Serializer,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+enum Kind {
+    Empty,
+    Code(u16),
+    Pair(u8, u8),
+    Named { a: i8 },
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Message {
+    id: u32,
+    flag: bool,
+    name: String,
+    tags: Vec<u8>,
+    extra: Option<i64>,
+    kinds: Vec<Kind>,
+    map: BTreeMap<u8, char>,
+    ratio: f32,
+    unit: (),
+    tuple: (u8, i16),
+}
+
+fn message() -> Message {
+    Message {
+        id: 42,
+        flag: true,
+        name: "hello".to_string(),
+        tags: vec![1, 2, 3],
+        extra: Some(-1),
+        kinds: vec![
+            Kind::Empty,
+            Kind::Code(7),
+            Kind::Pair(1, 2),
+            Kind::Named { a: -3 },
+        ],
+        map: [(1, 'a'), (2, 'é')].into_iter().collect(),
+        ratio: 0.5,
+        unit: (),
+        tuple: (9, -9),
+    }
+}
+
+#[test]
+fn test_roundtrip() {
+    let bytes = to_vec(&message()).unwrap();
+    assert_eq!(from_bytes::<Message>(&bytes).unwrap(), message());
+
+    let mut buffer = ByteBuffer::new();
+    buffer.set_endian(Endian::LittleEndian);
+    message()
+        .serialize(&mut Serializer::new(&mut buffer))
+        .unwrap();
+    let mut reader = ByteReader::from_bytes(buffer.as_bytes());
+    reader.set_endian(Endian::LittleEndian);
+    let value = Message::deserialize(&mut Deserializer::new(&mut reader)).unwrap();
+    assert_eq!(value, message());
+    assert_eq!(reader.get_rpos(), reader.len());
+}
+
+#[test]
+fn test_layout() {
+    #[derive(Serialize)]
+    struct Layout<'a> {
+        a: u16,
+        b: &'a str,
+        c: Option<u8>,
+        d: Kind,
+    }
+
+    let value = Layout {
+        a: 0x0102,
+        b: "hi",
+        c: None,
+        d: Kind::Code(3),
+    };
+    assert_eq!(
+        to_vec(&value).unwrap(),
+        [0x1, 0x2, 0x0, 0x0, 0x0, 0x2, b'h', b'i', 0x0, 0x0, 0x0, 0x0, 0x1, 0x0, 0x3]
+    );
+
+    let mut buffer = ByteBuffer::new();
+    buffer.set_endian(Endian::LittleEndian);
+    value.serialize(&mut Serializer::new(&mut buffer)).unwrap();
+    assert_eq!(
+        buffer.as_bytes(),
+        [0x2, 0x1, 0x2, 0x0, 0x0, 0x0, b'h', b'i', 0x0, 0x1, 0x0, 0x0, 0x0, 0x3, 0x0]
+    );
+}
+
+#[test]
+fn test_string_matches_read_string() {
+    let mut buffer = ByteBuffer::from_vec(to_vec("hello").unwrap());
+    assert_eq!(buffer.read_string().unwrap(), "hello");
+
+    let mut buffer = ByteBuffer::new();
+    buffer.write_string("hello");
+    assert_eq!(from_bytes::<String>(buffer.as_bytes()).unwrap(), "hello");
+}
+
+#[test]
+fn test_zero_copy() {
+    #[derive(Deserialize)]
+    struct Borrowed<'a> {
+        name: &'a str,
+        data: &'a [u8],
+    }
+
+    let data = [0x0, 0x0, 0x0, 0x1, b'a', 0x0, 0x0, 0x0, 0x2, 0xA, 0xB];
+    let value: Borrowed = from_bytes(&data).unwrap();
+    assert_eq!(value.name, "a");
+    assert_eq!(value.data, [0xA, 0xB]);
+    assert!(std::ptr::eq(value.name.as_ptr(), &data[4]));
+    assert!(std::ptr::eq(value.data.as_ptr(), &data[9]));
+}
+
+#[test]
+fn test_fixed_size_writer() {
+    let mut data = [0; 6];
+    let mut writer = ByteWriter::from_bytes(&mut data);
+    (1u16, 2u32)
+        .serialize(&mut Serializer::new(&mut writer))
+        .unwrap();
+    assert_eq!(data, [0x0, 0x1, 0x0, 0x0, 0x0, 0x2]);
+
+    let mut data = [0; 5];
+    let mut writer = ByteWriter::from_bytes(&mut data);
+    assert!(matches!(
+        (1u16, 2u32)
+            .serialize(&mut Serializer::new(&mut writer))
+            .unwrap_err(),
+        Error::BufferFull { .. }
+    ));
+}
+
+#[test]
+fn test_errors() {
+    assert!(matches!(
+        from_bytes::<u32>(&[0x0, 0x1]).unwrap_err(),
+        Error::UnexpectedEof { offset: 0, .. }
+    ));
+    assert!(matches!(
+        from_bytes::<bool>(&[0x2]).unwrap_err(),
+        Error::Custom(_)
+    ));
+    assert!(matches!(
+        from_bytes::<char>(&[0x0, 0x0, 0xD8, 0x00]).unwrap_err(),
+        Error::Custom(_)
+    ));
+    assert_eq!(
+        from_bytes::<&str>(&[0x0, 0x0, 0x0, 0x2, b'a', 0xFF]).unwrap_err(),
+        Error::InvalidUtf8 { offset: 5 }
+    );
+    assert!(matches!(
+        from_bytes::<Kind>(&[0x0, 0x0, 0x0, 0x9]).unwrap_err(),
+        Error::Custom(_)
+    ));
+    assert!(matches!(
+        from_bytes::<Vec<u8>>(&[0x0, 0x0, 0x0, 0x3, 0x1]).unwrap_err(),
+        Error::UnexpectedEof { offset: 5, .. }
+    ));
+
+    // Iterators without an exact size cannot be prefixed with their length
+    struct Unsized;
+    impl Serialize for Unsized {
+        fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+            s.collect_seq((0..3u8).filter(|_| true))
+        }
+    }
+    assert!(matches!(to_vec(&Unsized).unwrap_err(), Error::Custom(_)));
+}
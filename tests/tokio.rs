@@ -0,0 +1,136 @@
+#![cfg(feature = "tokio")]
+
+use bytebuffer::{
+    ByteBuffer, ByteReader, Endian, Error, FrameCodec, FrameMessage, LengthPrefix, Result,
+};
+use bytes::BytesMut;
+use futures::{SinkExt, StreamExt};
+use tokio::io::AsyncWriteExt;
+use tokio_util::codec::{Decoder, Encoder, FramedRead, FramedWrite};
+
+#[derive(Debug, PartialEq)]
+struct Message {
+    id: u16,
+    text: String,
+}
+
+impl FrameMessage for Message {
+    fn decode_frame(reader: &mut ByteReader<'_>) -> Result<Self> {
+        Ok(Message {
+            id: reader.read_u16()?,
+            text: reader.read_prefixed_string(LengthPrefix::U8)?,
+        })
+    }
+
+    fn encode_frame(&self, buffer: &mut ByteBuffer) -> Result<()> {
+        buffer.write_u16(self.id);
+        buffer.write_prefixed_string(&self.text, LengthPrefix::U8)
+    }
+}
+
+fn message(id: u16, text: &str) -> Message {
+    Message {
+        id,
+        text: text.to_string(),
+    }
+}
+
+#[test]
+fn test_frame_layout() {
+    let mut codec = FrameCodec::new(LengthPrefix::U16);
+    let mut dst = BytesMut::new();
+    codec.encode(message(1, "hi"), &mut dst).unwrap();
+    assert_eq!(dst[..], [0x0, 0x5, 0x0, 0x1, 0x2, b'h', b'i']);
+
+    codec.set_endian(Endian::LittleEndian);
+    codec.encode(message(1, "hi"), &mut dst).unwrap();
+    assert_eq!(dst[7..], [0x5, 0x0, 0x1, 0x0, 0x2, b'h', b'i']);
+
+    let mut codec = FrameCodec::<Message>::new(LengthPrefix::U16);
+    assert_eq!(codec.decode(&mut dst).unwrap(), Some(message(1, "hi")));
+    assert_eq!(dst.len(), 7);
+}
+
+#[test]
+fn test_partial_frames() {
+    for prefix in [
+        LengthPrefix::U8,
+        LengthPrefix::U32,
+        LengthPrefix::VarInt,
+        LengthPrefix::Fixed(8),
+        LengthPrefix::NulTerminated,
+    ] {
+        let mut codec = FrameCodec::<Message>::new(prefix);
+        let mut encoded = BytesMut::new();
+        // The messages contain no 0 byte, so that they can be NulTerminated
+        codec.encode(message(0x107, "abc"), &mut encoded).unwrap();
+        codec.encode(message(0x108, "d"), &mut encoded).unwrap();
+
+        // Feed the stream one byte at a time
+        let mut src = BytesMut::new();
+        let mut decoded = Vec::new();
+        for byte in encoded.iter() {
+            src.extend_from_slice(&[*byte]);
+            while let Some(message) = codec.decode(&mut src).unwrap() {
+                decoded.push(message);
+            }
+        }
+        assert_eq!(decoded.len(), 2, "{:?}", prefix);
+        assert_eq!(decoded[0].id, 0x107);
+        assert!(src.is_empty());
+    }
+}
+
+#[test]
+fn test_frame_errors() {
+    let mut codec = FrameCodec::<Message>::new(LengthPrefix::U32);
+    codec.set_max_frame_length(16);
+    assert_eq!(codec.max_frame_length(), 16);
+
+    // The length is checked before the body is received
+    let mut src = BytesMut::from(&[0x0, 0x0, 0x0, 0x11][..]);
+    let error = codec.decode(&mut src).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+
+    let mut dst = BytesMut::new();
+    assert!(codec.encode(message(1, &"a".repeat(14)), &mut dst).is_err());
+    assert!(dst.is_empty());
+
+    // An invalid body is consumed, so that the next frame can still be decoded
+    let mut src = BytesMut::from(&[0x0, 0x0, 0x0, 0x2, 0x0, 0x1][..]);
+    codec.encode(message(2, "ok"), &mut src).unwrap();
+    let error = codec.decode(&mut src).unwrap_err();
+    let error = error.into_inner().unwrap().downcast::<Error>().unwrap();
+    assert!(matches!(*error, Error::UnexpectedEof { offset: 2, .. }));
+    assert_eq!(codec.decode(&mut src).unwrap(), Some(message(2, "ok")));
+
+    let mut codec = FrameCodec::<Message>::new(LengthPrefix::NulTerminated);
+    codec.set_max_frame_length(4);
+    let mut src = BytesMut::from(&[0x1; 5][..]);
+    assert!(codec.decode(&mut src).is_err());
+}
+
+#[tokio::test]
+async fn test_framed_over_duplex() {
+    let (client, server) = tokio::io::duplex(8);
+    let mut reader = FramedRead::new(server, FrameCodec::<Message>::new(LengthPrefix::VarInt));
+
+    let writer = tokio::spawn(async move {
+        let mut writer = FramedWrite::new(client, FrameCodec::new(LengthPrefix::VarInt));
+        for id in 0..20 {
+            let text = "x".repeat(id as usize * 3);
+            writer.send(message(id, &text)).await.unwrap();
+        }
+        // A truncated frame at the end of the stream
+        writer.get_mut().write_all(&[0x5, 0x0]).await.unwrap();
+    });
+
+    for id in 0..20 {
+        let received = reader.next().await.unwrap().unwrap();
+        assert_eq!(received, message(id, &"x".repeat(id as usize * 3)));
+    }
+    writer.await.unwrap();
+    let error = reader.next().await.unwrap().unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::Other);
+    assert!(reader.next().await.is_none());
+}
@@ -0,0 +1,144 @@
+#![cfg(feature = "tokio")]
+
+use bytebuffer::{
+    tokio_io::{AsyncByteReadExt, AsyncByteWriteExt},
+    ByteBuffer, Endian, Error,
+};
+use std::io::ErrorKind;
+
+#[tokio::test]
+async fn test_values_over_duplex() {
+    // A small pipe, so that values are split across several reads
+    let (mut client, mut server) = tokio::io::duplex(3);
+
+    let writer = tokio::spawn(async move {
+        client.write_byte(0xFF).await.unwrap();
+        client
+            .write_i16_with(-2, Endian::LittleEndian)
+            .await
+            .unwrap();
+        client
+            .write_u32_with(0x01020304, Endian::BigEndian)
+            .await
+            .unwrap();
+        client
+            .write_i64_with(-3, Endian::LittleEndian)
+            .await
+            .unwrap();
+        client
+            .write_u128_with(u128::MAX - 1, Endian::BigEndian)
+            .await
+            .unwrap();
+        client
+            .write_f64_with(0.5, Endian::LittleEndian)
+            .await
+            .unwrap();
+        client
+            .write_string("héllo", Endian::LittleEndian)
+            .await
+            .unwrap();
+        client.write_var_u32(300).await.unwrap();
+        client.write_var_u64(u64::MAX).await.unwrap();
+        client.write_var_i32(-129).await.unwrap();
+        client.write_var_i64(i64::MIN).await.unwrap();
+
+        let mut frame = ByteBuffer::new();
+        frame.write_u16(0xABCD);
+        frame.write_string("frame");
+        client.write_var_u64(frame.len() as u64).await.unwrap();
+        client.write_bytes(frame.as_bytes()).await.unwrap();
+    });
+
+    assert_eq!(server.read_byte().await.unwrap(), 0xFF);
+    assert_eq!(
+        server.read_i16_with(Endian::LittleEndian).await.unwrap(),
+        -2
+    );
+    assert_eq!(
+        server.read_u32_with(Endian::BigEndian).await.unwrap(),
+        0x01020304
+    );
+    assert_eq!(
+        server.read_i64_with(Endian::LittleEndian).await.unwrap(),
+        -3
+    );
+    assert_eq!(
+        server.read_u128_with(Endian::BigEndian).await.unwrap(),
+        u128::MAX - 1
+    );
+    assert_eq!(
+        server.read_f64_with(Endian::LittleEndian).await.unwrap(),
+        0.5
+    );
+    assert_eq!(
+        server.read_string(Endian::LittleEndian).await.unwrap(),
+        "héllo"
+    );
+    assert_eq!(server.read_var_u32().await.unwrap(), 300);
+    assert_eq!(server.read_var_u64().await.unwrap(), u64::MAX);
+    assert_eq!(server.read_var_i32().await.unwrap(), -129);
+    assert_eq!(server.read_var_i64().await.unwrap(), i64::MIN);
+
+    let len = server.read_var_u64().await.unwrap() as usize;
+    let mut frame = server.read_frame(len).await.unwrap();
+    assert_eq!(frame.read_u16().unwrap(), 0xABCD);
+    assert_eq!(frame.read_string().unwrap(), "frame");
+    assert_eq!(frame.get_rpos(), frame.len());
+
+    writer.await.unwrap();
+    let error = server.read_byte().await.unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+}
+
+#[tokio::test]
+async fn test_errors() {
+    let (mut client, mut server) = tokio::io::duplex(64);
+    client.write_u32_with(3, Endian::BigEndian).await.unwrap();
+    client.write_bytes(&[b'a', 0xC3, 0x28]).await.unwrap();
+    client.write_bytes(&[0xFF; 10]).await.unwrap();
+    client
+        .write_bytes(&[0x80, 0x80, 0x80, 0x80, 0x10])
+        .await
+        .unwrap();
+    // A bogus length, larger than the stream
+    client
+        .write_u32_with(u32::MAX, Endian::BigEndian)
+        .await
+        .unwrap();
+    client.write_bytes(&[0x1; 16]).await.unwrap();
+    drop(client);
+
+    let error = server.read_string(Endian::BigEndian).await.unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidData);
+    let error = error.into_inner().unwrap().downcast::<Error>().unwrap();
+    assert_eq!(*error, Error::InvalidUtf8 { offset: 5 });
+
+    let error = server.read_var_u64().await.unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidData);
+    let error = server.read_var_u32().await.unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidData);
+
+    let error = server.read_string(Endian::BigEndian).await.unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+}
+
+#[tokio::test]
+async fn test_with_tokio_ext_traits() {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let (mut client, mut server) = tokio::io::duplex(64);
+    client.write_byte(0x1).await.unwrap();
+    client
+        .write_u16_with(0x0203, Endian::LittleEndian)
+        .await
+        .unwrap();
+    client.write_u16(0x0405).await.unwrap();
+    client.shutdown().await.unwrap();
+
+    assert_eq!(server.read_byte().await.unwrap(), 0x1);
+    assert_eq!(
+        server.read_u16_with(Endian::LittleEndian).await.unwrap(),
+        0x0203
+    );
+    assert_eq!(server.read_u16().await.unwrap(), 0x0405);
+}
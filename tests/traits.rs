@@ -0,0 +1,127 @@
+use bytebuffer::{ByteBuffer, ByteRead, ByteReader, ByteWrite, Endian, Error, Result};
+
+#[derive(Debug, PartialEq)]
+struct Packet {
+    id: u16,
+    flag: bool,
+    kind: u8,
+    value: f64,
+    name: String,
+}
+
+fn encode<W: ByteWrite>(w: &mut W, packet: &Packet) -> Result<()> {
+    w.write_u16(packet.id)?;
+    w.write_bit(packet.flag)?;
+    w.write_bits(packet.kind as u64, 3)?;
+    w.flush_bits();
+    w.write_f64(packet.value)?;
+    w.write_string(&packet.name)
+}
+
+fn decode<R: ByteRead>(r: &mut R) -> Result<Packet> {
+    let id = r.read_u16()?;
+    let flag = r.read_bit()?;
+    let kind = r.read_bits(3)? as u8;
+    r.flush_bits();
+    Ok(Packet {
+        id,
+        flag,
+        kind,
+        value: r.read_f64()?,
+        name: r.read_string()?,
+    })
+}
+
+fn packet() -> Packet {
+    Packet {
+        id: 0x1234,
+        flag: true,
+        kind: 5,
+        value: 0.5,
+        name: "hello".to_string(),
+    }
+}
+
+#[test]
+fn test_generic_roundtrip_buffer() {
+    let mut buffer = ByteBuffer::new();
+    encode(&mut buffer, &packet()).unwrap();
+    assert_eq!(decode(&mut buffer).unwrap(), packet());
+}
+
+#[test]
+fn test_generic_roundtrip_reader() {
+    let mut buffer = ByteBuffer::new();
+    encode(&mut buffer, &packet()).unwrap();
+    let mut reader = ByteReader::from(buffer.as_bytes());
+    assert_eq!(decode(&mut reader).unwrap(), packet());
+}
+
+#[test]
+fn test_generic_matches_inherent() {
+    let mut generic = ByteBuffer::new();
+    encode(&mut generic, &packet()).unwrap();
+
+    let mut inherent = ByteBuffer::new();
+    inherent.write_u16(0x1234);
+    inherent.write_bit(true);
+    inherent.write_bits(5, 3);
+    inherent.flush_bits();
+    inherent.write_f64(0.5);
+    inherent.write_string("hello");
+
+    assert_eq!(generic.as_bytes(), inherent.as_bytes());
+}
+
+#[test]
+fn test_generic_endian() {
+    let mut buffer = ByteBuffer::new();
+    buffer.set_endian(Endian::LittleEndian);
+    assert_eq!(ByteWrite::endian(&buffer), Endian::LittleEndian);
+    ByteWrite::write_u32(&mut buffer, 1).unwrap();
+    assert_eq!(buffer.as_bytes(), [0x1, 0x0, 0x0, 0x0]);
+
+    let mut reader = ByteReader::from(buffer.as_bytes());
+    reader.set_endian(Endian::LittleEndian);
+    assert_eq!(ByteRead::endian(&reader), Endian::LittleEndian);
+    assert_eq!(ByteRead::read_u32(&mut reader).unwrap(), 1);
+}
+
+#[test]
+fn test_generic_flush_bits_only_affects_one_cursor() {
+    let mut buffer = ByteBuffer::new();
+    buffer.write_bit(true);
+    ByteRead::flush_bits(&mut buffer);
+    buffer.write_bit(true);
+    ByteWrite::flush_bits(&mut buffer);
+    assert_eq!(buffer.as_bytes(), [0xC0]);
+}
+
+#[test]
+fn test_generic_overread() {
+    let data = [0x0, 0x1];
+    let mut reader = ByteReader::from(&data[..]);
+    let error = decode(&mut reader).err().unwrap();
+    assert!(matches!(error, Error::UnexpectedEof { .. }));
+}
+
+#[test]
+#[cfg(feature = "half")]
+fn test_generic_half() {
+    fn write_half<W: ByteWrite>(w: &mut W) -> Result<()> {
+        w.write_f16(half::f16::from_f32(0.5))?;
+        w.write_bf16(half::bf16::from_f32(0.5))
+    }
+
+    fn read_half<R: ByteRead>(r: &mut R) -> Result<(half::f16, half::bf16)> {
+        Ok((r.read_f16()?, r.read_bf16()?))
+    }
+
+    let mut buffer = ByteBuffer::new();
+    write_half(&mut buffer).unwrap();
+    let mut reader = ByteReader::from(buffer.as_bytes());
+    assert_eq!(
+        read_half(&mut reader).unwrap(),
+        (half::f16::from_f32(0.5), half::bf16::from_f32(0.5))
+    );
+}
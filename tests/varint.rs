@@ -0,0 +1,246 @@
+use bytebuffer::{ByteBuffer, ByteRead, ByteReader, ByteWrite, ByteWriter, Error};
+
+#[test]
+fn test_var_u32_encoding() {
+    let cases: [(u32, &[u8]); 6] = [
+        (0, &[0x00]),
+        (1, &[0x01]),
+        (127, &[0x7F]),
+        (128, &[0x80, 0x01]),
+        (300, &[0xAC, 0x02]),
+        (u32::MAX, &[0xFF, 0xFF, 0xFF, 0xFF, 0x0F]),
+    ];
+    for (value, bytes) in cases {
+        let mut buffer = ByteBuffer::new();
+        buffer.write_var_u32(value);
+        assert_eq!(buffer.as_bytes(), bytes);
+        assert_eq!(buffer.read_var_u32().unwrap(), value);
+        assert_eq!(ByteReader::from(bytes).read_var_u32().unwrap(), value);
+    }
+}
+
+#[test]
+fn test_var_i32_encoding() {
+    let cases: [(i32, &[u8]); 7] = [
+        (0, &[0x00]),
+        (1, &[0x01]),
+        (-1, &[0x7F]),
+        (63, &[0x3F]),
+        (-64, &[0x40]),
+        (64, &[0xC0, 0x00]),
+        (-123456, &[0xC0, 0xBB, 0x78]),
+    ];
+    for (value, bytes) in cases {
+        let mut buffer = ByteBuffer::new();
+        buffer.write_var_i32(value);
+        assert_eq!(buffer.as_bytes(), bytes);
+        assert_eq!(buffer.read_var_i32().unwrap(), value);
+        assert_eq!(ByteReader::from(bytes).read_var_i32().unwrap(), value);
+    }
+}
+
+#[test]
+fn test_zigzag_encoding() {
+    let cases: [(i32, &[u8]); 6] = [
+        (0, &[0x00]),
+        (-1, &[0x01]),
+        (1, &[0x02]),
+        (-2, &[0x03]),
+        (i32::MAX, &[0xFE, 0xFF, 0xFF, 0xFF, 0x0F]),
+        (i32::MIN, &[0xFF, 0xFF, 0xFF, 0xFF, 0x0F]),
+    ];
+    for (value, bytes) in cases {
+        let mut buffer = ByteBuffer::new();
+        buffer.write_zigzag_i32(value);
+        assert_eq!(buffer.as_bytes(), bytes);
+        assert_eq!(buffer.read_zigzag_i32().unwrap(), value);
+        assert_eq!(ByteReader::from(bytes).read_zigzag_i32().unwrap(), value);
+    }
+}
+
+#[test]
+fn test_roundtrip_limits() {
+    let mut buffer = ByteBuffer::new();
+    for v in [0, 1, u64::MAX, u64::MAX >> 1] {
+        buffer.write_var_u64(v);
+    }
+    for v in [0, u128::MAX, 1 << 127] {
+        buffer.write_var_u128(v);
+    }
+    for v in [i64::MIN, i64::MAX, -1, 0] {
+        buffer.write_var_i64(v);
+        buffer.write_zigzag_i64(v);
+    }
+    for v in [i128::MIN, i128::MAX, -1, 0] {
+        buffer.write_var_i128(v);
+        buffer.write_zigzag_i128(v);
+    }
+    for v in [i32::MIN, i32::MAX] {
+        buffer.write_var_i32(v);
+    }
+
+    let mut reader = ByteReader::from(buffer.as_bytes());
+    for v in [0, 1, u64::MAX, u64::MAX >> 1] {
+        assert_eq!(reader.read_var_u64().unwrap(), v);
+    }
+    for v in [0, u128::MAX, 1 << 127] {
+        assert_eq!(reader.read_var_u128().unwrap(), v);
+    }
+    for v in [i64::MIN, i64::MAX, -1, 0] {
+        assert_eq!(reader.read_var_i64().unwrap(), v);
+        assert_eq!(reader.read_zigzag_i64().unwrap(), v);
+    }
+    for v in [i128::MIN, i128::MAX, -1, 0] {
+        assert_eq!(reader.read_var_i128().unwrap(), v);
+        assert_eq!(reader.read_zigzag_i128().unwrap(), v);
+    }
+    for v in [i32::MIN, i32::MAX] {
+        assert_eq!(reader.read_var_i32().unwrap(), v);
+    }
+    assert_eq!(reader.get_rpos(), reader.len());
+}
+
+#[test]
+fn test_max_encoded_length() {
+    let mut buffer = ByteBuffer::new();
+    buffer.write_var_u64(u64::MAX);
+    assert_eq!(buffer.len(), 10);
+
+    let mut buffer = ByteBuffer::new();
+    buffer.write_var_u128(u128::MAX);
+    assert_eq!(buffer.len(), 19);
+
+    let mut buffer = ByteBuffer::new();
+    buffer.write_var_i128(i128::MIN);
+    assert_eq!(buffer.len(), 19);
+}
+
+#[test]
+fn test_padded_encoding_is_accepted() {
+    let data = [0x81, 0x80, 0x80, 0x00];
+    assert_eq!(ByteReader::from(&data[..]).read_var_u32().unwrap(), 1);
+    let data = [0xFF, 0xFF, 0x7F];
+    assert_eq!(ByteReader::from(&data[..]).read_var_i32().unwrap(), -1);
+}
+
+#[test]
+fn test_overlong_u32() {
+    let data = [0x80, 0x80, 0x80, 0x80, 0x80, 0x00];
+    let mut reader = ByteReader::from(&data[..]);
+    assert_eq!(
+        reader.read_var_u32().unwrap_err(),
+        Error::VarIntOverflow { offset: 0 }
+    );
+}
+
+#[test]
+fn test_overflow_u32() {
+    let data = [0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0x1F];
+    let mut buffer = ByteBuffer::from_bytes(&data);
+    buffer.read_u8().unwrap();
+    assert_eq!(
+        buffer.read_var_u32().unwrap_err(),
+        Error::VarIntOverflow { offset: 1 }
+    );
+    // The same bytes fit in 64 bits
+    buffer.set_rpos(1);
+    assert_eq!(buffer.read_var_u64().unwrap(), 0x1_FFFF_FFFF);
+}
+
+#[test]
+fn test_overflow_u64() {
+    let mut data = [0xFF; 10];
+    data[9] = 0x02;
+    let mut reader = ByteReader::from(&data[..]);
+    assert_eq!(
+        reader.read_var_u64().unwrap_err(),
+        Error::VarIntOverflow { offset: 0 }
+    );
+    data[9] = 0x01;
+    let mut reader = ByteReader::from(&data[..]);
+    assert_eq!(reader.read_var_u64().unwrap(), u64::MAX);
+}
+
+#[test]
+fn test_overflow_i32() {
+    // 2^31 does not fit in an i32
+    let data = [0x80, 0x80, 0x80, 0x80, 0x08];
+    assert_eq!(
+        ByteReader::from(&data[..]).read_var_i32().unwrap_err(),
+        Error::VarIntOverflow { offset: 0 }
+    );
+    // -2^31 - 1 does not fit either
+    let data = [0xFF, 0xFF, 0xFF, 0xFF, 0x77];
+    assert_eq!(
+        ByteReader::from(&data[..]).read_var_i32().unwrap_err(),
+        Error::VarIntOverflow { offset: 0 }
+    );
+    let data = [0x80, 0x80, 0x80, 0x80, 0x78];
+    assert_eq!(
+        ByteReader::from(&data[..]).read_var_i32().unwrap(),
+        i32::MIN
+    );
+}
+
+#[test]
+fn test_overflow_zigzag() {
+    let data = [0xFF, 0xFF, 0xFF, 0xFF, 0x1F];
+    assert_eq!(
+        ByteReader::from(&data[..]).read_zigzag_i32().unwrap_err(),
+        Error::VarIntOverflow { offset: 0 }
+    );
+}
+
+#[test]
+fn test_truncated() {
+    let data = [0x80, 0x80];
+    let mut reader = ByteReader::from(&data[..]);
+    assert!(matches!(
+        reader.read_var_u64().unwrap_err(),
+        Error::UnexpectedEof { offset: 2, .. }
+    ));
+}
+
+#[test]
+fn test_flushes_bits() {
+    let mut buffer = ByteBuffer::new();
+    buffer.write_bit(true);
+    buffer.write_var_u32(300);
+    assert_eq!(buffer.as_bytes(), [0x80, 0xAC, 0x02]);
+    buffer.read_bit().unwrap();
+    assert_eq!(buffer.read_var_u32().unwrap(), 300);
+}
+
+#[test]
+fn test_generic_traits() {
+    fn encode<W: ByteWrite>(w: &mut W) -> bytebuffer::Result<()> {
+        w.write_var_u32(300)?;
+        w.write_var_i64(-1)?;
+        w.write_zigzag_i128(-2)
+    }
+
+    fn decode<R: ByteRead>(r: &mut R) -> bytebuffer::Result<(u32, i64, i128)> {
+        Ok((r.read_var_u32()?, r.read_var_i64()?, r.read_zigzag_i128()?))
+    }
+
+    let mut data = [0; 4];
+    let mut writer = ByteWriter::from_bytes(&mut data);
+    encode(&mut writer).unwrap();
+    assert_eq!(data, [0xAC, 0x02, 0x7F, 0x03]);
+
+    let mut buffer = ByteBuffer::new();
+    encode(&mut buffer).unwrap();
+    assert_eq!(buffer.as_bytes(), data);
+    assert_eq!(decode(&mut buffer).unwrap(), (300, -1, -2));
+    assert_eq!(
+        decode(&mut ByteReader::from(&data[..])).unwrap(),
+        (300, -1, -2)
+    );
+
+    let mut data = [0; 3];
+    let mut writer = ByteWriter::from_bytes(&mut data);
+    assert!(matches!(
+        encode(&mut writer).unwrap_err(),
+        Error::BufferFull { .. }
+    ));
+}
@@ -0,0 +1,248 @@
+use bytebuffer::{ByteBuffer, ByteReader, ByteWrite, ByteWriter, Endian, Error, LengthPrefix};
+use std::io::Write;
+
+#[test]
+fn test_api() {
+    let mut data = [0; 128];
+    let mut writer = ByteWriter::from_bytes(&mut data);
+    writer.write_bytes(&[0x1, 0xFF, 0x45]).unwrap();
+    writer.write_u8(1).unwrap();
+    writer.write_i8(1).unwrap();
+    writer.write_u16(1).unwrap();
+    writer.write_i16(1).unwrap();
+    writer.write_u32(1).unwrap();
+    writer.write_i32(1).unwrap();
+    writer.write_u64(1).unwrap();
+    writer.write_i64(1).unwrap();
+    writer.write_u128(1).unwrap();
+    writer.write_i128(1).unwrap();
+    #[cfg(feature = "half")]
+    {
+        writer.write_bf16(half::bf16::from_f32(12.5)).unwrap();
+        writer.write_f16(half::f16::from_f32(12.5)).unwrap();
+    }
+    writer.write_f32(0.1).unwrap();
+    writer.write_f64(0.1).unwrap();
+    writer.write_string("Hello").unwrap();
+    writer.write_bit(true).unwrap();
+    writer.write_bits(4, 3).unwrap();
+    writer.flush_bits();
+}
+
+#[test]
+fn test_same_output_as_buffer() {
+    let mut buffer = ByteBuffer::new();
+    buffer.write_u8(0xF0);
+    buffer.write_i16(-2);
+    buffer.write_u32(0x12345678);
+    buffer.write_u64(0xF0E1D2C3B4A59687);
+    buffer.write_u128(1);
+    buffer.write_f32(0.5);
+    buffer.write_f64(0.25);
+    buffer.write_string("hello");
+    buffer.write_bits(6, 3);
+    buffer.flush_bits();
+
+    let mut data = [0; 64];
+    let mut writer = ByteWriter::from_bytes(&mut data);
+    writer.write_u8(0xF0).unwrap();
+    writer.write_i16(-2).unwrap();
+    writer.write_u32(0x12345678).unwrap();
+    writer.write_u64(0xF0E1D2C3B4A59687).unwrap();
+    writer.write_u128(1).unwrap();
+    writer.write_f32(0.5).unwrap();
+    writer.write_f64(0.25).unwrap();
+    writer.write_string("hello").unwrap();
+    writer.write_bits(6, 3).unwrap();
+    writer.flush_bits();
+
+    let wpos = writer.get_wpos();
+    assert_eq!(wpos, buffer.len());
+    assert_eq!(&data[..wpos], buffer.as_bytes());
+}
+
+#[test]
+fn test_little_endian() {
+    let mut data = [0; 6];
+    let mut writer = ByteWriter::from_bytes(&mut data);
+    writer.set_endian(Endian::LittleEndian);
+    assert_eq!(writer.endian(), Endian::LittleEndian);
+    writer.write_u16(0x0102).unwrap();
+    writer.write_u32(0x03040506).unwrap();
+    assert_eq!(data, [0x02, 0x01, 0x06, 0x05, 0x04, 0x03]);
+}
+
+#[test]
+fn test_roundtrip_with_reader() {
+    let mut data = [0; 32];
+    let mut writer = ByteWriter::from_bytes(&mut data);
+    writer.write_i64(-42).unwrap();
+    writer.write_string("hello").unwrap();
+    writer.write_f32(1.5).unwrap();
+
+    let mut reader = ByteReader::from_bytes(&data);
+    assert_eq!(reader.read_i64().unwrap(), -42);
+    assert_eq!(reader.read_string().unwrap(), "hello");
+    assert_eq!(reader.read_f32().unwrap(), 1.5);
+}
+
+#[test]
+fn test_len() {
+    let mut data = [0; 4];
+    let writer = ByteWriter::from_bytes(&mut data);
+    assert_eq!(writer.len(), 4);
+    assert!(!writer.is_empty());
+
+    let writer = ByteWriter::from_bytes(&mut []);
+    assert!(writer.is_empty());
+}
+
+#[test]
+fn test_overflow_leaves_data_untouched() {
+    let mut data = [0; 3];
+    let mut writer = ByteWriter::from_bytes(&mut data);
+    writer.write_u8(1).unwrap();
+    let error = writer.write_u32(0xFFFFFFFF).err().unwrap();
+    assert!(matches!(error, Error::BufferFull { .. }));
+    assert_eq!(writer.get_wpos(), 1);
+    writer.write_u16(0x0203).unwrap();
+    assert_eq!(data, [1, 2, 3]);
+}
+
+#[test]
+fn test_string_overflow() {
+    let mut data = [0; 6];
+    let mut writer = ByteWriter::from_bytes(&mut data);
+    let error = writer.write_string("hello").err().unwrap();
+    assert!(matches!(error, Error::BufferFull { .. }));
+    assert_eq!(writer.get_wpos(), 0);
+    assert_eq!(data, [0; 6]);
+}
+
+#[test]
+fn test_write_bit() {
+    let mut data = [0xFF; 1];
+    let mut writer = ByteWriter::from_bytes(&mut data);
+    writer.write_bit(true).unwrap();
+    writer.write_bit(true).unwrap();
+    writer.write_bit(false).unwrap();
+    writer.write_bits(0, 5).unwrap();
+    assert_eq!(data[0], 128 + 64);
+}
+
+#[test]
+fn test_write_bits_overflow() {
+    let mut data = [0; 2];
+    let mut writer = ByteWriter::from_bytes(&mut data);
+    writer.write_bits(0x7, 3).unwrap();
+    let error = writer.write_bits(0xFFFF, 16).err().unwrap();
+    assert!(matches!(error, Error::BufferFull { .. }));
+    writer.write_bits(0x1FFF, 13).unwrap();
+    assert_eq!(data, [0xFF, 0xFF]);
+}
+
+#[test]
+fn test_write_bit_overflow() {
+    let mut data = [0; 1];
+    let mut writer = ByteWriter::from_bytes(&mut data);
+    writer.write_bits(0, 8).unwrap();
+    let error = writer.write_bit(true).err().unwrap();
+    assert!(matches!(error, Error::BufferFull { .. }));
+}
+
+#[test]
+fn test_cannot_write_more_than_64_bits() {
+    let mut data = [0; 16];
+    let mut writer = ByteWriter::from_bytes(&mut data);
+    let error = writer.write_bits(0, 65).err().unwrap();
+    assert!(matches!(error, Error::TooManyBits { .. }));
+}
+
+#[test]
+fn test_wpos() {
+    let mut data = [0; 4];
+    let mut writer = ByteWriter::from_bytes(&mut data);
+    writer.write_u32(0).unwrap();
+    writer.set_wpos(1);
+    writer.write_u8(0xFF).unwrap();
+    writer.set_wpos(10);
+    assert_eq!(writer.get_wpos(), 4);
+    writer.reset_cursors();
+    writer.write_u8(0x11).unwrap();
+    assert_eq!(writer.as_bytes(), [0x11, 0xFF, 0x0, 0x0]);
+}
+
+#[test]
+fn test_io_write() {
+    let mut data = [0; 3];
+    let mut writer = ByteWriter::from_bytes(&mut data);
+    assert_eq!(writer.write(&[1, 2]).unwrap(), 2);
+    assert_eq!(writer.write(&[3, 4]).unwrap(), 1);
+    assert_eq!(writer.write(&[5]).unwrap(), 0);
+    assert!(writer.write_all(&[6]).is_err());
+    writer.flush().unwrap();
+    assert_eq!(data, [1, 2, 3]);
+}
+
+#[test]
+fn test_byte_write_trait() {
+    fn encode<W: ByteWrite>(w: &mut W) -> bytebuffer::Result<()> {
+        w.write_u16(1)?;
+        w.write_string("hi")
+    }
+
+    let mut data = [0; 8];
+    let mut writer = ByteWriter::from(&mut data[..]);
+    encode(&mut writer).unwrap();
+    assert_eq!(data, [0, 1, 0, 0, 0, 2, b'h', b'i']);
+
+    let mut data = [0; 7];
+    let mut writer = ByteWriter::from(&mut data[..]);
+    assert!(encode(&mut writer).is_err());
+}
+
+#[test]
+fn test_prefixed_untouched_when_full() {
+    let mut data = [0xAA; 6];
+    let mut writer = ByteWriter::from_bytes(&mut data);
+    writer.write_u8(0x1).unwrap();
+    for prefix in [
+        LengthPrefix::U16,
+        LengthPrefix::VarInt,
+        LengthPrefix::NulTerminated,
+        LengthPrefix::Fixed(6),
+    ] {
+        assert!(matches!(
+            writer.write_prefixed_string("hello", prefix),
+            Err(Error::BufferFull { offset: 1, .. })
+        ));
+        assert_eq!(writer.get_wpos(), 1);
+    }
+    writer
+        .write_prefixed_bytes(&[0x2], LengthPrefix::U32)
+        .unwrap();
+    assert_eq!(data, [0x1, 0, 0, 0, 1, 0x2]);
+}
+
+#[test]
+fn test_debug() {
+    let mut data = [0; 3];
+    let mut writer = ByteWriter::from_bytes(&mut data);
+    writer.write_u8(0x1).unwrap();
+    assert_eq!(
+        format!("{:?}", writer),
+        "ByteWriter { written_data: [1], total_data: [1, 0, 0], wpos: 1, endian: BigEndian }"
+    );
+}
+
+#[test]
+#[cfg(feature = "half")]
+fn test_f16() {
+    let mut data = [0; 4];
+    let mut writer = ByteWriter::from_bytes(&mut data);
+    writer.write_f16(half::f16::from_f32(0.1)).unwrap();
+    writer.write_bf16(half::bf16::from_f32(0.1)).unwrap();
+    let mut reader = ByteReader::from_bytes(&data);
+    assert_eq!(reader.read_f16().unwrap(), half::f16::from_f32(0.1));
+    assert_eq!(reader.read_bf16().unwrap(), half::bf16::from_f32(0.1));
+}